@@ -16,7 +16,6 @@
 
 use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
 use flurry::HashMap;
-use rayon;
 use rayon::prelude::*;
 use std::sync::Arc;
 
@@ -34,7 +33,7 @@ fn task_insert_flurry_u64_u64_guard_every_it() -> HashMap<u64, u64> {
 
 fn insert_flurry_u64_u64_guard_every_it(c: &mut Criterion) {
     let mut group = c.benchmark_group("insert_flurry_u64_u64_guard_every_it");
-    group.throughput(Throughput::Elements(ITER as u64));
+    group.throughput(Throughput::Elements(ITER));
     let max = num_cpus::get();
 
     for threads in 1..=max {
@@ -75,7 +74,7 @@ fn task_insert_flurry_u64_u64_guard_once(threads: usize) -> HashMap<u64, u64> {
 
 fn insert_flurry_u64_u64_guard_once(c: &mut Criterion) {
     let mut group = c.benchmark_group("insert_flurry_u64_u64_guard_once");
-    group.throughput(Throughput::Elements(ITER as u64));
+    group.throughput(Throughput::Elements(ITER));
     let max = num_cpus::get();
 
     for threads in 1..=max {
@@ -104,7 +103,7 @@ fn task_get_flurry_u64_u64_guard_every_it(map: &HashMap<u64, u64>) {
 
 fn get_flurry_u64_u64_guard_every_it(c: &mut Criterion) {
     let mut group = c.benchmark_group("get_flurry_u64_u64_guard_every_it");
-    group.throughput(Throughput::Elements(ITER as u64));
+    group.throughput(Throughput::Elements(ITER));
     let max = num_cpus::get();
 
     for threads in 1..=max {
@@ -147,7 +146,7 @@ fn task_get_flurry_u64_u64_guard_once(threads: usize, map: Arc<HashMap<u64, u64>
 
 fn get_flurry_u64_u64_guard_once(c: &mut Criterion) {
     let mut group = c.benchmark_group("get_flurry_u64_u64_guard_once");
-    group.throughput(Throughput::Elements(ITER as u64));
+    group.throughput(Throughput::Elements(ITER));
     let max = num_cpus::get();
 
     for threads in 1..=max {
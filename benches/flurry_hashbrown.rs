@@ -8,7 +8,9 @@
  * For the associated license information, please refer to hashbrown.LICENSE.
  */
 
-use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use criterion::{
+    black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput,
+};
 use flurry::HashMap;
 
 const SIZE: usize = 1000;
@@ -70,6 +72,77 @@ bench_suite!(
     "insert_flurry_hashbrown",
 );
 
+macro_rules! bench_bulk_insert {
+    ($group:ident, $keydist:expr, $bench_id: expr) => {
+        $group.bench_function(BenchmarkId::new("loop", $bench_id), |b| {
+            b.iter(|| {
+                let map: HashMap<_, _> = HashMap::new();
+                let guard = map.guard();
+                ($keydist).take(SIZE).for_each(|i| {
+                    map.insert(i, i, &guard);
+                });
+                black_box(&map);
+            });
+        });
+        $group.bench_function(BenchmarkId::new("bulk", $bench_id), |b| {
+            b.iter(|| {
+                let map: HashMap<_, _> = HashMap::new();
+                let guard = map.guard();
+                map.bulk_insert(($keydist).take(SIZE).map(|i| (i, i)), &guard);
+                black_box(&map);
+            });
+        });
+    };
+}
+
+bench_suite!(
+    bench_bulk_insert,
+    bulk_insert_flurry_hashbrown,
+    "bulk_insert_flurry_hashbrown",
+);
+
+macro_rules! bench_clear {
+    ($group:ident, $keydist:expr, $bench_id: expr) => {
+        let fill = || {
+            let map: HashMap<_, _> = HashMap::with_capacity(SIZE as usize);
+            {
+                // see bench_insert_erase for a comment on the local scope
+                let guard = map.guard();
+                ($keydist).take(SIZE).for_each(|i| {
+                    map.insert(i, i, &guard);
+                });
+            }
+            map
+        };
+        $group.bench_function(BenchmarkId::new("clear", $bench_id), |b| {
+            b.iter_batched(
+                fill,
+                |map| {
+                    map.pin().clear();
+                    map
+                },
+                BatchSize::SmallInput,
+            );
+        });
+        $group.bench_function(BenchmarkId::new("clear_fast", $bench_id), |b| {
+            b.iter_batched(
+                fill,
+                |map| {
+                    map.pin().clear_fast();
+                    map
+                },
+                BatchSize::SmallInput,
+            );
+        });
+    };
+}
+
+bench_suite!(
+    bench_clear,
+    clear_flurry_hashbrown,
+    "clear_flurry_hashbrown",
+);
+
 macro_rules! bench_insert_erase {
     ($group:ident, $keydist:expr, $bench_id: expr) => {
         let base: HashMap<_, _> = HashMap::with_capacity(SIZE as usize);
@@ -193,6 +266,8 @@ bench_suite!(bench_iter, iter_flurry_hashbrown, "iter_flurry_hashbrown",);
 criterion_group!(
     benches,
     insert_flurry_hashbrown,
+    bulk_insert_flurry_hashbrown,
+    clear_flurry_hashbrown,
     insert_erase_flurry_hashbrown,
     get_flurry_hashbrown,
     get_absent_flurry_hashbrown,
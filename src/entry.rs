@@ -0,0 +1,301 @@
+use crate::reclaim::Guard;
+use crate::HashMap;
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{BuildHasher, Hash};
+
+/// A view into a single entry in a map, which may either be vacant or occupied.
+///
+/// This `enum` is constructed from the [`entry`](HashMap::entry) method on [`HashMap`].
+///
+/// Note that since the map may be concurrently modified by other threads, the variant only
+/// reflects the state of the map at the time the entry was looked up. All operations that insert
+/// into the map re-check the key's bin under the bin lock, so they never overwrite a value that
+/// was inserted concurrently.
+pub enum Entry<'g, K, V, S = crate::DefaultHashBuilder> {
+    /// An occupied entry.
+    Occupied(OccupiedEntry<'g, K, V, S>),
+    /// A vacant entry.
+    Vacant(VacantEntry<'g, K, V, S>),
+}
+
+/// A view into an occupied entry in a [`HashMap`].
+/// It is part of the [`Entry`] enum.
+pub struct OccupiedEntry<'g, K, V, S = crate::DefaultHashBuilder> {
+    key: K,
+    value: &'g V,
+    map: &'g HashMap<K, V, S>,
+    guard: &'g Guard<'g>,
+}
+
+/// A view into a vacant entry in a [`HashMap`].
+/// It is part of the [`Entry`] enum.
+pub struct VacantEntry<'g, K, V, S = crate::DefaultHashBuilder> {
+    key: K,
+    map: &'g HashMap<K, V, S>,
+    guard: &'g Guard<'g>,
+}
+
+impl<'g, K, V, S> Entry<'g, K, V, S> {
+    pub(crate) fn new(
+        key: K,
+        value: Option<&'g V>,
+        map: &'g HashMap<K, V, S>,
+        guard: &'g Guard<'g>,
+    ) -> Self {
+        match value {
+            Some(value) => Entry::Occupied(OccupiedEntry {
+                key,
+                value,
+                map,
+                guard,
+            }),
+            None => Entry::Vacant(VacantEntry { key, map, guard }),
+        }
+    }
+
+    /// Returns a reference to this entry's key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map: HashMap<&str, u32> = HashMap::new();
+    /// assert_eq!(map.pin().entry("poneyland").key(), &"poneyland");
+    /// ```
+    pub fn key(&self) -> &K {
+        match *self {
+            Entry::Occupied(ref entry) => entry.key(),
+            Entry::Vacant(ref entry) => entry.key(),
+        }
+    }
+}
+
+impl<'g, K, V, S> Entry<'g, K, V, S>
+where
+    K: Sync + Send + Clone + Hash + Ord,
+    V: Sync + Send,
+    S: BuildHasher,
+{
+    /// Ensures a value is in the entry by inserting `default` if empty, and returns a reference
+    /// to the value in the entry.
+    ///
+    /// If another thread inserts a value for the key after the entry was looked up, that value
+    /// is left in place and returned instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map: HashMap<&str, u32> = HashMap::new();
+    /// let mref = map.pin();
+    ///
+    /// assert_eq!(mref.entry("poneyland").or_insert(3), &3);
+    /// assert_eq!(mref.entry("poneyland").or_insert(10), &3);
+    /// ```
+    pub fn or_insert(self, default: V) -> &'g V {
+        match self {
+            Entry::Occupied(entry) => entry.into_ref(),
+            Entry::Vacant(entry) => entry.insert(default),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting the result of the `default` function if
+    /// empty, and returns a reference to the value in the entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map: HashMap<&str, Vec<u32>> = HashMap::new();
+    /// let mref = map.pin();
+    ///
+    /// assert!(mref.entry("poneyland").or_insert_with(Vec::new).is_empty());
+    /// ```
+    pub fn or_insert_with<F>(self, default: F) -> &'g V
+    where
+        F: FnOnce() -> V,
+    {
+        match self {
+            Entry::Occupied(entry) => entry.into_ref(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting the result of the `default` function if
+    /// empty, and returns a reference to the value in the entry.
+    ///
+    /// The `default` function is given a reference to the key that is about to be inserted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map: HashMap<&str, usize> = HashMap::new();
+    /// let mref = map.pin();
+    ///
+    /// assert_eq!(mref.entry("poneyland").or_insert_with_key(|k| k.len()), &9);
+    /// ```
+    pub fn or_insert_with_key<F>(self, default: F) -> &'g V
+    where
+        F: FnOnce(&K) -> V,
+    {
+        match self {
+            Entry::Occupied(entry) => entry.into_ref(),
+            Entry::Vacant(entry) => {
+                let value = default(entry.key());
+                entry.insert(value)
+            }
+        }
+    }
+
+    /// Provides in-place access to an occupied entry before any potential inserts into the map.
+    ///
+    /// Because other threads may be reading the current value, `f` does not get mutable access
+    /// to it. Instead, it is given the key and the current value, and returns the value to
+    /// replace it with. The replacement happens atomically under the bin lock, as with
+    /// [`HashMap::compute_if_present`].
+    ///
+    /// If the entry was removed by another thread in the meantime, `f` is not called and the
+    /// entry becomes vacant.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map: HashMap<&str, u32> = HashMap::new();
+    /// let mref = map.pin();
+    ///
+    /// mref.entry("poneyland").and_modify(|_, v| v + 1).or_insert(42);
+    /// assert_eq!(mref.get("poneyland"), Some(&42));
+    ///
+    /// mref.entry("poneyland").and_modify(|_, v| v + 1).or_insert(42);
+    /// assert_eq!(mref.get("poneyland"), Some(&43));
+    /// ```
+    pub fn and_modify<F>(self, f: F) -> Self
+    where
+        F: FnOnce(&K, &V) -> V,
+    {
+        match self {
+            Entry::Occupied(entry) => {
+                let OccupiedEntry {
+                    key, map, guard, ..
+                } = entry;
+                let value = map.compute_if_present(&key, |k, v| Some(f(k, v)), guard);
+                Entry::new(key, value, map, guard)
+            }
+            entry @ Entry::Vacant(_) => entry,
+        }
+    }
+}
+
+impl<'g, K, V, S> Entry<'g, K, V, S>
+where
+    K: Sync + Send + Clone + Hash + Ord,
+    V: Sync + Send + Default,
+    S: BuildHasher,
+{
+    /// Ensures a value is in the entry by inserting the default value if empty, and returns a
+    /// reference to the value in the entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map: HashMap<&str, Option<u32>> = HashMap::new();
+    /// assert_eq!(map.pin().entry("poneyland").or_default(), &None);
+    /// ```
+    pub fn or_default(self) -> &'g V {
+        self.or_insert_with(V::default)
+    }
+}
+
+impl<'g, K, V, S> OccupiedEntry<'g, K, V, S> {
+    /// Gets a reference to the key in the entry.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Gets a reference to the value in the entry.
+    ///
+    /// This is the value that was observed when the entry was looked up. It may since have been
+    /// replaced or removed by another thread.
+    pub fn get(&self) -> &'g V {
+        self.value
+    }
+
+    fn into_ref(self) -> &'g V {
+        self.value
+    }
+}
+
+impl<'g, K, V, S> VacantEntry<'g, K, V, S> {
+    /// Gets a reference to the key that would be used when inserting a value through the
+    /// `VacantEntry`.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Take ownership of the key.
+    pub fn into_key(self) -> K {
+        self.key
+    }
+}
+
+impl<'g, K, V, S> VacantEntry<'g, K, V, S>
+where
+    K: Sync + Send + Clone + Hash + Ord,
+    V: Sync + Send,
+    S: BuildHasher,
+{
+    /// Sets the value of the entry, and returns a reference to it.
+    ///
+    /// If another thread inserted a value for this key after the entry was looked up, the map is
+    /// left unchanged and a reference to that value is returned instead.
+    pub fn insert(self, value: V) -> &'g V {
+        match self.map.try_insert(self.key, value, self.guard) {
+            Ok(value) => value,
+            Err(e) => e.current,
+        }
+    }
+}
+
+impl<K, V, S> Debug for Entry<'_, K, V, S>
+where
+    K: Debug,
+    V: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match *self {
+            Entry::Occupied(ref o) => f.debug_tuple("Entry").field(o).finish(),
+            Entry::Vacant(ref v) => f.debug_tuple("Entry").field(v).finish(),
+        }
+    }
+}
+
+impl<K, V, S> Debug for OccupiedEntry<'_, K, V, S>
+where
+    K: Debug,
+    V: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OccupiedEntry")
+            .field("key", &self.key)
+            .field("value", self.value)
+            .finish()
+    }
+}
+
+impl<K, V, S> Debug for VacantEntry<'_, K, V, S>
+where
+    K: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("VacantEntry").field(&self.key).finish()
+    }
+}
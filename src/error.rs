@@ -0,0 +1,33 @@
+//! Error types.
+
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+
+/// The error type for [`HashMap::try_reserve`](crate::HashMap::try_reserve) and friends.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TryReserveError {
+    /// The requested capacity exceeds the maximum capacity of the map.
+    CapacityOverflow,
+    /// The allocator could not provide the memory for the map's bin table.
+    AllocError,
+}
+
+impl Display for TryReserveError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            TryReserveError::CapacityOverflow => {
+                write!(f, "requested capacity exceeds the map's maximum capacity")
+            }
+            TryReserveError::AllocError => {
+                write!(f, "memory allocation for the map's bin table failed")
+            }
+        }
+    }
+}
+
+impl Error for TryReserveError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        None
+    }
+}
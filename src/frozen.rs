@@ -0,0 +1,220 @@
+use crate::iter::FrozenIter;
+use std::borrow::Borrow;
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::ops::Index;
+
+/// An immutable copy of a [`HashMap`](crate::HashMap), constructed with
+/// [`HashMap::freeze`](crate::HashMap::freeze).
+///
+/// All entries are stored in a single contiguous allocation, grouped by bin, so lookups need
+/// neither a [`Guard`](crate::Guard) nor any bin locks. Since the frozen map is a copy, it does not
+/// observe any changes made to the source map after it was frozen.
+pub struct FrozenHashMap<K, V, S = crate::DefaultHashBuilder> {
+    /// The entries of the map, ordered by the bin they hash to.
+    entries: Box<[(K, V)]>,
+    /// `entries[bins[i]..bins[i + 1]]` are the entries in bin `i`.
+    ///
+    /// There is always one more offset than there are bins, and the number of bins is a power of
+    /// two.
+    bins: Box<[usize]>,
+    build_hasher: S,
+}
+
+impl<K, V, S> FrozenHashMap<K, V, S>
+where
+    K: Hash,
+    S: BuildHasher,
+{
+    pub(crate) fn from_entries(mut entries: Vec<(K, V)>, build_hasher: S) -> Self {
+        let n = entries.len().max(1).next_power_of_two();
+        let bini = |key: &K| {
+            let mut h = build_hasher.build_hasher();
+            key.hash(&mut h);
+            (h.finish() as usize) & (n - 1)
+        };
+
+        // the sort is stable, so keys that share a bin keep the order in which they were copied
+        entries.sort_by_cached_key(|(key, _)| bini(key));
+
+        let mut bins = vec![0; n + 1];
+        for (key, _) in &entries {
+            bins[bini(key) + 1] += 1;
+        }
+        for i in 0..n {
+            bins[i + 1] += bins[i];
+        }
+
+        Self {
+            entries: entries.into_boxed_slice(),
+            bins: bins.into_boxed_slice(),
+            build_hasher,
+        }
+    }
+}
+
+impl<K, V, S> FrozenHashMap<K, V, S> {
+    /// Returns the number of entries in the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// map.pin().insert(1, "a");
+    ///
+    /// let frozen = map.pin().freeze();
+    /// assert_eq!(frozen.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the map contains no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// An iterator visiting all key-value pairs in arbitrary order.
+    ///
+    /// The iterator element type is `(&'a K, &'a V)`.
+    pub fn iter(&self) -> FrozenIter<'_, K, V> {
+        FrozenIter {
+            inner: self.entries.iter(),
+        }
+    }
+
+    /// Returns a reference to the map's [`BuildHasher`].
+    pub fn hasher(&self) -> &S {
+        &self.build_hasher
+    }
+}
+
+impl<K, V, S> FrozenHashMap<K, V, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    fn bin<Q>(&self, key: &Q) -> &[(K, V)]
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash,
+    {
+        let mut h = self.build_hasher.build_hasher();
+        key.hash(&mut h);
+        let bini = (h.finish() as usize) & (self.bins.len() - 2);
+        &self.entries[self.bins[bini]..self.bins[bini + 1]]
+    }
+
+    /// Returns the key-value pair corresponding to `key`.
+    ///
+    /// The supplied key may be any borrowed form of the map's key type, but [`Hash`] and [`Eq`]
+    /// on the borrowed form must match those for the key type.
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.bin(key)
+            .iter()
+            .find(|(k, _)| k.borrow() == key)
+            .map(|(k, v)| (k, v))
+    }
+
+    /// Returns a reference to the value corresponding to the key.
+    ///
+    /// The supplied key may be any borrowed form of the map's key type, but [`Hash`] and [`Eq`]
+    /// on the borrowed form must match those for the key type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// let guard = map.guard();
+    /// map.insert(1, "a", &guard);
+    ///
+    /// let frozen = map.freeze(&guard);
+    /// map.insert(1, "b", &guard);
+    /// drop(guard);
+    ///
+    /// // the frozen map is a copy, and does not see the later insert
+    /// assert_eq!(frozen.get(&1), Some(&"a"));
+    /// assert_eq!(frozen.get(&2), None);
+    /// ```
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.get_key_value(key).map(|(_, v)| v)
+    }
+
+    /// Returns `true` if the map contains a value for the specified key.
+    ///
+    /// The supplied key may be any borrowed form of the map's key type, but [`Hash`] and [`Eq`]
+    /// on the borrowed form must match those for the key type.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.get(key).is_some()
+    }
+}
+
+impl<K, Q, V, S> Index<&'_ Q> for FrozenHashMap<K, V, S>
+where
+    K: Hash + Eq + Borrow<Q>,
+    Q: ?Sized + Hash + Eq,
+    S: BuildHasher,
+{
+    type Output = V;
+
+    /// Returns a reference to the value corresponding to `key`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key is not present in the map. See the [`Index`] implementation of
+    /// [`HashMapRef`](crate::HashMapRef) for details.
+    #[track_caller]
+    fn index(&self, key: &Q) -> &V {
+        self.get(key).expect("no entry found for key")
+    }
+}
+
+impl<'a, K, V, S> IntoIterator for &'a FrozenHashMap<K, V, S> {
+    type IntoIter = FrozenIter<'a, K, V>;
+    type Item = (&'a K, &'a V);
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<K, V, S> Clone for FrozenHashMap<K, V, S>
+where
+    K: Clone,
+    V: Clone,
+    S: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            entries: self.entries.clone(),
+            bins: self.bins.clone(),
+            build_hasher: self.build_hasher.clone(),
+        }
+    }
+}
+
+impl<K, V, S> Debug for FrozenHashMap<K, V, S>
+where
+    K: Debug,
+    V: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
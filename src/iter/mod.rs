@@ -1,21 +1,39 @@
 mod traverser;
 pub(crate) use traverser::NodeIter;
 
-use crate::reclaim::{Guard, Shared};
+#[cfg(feature = "rayon")]
+pub use crate::rayon_impls::{ParIter, ParKeys, ParValues};
+
+use crate::node::Node;
+use crate::raw::Table;
+use crate::reclaim::{Guard, Linked, Shared};
+use crate::{HashMap, HashSet};
+use std::borrow::Borrow;
+use std::hash::{BuildHasher, Hash};
+use std::iter::{Chain, FusedIterator};
+use std::marker::PhantomData;
+use std::ops::RangeBounds;
 use std::sync::atomic::Ordering;
 
 /// An iterator over a map's entries.
 ///
 /// See [`HashMap::iter`](crate::HashMap::iter) for details.
+///
+/// The lower bound of its [`size_hint`](Iterator::size_hint) is the length of the map when the
+/// iterator was created, minus the number of entries yielded since. It is only a hint: if the map
+/// is modified concurrently, the iterator may yield more or fewer entries than that, which is also
+/// why there is no upper bound and the iterator does not implement [`ExactSizeIterator`].
 #[derive(Debug)]
 pub struct Iter<'g, K, V> {
     pub(crate) node_iter: NodeIter<'g, K, V>,
     pub(crate) guard: &'g Guard<'g>,
+    pub(crate) remaining: usize,
 }
 
 impl<'g, K, V> Iter<'g, K, V> {
     pub(crate) fn next_internal(&mut self) -> Option<(&'g K, Shared<'g, V>)> {
         let node = self.node_iter.next()?;
+        self.remaining = self.remaining.saturating_sub(1);
         let value = node.value.load(Ordering::SeqCst, self.guard);
         Some((&node.key, value))
     }
@@ -28,42 +46,634 @@ impl<'g, K, V> Iterator for Iter<'g, K, V> {
         self.next_internal()
             .map(|(k, v)| unsafe { (k, &**v.deref()) })
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, None)
+    }
+}
+
+impl<K, V> FusedIterator for Iter<'_, K, V> {}
+
+impl<K, V> Clone for Iter<'_, K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            node_iter: self.node_iter.clone(),
+            guard: self.guard,
+            remaining: self.remaining,
+        }
+    }
 }
 
 /// An iterator over a map's keys.
 ///
 /// See [`HashMap::keys`](crate::HashMap::keys) for details.
+///
+/// See [`Iter`] for how its [`size_hint`](Iterator::size_hint) relates to the number of keys
+/// yielded.
 #[derive(Debug)]
 pub struct Keys<'g, K, V> {
     pub(crate) node_iter: NodeIter<'g, K, V>,
+    pub(crate) remaining: usize,
 }
 
 impl<'g, K, V> Iterator for Keys<'g, K, V> {
     type Item = &'g K;
     fn next(&mut self) -> Option<Self::Item> {
         let node = self.node_iter.next()?;
+        self.remaining = self.remaining.saturating_sub(1);
         Some(&node.key)
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, None)
+    }
+}
+
+impl<K, V> FusedIterator for Keys<'_, K, V> {}
+
+impl<K, V> Clone for Keys<'_, K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            node_iter: self.node_iter.clone(),
+            remaining: self.remaining,
+        }
+    }
 }
 
 /// An iterator over a map's values.
 ///
 /// See [`HashMap::values`](crate::HashMap::values) for details.
+///
+/// See [`Iter`] for how its [`size_hint`](Iterator::size_hint) relates to the number of values
+/// yielded.
 #[derive(Debug)]
 pub struct Values<'g, K, V> {
     pub(crate) node_iter: NodeIter<'g, K, V>,
     pub(crate) guard: &'g Guard<'g>,
+    pub(crate) remaining: usize,
 }
 
 impl<'g, K, V> Iterator for Values<'g, K, V> {
     type Item = &'g V;
     fn next(&mut self) -> Option<Self::Item> {
         let node = self.node_iter.next()?;
+        self.remaining = self.remaining.saturating_sub(1);
         let value = node.value.load(Ordering::SeqCst, self.guard);
         // safety: flurry does not drop or move until after guard drop
         let value = unsafe { value.deref() };
         Some(value)
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, None)
+    }
+}
+
+impl<K, V> FusedIterator for Values<'_, K, V> {}
+
+impl<K, V> Clone for Values<'_, K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            node_iter: self.node_iter.clone(),
+            guard: self.guard,
+            remaining: self.remaining,
+        }
+    }
+}
+
+/// An iterator over the entries of a map whose keys fall within a range.
+///
+/// See [`HashMap::range`](crate::HashMap::range) for details.
+#[derive(Debug)]
+pub struct Range<'g, K, V, Q: ?Sized, R> {
+    pub(crate) iter: Iter<'g, K, V>,
+    pub(crate) range: R,
+    pub(crate) _marker: PhantomData<fn(&Q)>,
+}
+
+impl<'g, K, V, Q, R> Iterator for Range<'g, K, V, Q, R>
+where
+    K: Borrow<Q>,
+    Q: ?Sized + Ord,
+    R: RangeBounds<Q>,
+{
+    type Item = (&'g K, &'g V);
+    fn next(&mut self) -> Option<Self::Item> {
+        let range = &self.range;
+        self.iter
+            .by_ref()
+            .find(|(k, _)| range.contains((*k).borrow()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+}
+
+impl<K, V, Q, R> FusedIterator for Range<'_, K, V, Q, R>
+where
+    K: Borrow<Q>,
+    Q: ?Sized + Ord,
+    R: RangeBounds<Q>,
+{
+}
+
+/// An owning iterator over a map's entries.
+///
+/// This `struct` is created by the `into_iter` method on [`HashMap`](crate::HashMap).
+#[derive(Debug)]
+pub struct IntoIter<K, V> {
+    pub(crate) entries: std::vec::IntoIter<(K, V)>,
+}
+
+impl<K, V> Iterator for IntoIter<K, V> {
+    type Item = (K, V);
+    fn next(&mut self) -> Option<Self::Item> {
+        self.entries.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.entries.size_hint()
+    }
+}
+
+impl<K, V> FusedIterator for IntoIter<K, V> {}
+
+/// An iterator over a map's entries and their hashes.
+///
+/// See [`HashMap::iter_hashed`](crate::HashMap::iter_hashed) for details.
+///
+/// See [`Iter`] for how its [`size_hint`](Iterator::size_hint) relates to the number of entries
+/// yielded.
+#[derive(Debug)]
+pub struct IterHashed<'g, K, V> {
+    pub(crate) node_iter: NodeIter<'g, K, V>,
+    pub(crate) guard: &'g Guard<'g>,
+    pub(crate) remaining: usize,
+}
+
+impl<'g, K, V> Iterator for IterHashed<'g, K, V> {
+    type Item = (u64, &'g K, &'g V);
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.node_iter.next()?;
+        self.remaining = self.remaining.saturating_sub(1);
+        let value = node.value.load(Ordering::SeqCst, self.guard);
+        // safety: flurry does not drop or move until after guard drop
+        let value = unsafe { value.deref() };
+        Some((node.hash, &node.key, value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, None)
+    }
+}
+
+impl<K, V> FusedIterator for IterHashed<'_, K, V> {}
+
+impl<K, V> Clone for IterHashed<'_, K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            node_iter: self.node_iter.clone(),
+            guard: self.guard,
+            remaining: self.remaining,
+        }
+    }
+}
+
+/// An iterator over the entries of a map that changed after a given version.
+///
+/// See [`HashMap::iter_since`](crate::HashMap::iter_since) for details.
+#[cfg(feature = "versioned")]
+#[derive(Debug)]
+pub struct IterSince<'g, K, V> {
+    pub(crate) node_iter: NodeIter<'g, K, V>,
+    pub(crate) guard: &'g Guard<'g>,
+    pub(crate) since: u64,
+}
+
+#[cfg(feature = "versioned")]
+impl<'g, K, V> Iterator for IterSince<'g, K, V> {
+    type Item = (&'g K, &'g V);
+    fn next(&mut self) -> Option<Self::Item> {
+        let since = self.since;
+        let guard = self.guard;
+        self.node_iter.by_ref().find_map(|node| {
+            // the version must be read before the value: a writer marks the node as pending
+            // before it stores a new value, and stamps it only once that value is in place
+            if node.version.load(Ordering::SeqCst) <= since {
+                return None;
+            }
+            let value = node.value.load(Ordering::SeqCst, guard);
+            // safety: flurry does not drop or move until after guard drop
+            let value = unsafe { value.deref() };
+            Some((&node.key, &**value))
+        })
+    }
+}
+
+#[cfg(feature = "versioned")]
+impl<K, V> FusedIterator for IterSince<'_, K, V> {}
+
+/// An iterator over clones of a map's entries.
+///
+/// See [`HashMap::iter_cloned`](crate::HashMap::iter_cloned) for details.
+#[derive(Debug, Clone)]
+pub struct IterCloned<'g, K, V> {
+    pub(crate) inner: Iter<'g, K, V>,
+}
+
+impl<K, V> Iterator for IterCloned<'_, K, V>
+where
+    K: Clone,
+    V: Clone,
+{
+    type Item = (K, V);
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(k, v)| (k.clone(), v.clone()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K: Clone, V: Clone> FusedIterator for IterCloned<'_, K, V> {}
+
+/// An iterator over clones of a map's keys.
+///
+/// See [`HashMap::keys_cloned`](crate::HashMap::keys_cloned) for details.
+#[derive(Debug, Clone)]
+pub struct KeysCloned<'g, K, V> {
+    pub(crate) inner: Keys<'g, K, V>,
+}
+
+impl<K, V> Iterator for KeysCloned<'_, K, V>
+where
+    K: Clone,
+{
+    type Item = K;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().cloned()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K: Clone, V> FusedIterator for KeysCloned<'_, K, V> {}
+
+/// An iterator over clones of a map's values.
+///
+/// See [`HashMap::values_cloned`](crate::HashMap::values_cloned) for details.
+#[derive(Debug, Clone)]
+pub struct ValuesCloned<'g, K, V> {
+    pub(crate) inner: Values<'g, K, V>,
+}
+
+impl<K, V> Iterator for ValuesCloned<'_, K, V>
+where
+    V: Clone,
+{
+    type Item = V;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().cloned()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V: Clone> FusedIterator for ValuesCloned<'_, K, V> {}
+
+/// An owning iterator over a map's keys.
+///
+/// This `struct` is created by the [`into_keys`](crate::HashMap::into_keys) method on
+/// [`HashMap`](crate::HashMap), and by the `into_iter` method on [`HashSet`](crate::HashSet).
+#[derive(Debug)]
+pub struct IntoKeys<K, V> {
+    pub(crate) inner: IntoIter<K, V>,
+}
+
+impl<K, V> Iterator for IntoKeys<K, V> {
+    type Item = K;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(k, _)| k)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> FusedIterator for IntoKeys<K, V> {}
+
+/// An owning iterator over a map's values.
+///
+/// This `struct` is created by the [`into_values`](crate::HashMap::into_values) method on
+/// [`HashMap`](crate::HashMap).
+#[derive(Debug)]
+pub struct IntoValues<K, V> {
+    pub(crate) inner: IntoIter<K, V>,
+}
+
+impl<K, V> Iterator for IntoValues<K, V> {
+    type Item = V;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(_, v)| v)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> FusedIterator for IntoValues<K, V> {}
+
+/// An iterator over a frozen map's entries.
+///
+/// See [`FrozenHashMap::iter`](crate::FrozenHashMap::iter) for details.
+#[derive(Debug, Clone)]
+pub struct FrozenIter<'a, K, V> {
+    pub(crate) inner: std::slice::Iter<'a, (K, V)>,
+}
+
+impl<'a, K, V> Iterator for FrozenIter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(k, v)| (k, v))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> ExactSizeIterator for FrozenIter<'_, K, V> {}
+
+impl<K, V> FusedIterator for FrozenIter<'_, K, V> {}
+
+/// An iterator over the bins of a map.
+///
+/// See [`HashMap::bins`](crate::HashMap::bins) for details.
+#[derive(Debug)]
+pub struct Bins<'g, K, V> {
+    pub(crate) table: Option<&'g Linked<Table<K, V>>>,
+    pub(crate) index: usize,
+    pub(crate) guard: &'g Guard<'g>,
+}
+
+impl<'g, K, V> Iterator for Bins<'g, K, V> {
+    type Item = Bin<'g, K, V>;
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let table = self.table?;
+            if self.index >= table.len() {
+                // make sure we stay exhausted, so that the iterator is fused
+                self.table = None;
+                return None;
+            }
+
+            let mut node_iter = NodeIter::bin(table, self.index, self.guard);
+            self.index += 1;
+            // only yield bins that hold at least one entry
+            if let Some(first) = node_iter.next() {
+                return Some(Bin {
+                    first: Some(first),
+                    node_iter,
+                    guard: self.guard,
+                });
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.table.map(|t| t.len() - self.index))
+    }
+}
+
+impl<K, V> FusedIterator for Bins<'_, K, V> {}
+
+impl<K, V> Clone for Bins<'_, K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            table: self.table,
+            index: self.index,
+            guard: self.guard,
+        }
+    }
+}
+
+/// An iterator over the entries of a single bin of a map.
+///
+/// See [`HashMap::bins`](crate::HashMap::bins) for details.
+#[derive(Debug)]
+pub struct Bin<'g, K, V> {
+    first: Option<&'g Node<K, V>>,
+    node_iter: NodeIter<'g, K, V>,
+    guard: &'g Guard<'g>,
+}
+
+impl<'g, K, V> Iterator for Bin<'g, K, V> {
+    type Item = (&'g K, &'g V);
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.first.take().or_else(|| self.node_iter.next())?;
+        let value = node.value.load(Ordering::SeqCst, self.guard);
+        // safety: flurry does not drop or move until after guard drop
+        Some((&node.key, unsafe { value.deref() }))
+    }
+}
+
+impl<K, V> FusedIterator for Bin<'_, K, V> {}
+
+impl<K, V> Clone for Bin<'_, K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            first: self.first,
+            node_iter: self.node_iter.clone(),
+            guard: self.guard,
+        }
+    }
+}
+
+/// A draining iterator over a map's entries.
+///
+/// Entries are removed as the iterator reaches them, so dropping it early leaves the remaining
+/// entries in the map. See [`HashMap::drain`](crate::HashMap::drain) for details.
+#[derive(Debug)]
+pub struct Drain<'g, K, V, S = crate::DefaultHashBuilder> {
+    pub(crate) node_iter: NodeIter<'g, K, V>,
+    pub(crate) map: &'g HashMap<K, V, S>,
+    pub(crate) guard: &'g Guard<'g>,
+}
+
+impl<'g, K, V, S> Iterator for Drain<'g, K, V, S>
+where
+    K: Sync + Send + Clone + Hash + Ord,
+    V: Sync + Send,
+    S: BuildHasher,
+{
+    type Item = (&'g K, &'g V);
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let node = self.node_iter.next()?;
+            // the entry may have been removed by another thread since we found it, in which case
+            // we just move on to the next one
+            if let Some(entry) = self.map.remove_entry(&node.key, self.guard) {
+                return Some(entry);
+            }
+        }
+    }
+}
+
+impl<K, V, S> FusedIterator for Drain<'_, K, V, S>
+where
+    K: Sync + Send + Clone + Hash + Ord,
+    V: Sync + Send,
+    S: BuildHasher,
+{
+}
+
+/// A draining iterator over a set's elements.
+///
+/// Elements are removed as the iterator reaches them, so dropping it early leaves the remaining
+/// elements in the set. See [`HashSet::drain`](crate::HashSet::drain) for details.
+#[derive(Debug)]
+pub struct SetDrain<'g, T, S = crate::DefaultHashBuilder> {
+    pub(crate) inner: Drain<'g, T, (), S>,
+}
+
+impl<'g, T, S> Iterator for SetDrain<'g, T, S>
+where
+    T: Sync + Send + Clone + Hash + Ord,
+    S: BuildHasher,
+{
+    type Item = &'g T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(k, _)| k)
+    }
+}
+
+impl<T, S> FusedIterator for SetDrain<'_, T, S>
+where
+    T: Sync + Send + Clone + Hash + Ord,
+    S: BuildHasher,
+{
+}
+
+/// A lazy iterator over the elements of one set that are not in another.
+///
+/// See [`HashSet::difference`](crate::HashSet::difference) for details.
+#[derive(Debug)]
+pub struct Difference<'g, T, S> {
+    pub(crate) iter: Keys<'g, T, ()>,
+    pub(crate) other: &'g HashSet<T, S>,
+    pub(crate) other_guard: &'g Guard<'g>,
+}
+
+impl<'g, T, S> Iterator for Difference<'g, T, S>
+where
+    T: Hash + Ord,
+    S: BuildHasher,
+{
+    type Item = &'g T;
+    fn next(&mut self) -> Option<Self::Item> {
+        let (other, other_guard) = (self.other, self.other_guard);
+        self.iter
+            .by_ref()
+            .find(|value| !other.contains(*value, other_guard))
+    }
+}
+
+impl<T, S> FusedIterator for Difference<'_, T, S>
+where
+    T: Hash + Ord,
+    S: BuildHasher,
+{
+}
+
+/// A lazy iterator over the elements that are in both of two sets.
+///
+/// See [`HashSet::intersection`](crate::HashSet::intersection) for details.
+#[derive(Debug)]
+pub struct Intersection<'g, T, S> {
+    pub(crate) iter: Keys<'g, T, ()>,
+    pub(crate) other: &'g HashSet<T, S>,
+    pub(crate) other_guard: &'g Guard<'g>,
+}
+
+impl<'g, T, S> Iterator for Intersection<'g, T, S>
+where
+    T: Hash + Ord,
+    S: BuildHasher,
+{
+    type Item = &'g T;
+    fn next(&mut self) -> Option<Self::Item> {
+        let (other, other_guard) = (self.other, self.other_guard);
+        self.iter
+            .by_ref()
+            .find(|value| other.contains(*value, other_guard))
+    }
+}
+
+impl<T, S> FusedIterator for Intersection<'_, T, S>
+where
+    T: Hash + Ord,
+    S: BuildHasher,
+{
+}
+
+/// A lazy iterator over the elements that are in exactly one of two sets.
+///
+/// See [`HashSet::symmetric_difference`](crate::HashSet::symmetric_difference) for details.
+#[derive(Debug)]
+pub struct SymmetricDifference<'g, T, S> {
+    pub(crate) iter: Chain<Difference<'g, T, S>, Difference<'g, T, S>>,
+}
+
+impl<'g, T, S> Iterator for SymmetricDifference<'g, T, S>
+where
+    T: Hash + Ord,
+    S: BuildHasher,
+{
+    type Item = &'g T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+}
+
+impl<T, S> FusedIterator for SymmetricDifference<'_, T, S>
+where
+    T: Hash + Ord,
+    S: BuildHasher,
+{
+}
+
+/// A lazy iterator over the elements that are in either of two sets.
+///
+/// See [`HashSet::union`](crate::HashSet::union) for details.
+#[derive(Debug)]
+pub struct Union<'g, T, S> {
+    pub(crate) iter: Chain<Keys<'g, T, ()>, Difference<'g, T, S>>,
+}
+
+impl<'g, T, S> Iterator for Union<'g, T, S>
+where
+    T: Hash + Ord,
+    S: BuildHasher,
+{
+    type Item = &'g T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+}
+
+impl<T, S> FusedIterator for Union<'_, T, S>
+where
+    T: Hash + Ord,
+    S: BuildHasher,
+{
 }
 
 #[cfg(test)]
@@ -71,6 +681,7 @@ mod tests {
     use crate::HashMap;
     use std::collections::HashSet;
     use std::iter::FromIterator;
+    use std::ops::Bound;
 
     #[test]
     fn iter() {
@@ -102,6 +713,102 @@ mod tests {
         );
     }
 
+    #[test]
+    fn iter_cloned() {
+        let map = HashMap::<usize, String>::new();
+
+        let guard = map.guard();
+        map.insert(1, String::from("a"), &guard);
+        map.insert(2, String::from("b"), &guard);
+
+        let iter = map.iter_cloned(&guard);
+        assert_eq!(iter.size_hint(), (2, None));
+        let entries: HashSet<(usize, String)> = iter.collect();
+        let keys: HashSet<usize> = map.keys_cloned(&guard).collect();
+        let values: HashSet<String> = map.values_cloned(&guard).collect();
+        drop(guard);
+
+        // the clones outlive the guard
+        assert_eq!(
+            entries,
+            HashSet::from_iter(vec![(1, String::from("a")), (2, String::from("b"))])
+        );
+        assert_eq!(keys, HashSet::from_iter(vec![1, 2]));
+        assert_eq!(
+            values,
+            HashSet::from_iter(vec![String::from("a"), String::from("b")])
+        );
+    }
+
+    #[test]
+    fn bins() {
+        let map = HashMap::<usize, usize>::new();
+        let guard = map.guard();
+        assert_eq!(map.bins(&guard).count(), 0);
+
+        for i in 0..200 {
+            map.insert(i, i, &guard);
+        }
+        let n = map.capacity(&guard);
+        let bins = map.bins(&guard);
+
+        // move every bin to a larger table before we look at any of them
+        map.reserve(1 << 12, &guard);
+        assert!(map.capacity(&guard) > n);
+
+        let mut seen = HashSet::new();
+        for bin in bins {
+            let entries: Vec<_> = bin.collect();
+            assert!(!entries.is_empty());
+            // the entries are still grouped by the bin they had in the old table
+            let bini = map.hash(entries[0].0) as usize & (n - 1);
+            for (k, v) in entries {
+                assert_eq!(k, v);
+                assert_eq!(map.hash(k) as usize & (n - 1), bini);
+                assert!(seen.insert(*k));
+            }
+        }
+        assert_eq!(seen, HashSet::from_iter(0..200));
+    }
+
+    #[test]
+    fn iter_with_len() {
+        let map = HashMap::<usize, usize>::new();
+
+        let guard = map.guard();
+        let (len, iter) = map.iter_with_len(&guard);
+        assert_eq!(len, 0);
+        assert_eq!(iter.count(), 0);
+
+        for i in 0..64 {
+            map.insert(i, i, &guard);
+        }
+        let (len, iter) = map.iter_with_len(&guard);
+        assert_eq!(len, 64);
+        assert_eq!(iter.size_hint(), (len, None));
+        assert_eq!(iter.map(|(k, _)| *k).collect::<HashSet<_>>().len(), len);
+    }
+
+    #[test]
+    fn iter_hashed() {
+        let map = HashMap::<usize, usize>::new();
+
+        let guard = map.guard();
+        for i in 0..64 {
+            map.insert(i, i * 2, &guard);
+        }
+
+        let iter = map.iter_hashed(&guard);
+        assert_eq!(iter.size_hint(), (64, None));
+        let mut seen = HashSet::new();
+        for (hash, k, v) in iter {
+            assert_eq!(hash, map.hash(k));
+            assert_eq!(*v, k * 2);
+            assert!(seen.insert(*k));
+        }
+        assert_eq!(seen, HashSet::from_iter(0..64));
+    }
+
     #[test]
     fn values() {
         let map = HashMap::<usize, usize>::new();
@@ -116,4 +823,156 @@ mod tests {
             HashSet::from_iter(vec![&42, &84])
         );
     }
+
+    #[test]
+    fn size_hint() {
+        let map = HashMap::<usize, usize>::new();
+
+        let guard = map.guard();
+        assert_eq!(map.iter(&guard).size_hint(), (0, None));
+        map.insert(1, 42, &guard);
+        map.insert(2, 84, &guard);
+
+        let mut iter = map.iter(&guard);
+        assert_eq!(iter.size_hint(), (2, None));
+        iter.next();
+        assert_eq!(iter.size_hint(), (1, None));
+        iter.next();
+        assert_eq!(iter.size_hint(), (0, None));
+        assert!(iter.next().is_none());
+
+        assert_eq!(map.keys(&guard).size_hint(), (2, None));
+        assert_eq!(map.values(&guard).size_hint(), (2, None));
+    }
+
+    #[test]
+    fn size_hint_concurrent_insert() {
+        let map = HashMap::<usize, usize>::new();
+
+        let guard = map.guard();
+        map.insert(1, 42, &guard);
+        let mut iter = map.keys(&guard);
+        map.insert(2, 84, &guard);
+
+        // the length is captured when the iterator is created, and never underflows
+        assert_eq!(iter.size_hint(), (1, None));
+        let n = iter.by_ref().count();
+        assert!(n >= 1);
+        assert_eq!(iter.size_hint(), (0, None));
+    }
+
+    #[test]
+    fn fused() {
+        let map = HashMap::<usize, usize>::new();
+
+        let guard = map.guard();
+        map.insert(1, 42, &guard);
+        let mut iter = map.iter(&guard);
+        let mut keys = map.keys(&guard);
+        assert!(iter.next().is_some());
+        assert!(iter.next().is_none());
+        assert!(keys.next().is_some());
+        assert!(keys.next().is_none());
+
+        // entries added after exhaustion, even ones that resize the table, are never yielded
+        for i in 2..64 {
+            map.insert(i, i, &guard);
+        }
+        assert!(iter.next().is_none());
+        assert!(keys.next().is_none());
+    }
+
+    #[test]
+    fn into_keys_and_values() {
+        let map = HashMap::<usize, String>::new();
+        assert_eq!(map.into_keys().count(), 0);
+
+        let map: HashMap<usize, String> = (0..8).map(|i| (i, i.to_string())).collect();
+        let mut keys: Vec<_> = map.into_keys().collect();
+        keys.sort_unstable();
+        assert_eq!(keys, (0..8).collect::<Vec<_>>());
+
+        let map: HashMap<usize, String> = (0..8).map(|i| (i, i.to_string())).collect();
+        let values = map.into_values();
+        assert_eq!(values.size_hint(), (8, Some(8)));
+        let mut values: Vec<_> = values.collect();
+        values.sort_unstable();
+        assert_eq!(values, (0..8).map(|i| i.to_string()).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn range() {
+        let map = HashMap::<usize, usize>::new();
+
+        let guard = map.guard();
+        assert!(map.range(.., &guard).next().is_none());
+        for i in 0..64 {
+            map.insert(i, i * 2, &guard);
+        }
+
+        let mut keys: Vec<_> = map.range(10..20, &guard).map(|(&k, _)| k).collect();
+        keys.sort_unstable();
+        assert_eq!(keys, (10..20).collect::<Vec<_>>());
+        assert_eq!(map.range(..=5, &guard).count(), 6);
+        assert_eq!(map.range(60.., &guard).count(), 4);
+        assert_eq!(map.range(.., &guard).count(), 64);
+        assert!(map.range(100.., &guard).next().is_none());
+
+        let map = HashMap::<String, usize>::new();
+        let guard = map.guard();
+        map.insert(String::from("b"), 1, &guard);
+        map.insert(String::from("x"), 2, &guard);
+        let bounds = (Bound::Included("a"), Bound::Excluded("c"));
+        let strs: Vec<_> = map.range::<str, _>(bounds, &guard).collect();
+        assert_eq!(strs, [(&String::from("b"), &1)]);
+    }
+
+    #[test]
+    fn drain() {
+        let map = HashMap::<usize, usize>::new();
+
+        let guard = map.guard();
+        assert!(map.drain(&guard).next().is_none());
+        for i in 0..64 {
+            map.insert(i, i * 2, &guard);
+        }
+
+        let mut drain = map.drain(&guard);
+        let (&k, &v) = drain.next().unwrap();
+        assert_eq!(v, k * 2);
+        assert!(!map.contains_key(&k, &guard));
+        assert_eq!(map.len(), 63);
+
+        let mut rest: Vec<_> = drain.map(|(&k, &v)| (k, v)).collect();
+        rest.push((k, v));
+        rest.sort_unstable();
+        assert_eq!(rest, (0..64).map(|i| (i, i * 2)).collect::<Vec<_>>());
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn clone() {
+        let map = HashMap::<usize, usize>::new();
+
+        let guard = map.guard();
+        for i in 0..64 {
+            map.insert(i, i, &guard);
+        }
+
+        let mut iter = map.iter(&guard);
+        let first: Vec<_> = iter.by_ref().take(32).collect();
+        let rest = iter.clone();
+        assert_eq!(rest.size_hint(), (32, None));
+        let rest: Vec<_> = rest.collect();
+        assert_eq!(rest, iter.collect::<Vec<_>>());
+        assert_eq!(rest.len(), 32);
+        for entry in &rest {
+            assert!(!first.contains(entry));
+        }
+
+        let keys = map.keys(&guard);
+        assert_eq!(keys.clone().count(), keys.count());
+        let values = map.values(&guard);
+        assert_eq!(values.clone().count(), values.count());
+    }
 }
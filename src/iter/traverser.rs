@@ -26,33 +26,115 @@ pub(crate) struct NodeIter<'g, K, V> {
     /// Initial table size
     base_size: usize,
 
+    /// Iterator over the part of a smaller table that a bin of a shrunk table was moved to
+    sub: Option<Box<NodeIter<'g, K, V>>>,
+
+    /// Only yield nodes whose hash masked by the first element equals the second element
+    filter: Option<(u64, u64)>,
+
     guard: &'g Guard<'g>,
 }
 
 impl<'g, K, V> NodeIter<'g, K, V> {
     pub(crate) fn new(table: Shared<'g, Table<K, V>>, guard: &'g Guard<'_>) -> Self {
-        let (table, len) = if table.is_null() {
-            (None, 0)
-        } else {
+        if !table.is_null() {
             // safety: flurry guarantees that a table read under a guard is never dropped or moved
             // until after that guard is dropped.
             let table = unsafe { table.deref() };
-            (Some(table), table.len())
-        };
+            return Self::range(table, 0, table.len(), guard);
+        }
 
         Self {
-            table,
+            table: None,
             stack: None,
             spare: None,
             prev: None,
-            base_size: len,
+            base_size: 0,
             base_index: 0,
             index: 0,
-            base_limit: len,
+            base_limit: 0,
+            sub: None,
+            filter: None,
+            guard,
+        }
+    }
+
+    /// Creates an iterator over the nodes in bins `lo..hi` of `table`, including those that a
+    /// resize has since moved to another table.
+    pub(crate) fn range(
+        table: &'g Linked<Table<K, V>>,
+        lo: usize,
+        hi: usize,
+        guard: &'g Guard<'_>,
+    ) -> Self {
+        Self {
+            table: Some(table),
+            stack: None,
+            spare: None,
+            prev: None,
+            base_size: table.len(),
+            base_index: lo,
+            index: lo,
+            base_limit: hi,
+            sub: None,
+            filter: None,
+            guard,
+        }
+    }
+
+    /// Creates an iterator over just the nodes that hash to bin `i` of `table`, including those
+    /// that a resize has since moved to another table.
+    pub(crate) fn bin(table: &'g Linked<Table<K, V>>, i: usize, guard: &'g Guard<'_>) -> Self {
+        let n = table.len();
+        Self {
+            table: Some(table),
+            stack: None,
+            spare: None,
+            prev: None,
+            base_size: n,
+            base_index: i,
+            index: i,
+            base_limit: i + 1,
+            sub: None,
+            filter: Some((n as u64 - 1, i as u64)),
             guard,
         }
     }
 
+    fn matches(&self, node: &Node<K, V>) -> bool {
+        self.filter
+            .map_or(true, |(mask, residue)| node.hash & mask == residue)
+    }
+
+    /// Whether bin `i` of a table with `n` bins may hold nodes that pass the filter.
+    fn bin_matches(&self, i: usize, n: usize) -> bool {
+        self.filter.map_or(true, |(mask, residue)| {
+            (i as u64 ^ residue) & mask & (n as u64 - 1) == 0
+        })
+    }
+
+    fn next_in_bin(&self, node: &'g Node<K, V>) -> Option<&'g Node<K, V>> {
+        let next = node.next.load(Ordering::SeqCst, self.guard);
+        if next.is_null() {
+            return None;
+        }
+
+        // we have to check if we are iterating over a regular bin or a
+        // TreeBin. the Java code gets away without this due to
+        // inheritance (everything is a node), but we have to explicitly
+        // check
+        // safety: flurry does not drop or move until after guard drop
+        match **unsafe { next.deref() } {
+            BinEntry::Node(ref node) => Some(node),
+            BinEntry::TreeNode(ref tree_node) => Some(&tree_node.node),
+            BinEntry::Moved => unreachable!("Nodes can only point to Nodes or TreeNodes"),
+            BinEntry::Tree(_) => unreachable!("Nodes can only point to Nodes or TreeNodes"),
+            BinEntry::Reservation(_) => {
+                unreachable!("Nodes can only point to Nodes or TreeNodes")
+            }
+        }
+    }
+
     fn push_state(&mut self, t: &'g Linked<Table<K, V>>, i: usize, n: usize) {
         let mut s = self.spare.take();
         if let Some(ref mut s) = s {
@@ -109,35 +191,52 @@ impl<'g, K, V> NodeIter<'g, K, V> {
     }
 }
 
+// manual impl, since #[derive(Clone)] would require K: Clone and V: Clone
+impl<K, V> Clone for NodeIter<'_, K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            table: self.table,
+            stack: self.stack.clone(),
+            // the spare stack frames are only there for re-use
+            spare: None,
+            prev: self.prev,
+            index: self.index,
+            base_index: self.base_index,
+            base_limit: self.base_limit,
+            base_size: self.base_size,
+            sub: self.sub.clone(),
+            filter: self.filter,
+            guard: self.guard,
+        }
+    }
+}
+
 impl<'g, K, V> Iterator for NodeIter<'g, K, V> {
     type Item = &'g Node<K, V>;
     fn next(&mut self) -> Option<Self::Item> {
-        let mut e = None;
-        if let Some(prev) = self.prev {
-            let next = prev.next.load(Ordering::SeqCst, self.guard);
-            if !next.is_null() {
-                // we have to check if we are iterating over a regular bin or a
-                // TreeBin. the Java code gets away without this due to
-                // inheritance (everything is a node), but we have to explicitly
-                // check
-                // safety: flurry does not drop or move until after guard drop
-                match **unsafe { next.deref() } {
-                    BinEntry::Node(ref node) => {
-                        e = Some(node);
-                    }
-                    BinEntry::TreeNode(ref tree_node) => {
-                        e = Some(&tree_node.node);
-                    }
-                    BinEntry::Moved => unreachable!("Nodes can only point to Nodes or TreeNodes"),
-                    BinEntry::Tree(_) => unreachable!("Nodes can only point to Nodes or TreeNodes"),
+        let mut e = self.prev.and_then(|prev| self.next_in_bin(prev));
+
+        loop {
+            if let Some(node) = e {
+                if self.matches(node) {
+                    self.prev = e;
+                    return e;
                 }
+                // the node belongs to a different bin of the table we were moved from
+                e = self.next_in_bin(node);
+                continue;
             }
-        }
 
-        loop {
-            if e.is_some() {
-                self.prev = e;
-                return e;
+            if let Some(ref mut sub) = self.sub {
+                let node = sub.next();
+                match node {
+                    Some(node) if !self.matches(node) => continue,
+                    Some(_) => {
+                        self.prev = None;
+                        return node;
+                    }
+                    None => self.sub = None,
+                }
             }
 
             // safety: flurry does not drop or move until after guard drop
@@ -145,6 +244,8 @@ impl<'g, K, V> Iterator for NodeIter<'g, K, V> {
                 || self.table.is_none()
                 || self.table.as_ref().unwrap().len() <= self.index
             {
+                // make sure we stay exhausted, so that the iterator is fused
+                self.base_index = self.base_limit;
                 self.prev = None;
                 return None;
             }
@@ -152,19 +253,53 @@ impl<'g, K, V> Iterator for NodeIter<'g, K, V> {
             let t = self.table.expect("is_none in if above");
             let i = self.index;
             let n = t.len();
-            let bin = t.bin(i, self.guard);
+            // skip bins that cannot contain entries we are interested in
+            let bin = if self.bin_matches(i, n) {
+                t.bin(i, self.guard)
+            } else {
+                Shared::null()
+            };
             if !bin.is_null() {
                 // safety: flurry does not drop or move until after guard drop
                 let bin = unsafe { bin.deref() };
                 match **bin {
                     BinEntry::Moved => {
-                        // recurse down into the target table
                         // safety: same argument as for following Moved in Table::find
-                        self.table = Some(unsafe { t.next_table(self.guard).deref() });
+                        let next_table = unsafe { t.next_table(self.guard).deref() };
                         self.prev = None;
-                        // make sure we can get back "up" to where we're at
-                        self.push_state(t, i, n);
-                        continue;
+                        let m = next_table.len();
+                        if m >= n {
+                            // recurse down into the target table
+                            self.table = Some(next_table);
+                            // make sure we can get back "up" to where we're at
+                            self.push_state(t, i, n);
+                            continue;
+                        }
+
+                        // the table is being shrunk, so the entries of this bin were moved to
+                        // a bin of the smaller table that also holds entries of other bins.
+                        // iterate over just that bin, and skip entries that are not from here.
+                        let index = i & (m - 1);
+                        self.sub = Some(Box::new(NodeIter {
+                            table: Some(next_table),
+                            stack: None,
+                            spare: None,
+                            prev: None,
+                            index,
+                            base_index: index,
+                            base_limit: index + 1,
+                            base_size: m,
+                            sub: None,
+                            // bin i matched our filter, so the filter with the longer mask
+                            // implies the other
+                            filter: match self.filter {
+                                Some((mask, residue)) if mask > n as u64 - 1 => {
+                                    Some((mask, residue))
+                                }
+                                _ => Some((n as u64 - 1, i as u64)),
+                            },
+                            guard: self.guard,
+                        }));
                     }
                     BinEntry::Node(ref node) => {
                         e = Some(node);
@@ -190,6 +325,9 @@ impl<'g, K, V> Iterator for NodeIter<'g, K, V> {
                     BinEntry::TreeNode(_) => unreachable!(
                         "The head of a bin cannot be a TreeNode directly without BinEntry::Tree"
                     ),
+                    BinEntry::Reservation(_) => {
+                        // the bin is empty while a value is being computed for it
+                    }
                 }
             }
 
@@ -214,12 +352,22 @@ struct TableStack<'g, K, V> {
     next: Option<Box<TableStack<'g, K, V>>>,
 }
 
+impl<K, V> Clone for TableStack<'_, K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            length: self.length,
+            index: self.index,
+            table: self.table,
+            next: self.next.clone(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::raw::Table;
     use crate::reclaim::Atomic;
-    use parking_lot::Mutex;
 
     #[test]
     fn iter_new() {
@@ -247,13 +395,12 @@ mod tests {
         let collector = seize::Collector::new();
         let mut bins = vec![Atomic::null(); 16];
         bins[8] = Atomic::from(Shared::boxed(
-            BinEntry::Node(Node {
-                hash: 0,
-                key: 0usize,
-                value: Atomic::from(Shared::boxed(0usize, &collector)),
-                next: Atomic::null(),
-                lock: Mutex::new(()),
-            }),
+            BinEntry::Node(Node::with_next(
+                0,
+                0usize,
+                Atomic::from(Shared::boxed(0usize, &collector)),
+                Atomic::null(),
+            )),
             &collector,
         ));
 
@@ -271,19 +418,51 @@ mod tests {
         t.drop_bins();
     }
 
+    #[test]
+    fn iter_range() {
+        let collector = seize::Collector::new();
+        let mut bins = vec![Atomic::null(); 16];
+        for i in [3, 8, 12] {
+            bins[i] = Atomic::from(Shared::boxed(
+                BinEntry::Node(Node::with_next(
+                    i as u64,
+                    i,
+                    Atomic::from(Shared::boxed(0usize, &collector)),
+                    Atomic::null(),
+                )),
+                &collector,
+            ));
+        }
+
+        let table = Shared::boxed(Table::from(bins, &collector), &collector);
+        let guard = collector.enter();
+        {
+            // safety: the table was just created, and is dropped below
+            let t = unsafe { table.deref() };
+            let keys: Vec<_> = NodeIter::range(t, 4, 12, &guard).map(|e| e.key).collect();
+            assert_eq!(keys, [8]);
+            let keys: Vec<_> = NodeIter::range(t, 0, 16, &guard).map(|e| e.key).collect();
+            assert_eq!(keys, [3, 8, 12]);
+            assert_eq!(NodeIter::range(t, 4, 4, &guard).count(), 0);
+        }
+
+        // safety: nothing holds on to references into the table any more
+        let mut t = unsafe { table.into_box() };
+        t.drop_bins();
+    }
+
     #[test]
     fn iter_fw() {
         // construct the forwarded-to table
         let collector = seize::Collector::new();
         let mut deep_bins = vec![Atomic::null(); 16];
         deep_bins[8] = Atomic::from(Shared::boxed(
-            BinEntry::Node(Node {
-                hash: 0,
-                key: 0usize,
-                value: Atomic::from(Shared::boxed(0usize, &collector)),
-                next: Atomic::null(),
-                lock: Mutex::new(()),
-            }),
+            BinEntry::Node(Node::with_next(
+                0,
+                0usize,
+                Atomic::from(Shared::boxed(0usize, &collector)),
+                Atomic::null(),
+            )),
             &collector,
         ));
 
@@ -291,7 +470,7 @@ mod tests {
         let deep_table = Shared::boxed(Table::from(deep_bins, &collector), &collector);
 
         // construct the forwarded-from table
-        let mut bins = vec![Shared::null(); 16];
+        let mut bins = [Shared::null(); 16];
         let table = Table::<usize, usize>::new(bins.len(), &collector);
         for bin in &mut bins[8..] {
             // this also sets table.next_table to deep_table
@@ -299,8 +478,8 @@ mod tests {
         }
         // this cannot use Table::from(bins), since we need the table to get
         // the Moved and set its next_table
-        for i in 0..bins.len() {
-            table.store_bin(i, bins[i]);
+        for (i, &bin) in bins.iter().enumerate() {
+            table.store_bin(i, bin);
         }
         let table = Shared::boxed(table, &collector);
         {
@@ -316,4 +495,50 @@ mod tests {
         // no one besides this test case uses deep_table
         unsafe { deep_table.into_box() }.drop_bins();
     }
+
+    #[test]
+    fn iter_shrunk() {
+        // construct the smaller table that both old bins were moved to
+        let collector = seize::Collector::new();
+        let mut deep_bins = vec![Atomic::null(); 8];
+        let tail = Shared::boxed(
+            BinEntry::Node(Node::with_next(
+                9,
+                9usize,
+                Atomic::from(Shared::boxed(9usize, &collector)),
+                Atomic::null(),
+            )),
+            &collector,
+        );
+        deep_bins[1] = Atomic::from(Shared::boxed(
+            BinEntry::Node(Node::with_next(
+                1,
+                1usize,
+                Atomic::from(Shared::boxed(1usize, &collector)),
+                Atomic::from(tail),
+            )),
+            &collector,
+        ));
+
+        let guard = collector.enter();
+        let deep_table = Shared::boxed(Table::from(deep_bins, &collector), &collector);
+
+        // construct the shrunk table
+        let table = Table::<usize, usize>::new(16, &collector);
+        for i in 0..16 {
+            table.store_bin(i, table.get_moved(deep_table, &guard));
+        }
+        let table = Shared::boxed(table, &collector);
+        {
+            let mut keys: Vec<_> = NodeIter::new(table, &guard).map(|e| e.key).collect();
+            keys.sort_unstable();
+            assert_eq!(keys, vec![1, 9]);
+        }
+
+        // safety: nothing holds on to references into the table any more
+        let mut t = unsafe { table.into_box() };
+        t.drop_bins();
+        // no one besides this test case uses deep_table
+        unsafe { deep_table.into_box() }.drop_bins();
+    }
 }
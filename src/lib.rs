@@ -25,6 +25,20 @@
 //! may accumulate much garbage which will take up valuable free memory on your system. Use your
 //! best judgement in deciding whether or not to re-use a `Guard`.
 //!
+//! Since destruction is deferred, the destructor of a value that was removed or replaced runs
+//! some time later, on whichever thread happens to reclaim its batch. Other threads may still be
+//! reading the value under their guards, so there is no way to make pending destructors run while
+//! the map is in use. If destructors have side effects that must happen by some point, such as
+//! flushing to disk at shutdown, drop the map: by the time the drop returns, the destructors of all
+//! of its values, including those that were removed earlier, have run. If the map shares its
+//! collector with others, remains of removed values are only guaranteed to be destroyed once the
+//! collector itself is dropped.
+//!
+//! Each map normally has its own [`Collector`], and only accepts guards from that collector. If you
+//! routinely operate on several maps together, you can instead build them with a shared collector
+//! using [`HashMap::with_shared_collector`], and then use a single guard from
+//! [`Collector::enter`] for all of them.
+//!
 //! # Consistency
 //!
 //! Retrieval operations (including [`get`](HashMap::get)) generally do not block, so may
@@ -57,9 +71,22 @@
 //! by the map to more efficiently store bins that contain a large number of elements with
 //! colliding hashes using the comparison order on their keys.
 //!
-/*
-//! TODO: dynamic load factor
-//! */
+//! The load factor can be changed with [`HashMapBuilder::load_factor`], to make a map denser at
+//! the cost of more collisions, or sparser at the cost of more memory. Maps only grow on their
+//! own, but can be shrunk explicitly with [`HashMap::shrink_to_fit`]. To avoid resizing back and
+//! forth, this only happens once the map is well below its load factor, as configured with
+//! [`HashMapBuilder::shrink_factor`].
+//!
+//! # Thread safety
+//!
+//! Like a [`std::collections::HashMap`], a [`HashMap`] is [`Send`] and [`Sync`] when its keys and
+//! values are (and its hasher is). Methods that put keys or values into the map additionally
+//! require `K: Send + Sync` and `V: Send + Sync`, since once an entry is in the map, any thread
+//! may read it, and whichever thread removes it or drops the map may end up dropping it.
+//!
+//! A [`Guard`] marks the _current_ thread as active, so guards, and [`HashMapRef`]s and iterators
+//! that hold on to one, can be neither sent to nor shared with other threads.
+//!
 //! # Hash Sets
 //!
 //! Flurry also supports concurrent hash sets, which may be created through [`HashSet`]. Hash sets
@@ -90,13 +117,10 @@
 //! However, some other types of nodes exist: `BinEntry::TreeNode`s are arranged in balanced trees
 //! instead of linear lists. Bins of type `BinEntry::Tree` hold the roots of sets of `BinEntry::TreeNode`s.
 //! Some nodes are of type `BinEntry::Moved`; these "forwarding nodes" are placed at the
-//! heads of bins during resizing. The Java version also has other special node types, but these
-//! have not yet been implemented in this port. These special nodes are all either uncommon or
-//! transient.
+//! heads of bins during resizing. `BinEntry::Reservation`s are placed in empty bins while a value
+//! is being computed for them by [`compute_if_absent`](HashMap::compute_if_absent). These special
+//! nodes are all either uncommon or transient.
 //!
-/*
-//! TODO: TreeNodes, ReservationNodes
-*/
 //! The table is lazily initialized to a power-of-two size upon the first insertion.  Each bin in
 //! the table normally contains a list of nodes (most often, the list has only zero or one
 //! `BinEntry`). Table accesses require atomic reads, writes, and CASes.
@@ -231,7 +255,13 @@
 //! `Guard` arguments to many methods or wrapping the return values, but provides much more efficient
 //! operation than if every individual value had to be atomically reference-counted.
 //!
+//! The reclamation scheme is also the main reason that flurry requires `std`: [`seize`] tracks
+//! the threads that participate in reclamation using thread-locals, and the bin locks are
+//! [`parking_lot`] mutexes, which park blocked threads through the operating system. Supporting
+//! `no_std` environments would require an abstraction over both that a `no_std` user can supply.
+//!
 //!  [`seize`]: https://docs.rs/seize
+//!  [`parking_lot`]: https://docs.rs/parking_lot
 #![deny(
     missing_docs,
     missing_debug_implementations,
@@ -241,10 +271,14 @@
 #![warn(rust_2018_idioms)]
 #![allow(clippy::cognitive_complexity)]
 
+mod entry;
+mod error;
+mod frozen;
 mod map;
 mod map_ref;
 mod node;
 mod raw;
+mod raw_entry;
 mod reclaim;
 mod set;
 mod set_ref;
@@ -261,12 +295,60 @@ pub mod iter;
 use std::hash::BuildHasherDefault;
 
 use ahash::AHasher;
-pub use map::{HashMap, TryInsertError};
-pub use map_ref::HashMapRef;
-pub use set::HashSet;
+pub use entry::{Entry, OccupiedEntry, VacantEntry};
+pub use error::TryReserveError;
+pub use frozen::FrozenHashMap;
+pub use map::{HashMap, HashMapBuilder, MapStats, TryInsertError};
+pub use map_ref::{HashMapRef, OwnedHashMapRef};
+pub use raw_entry::{
+    RawEntryBuilder, RawEntryBuilderMut, RawEntryMut, RawOccupiedEntryMut, RawVacantEntryMut,
+};
+pub use set::{HashSet, HashSetBuilder};
 pub use set_ref::HashSetRef;
 
 /// Default hasher for [`HashMap`].
+///
+/// This is [`ahash`](https://docs.rs/ahash), which is considerably faster than the SipHash used by
+/// [`std::collections::HashMap`]. Its keys are fixed rather than chosen randomly for each map,
+/// which makes hash values predictable. If the map may hold keys chosen by an adversary, consider
+/// using the DoS-resistant [`RandomState`](std::collections::hash_map::RandomState) through
+/// [`HashMap::with_hasher`] instead:
+///
+/// ```
+/// use flurry::HashMap;
+/// use std::collections::hash_map::RandomState;
+///
+/// let map: HashMap<&str, i32, _> = HashMap::with_hasher(RandomState::new());
+/// map.pin().insert("a", 1);
+/// ```
 pub type DefaultHashBuilder = BuildHasherDefault<AHasher>;
 
-pub use seize::Guard;
+/// A pinned reference to a [`HashMap`], as returned by [`HashMap::pin`].
+///
+/// This is just a shorter name for [`HashMapRef`], for use in the signatures of functions that
+/// take or return pinned maps.
+///
+/// # Examples
+///
+/// ```
+/// use flurry::{HashMap, PinnedMap};
+///
+/// fn count_word(words: &PinnedMap<'_, String, usize>, word: &str) {
+///     words.compute(word.to_string(), |_, n| Some(n.map_or(1, |n| n + 1)));
+/// }
+///
+/// let words = HashMap::new();
+/// let pinned = words.pin();
+/// count_word(&pinned, "a");
+/// count_word(&pinned, "a");
+/// assert_eq!(pinned.get("a"), Some(&2));
+/// ```
+pub type PinnedMap<'map, K, V, S = DefaultHashBuilder> = HashMapRef<'map, K, V, S>;
+
+/// A pinned reference to a [`HashSet`], as returned by [`HashSet::pin`].
+///
+/// This is just a shorter name for [`HashSetRef`], for use in the signatures of functions that
+/// take or return pinned sets.
+pub type PinnedSet<'set, T, S = DefaultHashBuilder> = HashSetRef<'set, T, S>;
+
+pub use seize::{Collector, Guard};
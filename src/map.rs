@@ -1,15 +1,26 @@
 use seize::Linked;
 
+use crate::entry::Entry;
+use crate::error::TryReserveError;
+use crate::frozen::FrozenHashMap;
 use crate::iter::*;
 use crate::node::*;
 use crate::raw::*;
+use crate::raw_entry::{RawEntryBuilder, RawEntryBuilderMut};
 use crate::reclaim::{Atomic, Collector, Guard, RetireShared, Shared};
+use parking_lot::RwLock;
 use std::borrow::Borrow;
+use std::convert::Infallible;
 use std::error::Error;
 use std::fmt::{self, Debug, Display, Formatter};
 use std::hash::{BuildHasher, Hash, Hasher};
 use std::iter::FromIterator;
-use std::sync::atomic::{AtomicIsize, Ordering};
+use std::marker::PhantomData;
+use std::ops::RangeBounds;
+#[cfg(feature = "versioned")]
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::{AtomicIsize, AtomicUsize, Ordering};
+use std::sync::Arc;
 
 const ISIZE_BITS: usize = core::mem::size_of::<isize>() * 8;
 
@@ -24,6 +35,15 @@ const MAXIMUM_CAPACITY: usize = 1 << 30; // TODO: use ISIZE_BITS
 /// (i.e., at least 1) and at most `MAXIMUM_CAPACITY`.
 const DEFAULT_CAPACITY: usize = 16;
 
+/// The default load factor. Tables are resized once they hold more elements than this fraction
+/// of their capacity.
+const DEFAULT_LOAD_FACTOR: f64 = 0.75;
+
+/// The default shrink factor. Tables are only shrunk once they hold fewer elements than this
+/// fraction of their capacity. Unless it is set explicitly, the shrink factor of a map is a third
+/// of its load factor.
+const DEFAULT_SHRINK_FACTOR: f64 = DEFAULT_LOAD_FACTOR / 3.0;
+
 /// The bin count threshold for using a tree rather than list for a bin. Bins are
 /// converted to trees when adding an element to a bin with at least this many
 /// nodes. The value must be greater than 2, and should be at least 8 to mesh
@@ -37,8 +57,8 @@ const TREEIFY_THRESHOLD: usize = 8;
 const UNTREEIFY_THRESHOLD: usize = 6;
 
 /// The smallest table capacity for which bins may be treeified. (Otherwise the
-/// table is resized if too many nodes in a bin.) The value should be at least 4
-/// * TREEIFY_THRESHOLD to avoid conflicts between resizing and treeification
+/// table is resized if too many nodes in a bin.) The value should be at least
+/// 4 * TREEIFY_THRESHOLD to avoid conflicts between resizing and treeification
 /// thresholds.
 const MIN_TREEIFY_CAPACITY: usize = 64;
 
@@ -66,13 +86,17 @@ static NCPU_INITIALIZER: std::sync::Once = std::sync::Once::new();
 #[cfg(not(miri))]
 static NCPU: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
 
-macro_rules! load_factor {
-    ($n: expr) => {
-        // ¾ n = n - n/4 = n - (n >> 2)
-        $n - ($n >> 2)
-    };
+thread_local! {
+    /// The number of transfer strides this thread has claimed, across all maps.
+    ///
+    /// [`HashMap::insert_cooperative`] compares this before and after an insert to tell whether
+    /// the insert was roped into helping with a resize.
+    static TRANSFER_STRIDES: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
 }
 
+/// A callback registered with [`HashMap::on_resize`].
+type ResizeObserver = Arc<dyn Fn(usize, usize) + Send + Sync>;
+
 /// A concurrent hash table.
 ///
 /// Flurry uses [`Guards`] to control the lifetime of the resources that get stored and
@@ -80,12 +104,72 @@ macro_rules! load_factor {
 /// [`HashMap::guard`] functions. For more information, see the [notes in the crate-level
 /// documentation].
 ///
+/// # Keying on part of a key
+///
+/// The map hashes and compares keys using their [`Hash`] and [`Ord`] implementations, and looks
+/// them up by any type the key can be [`Borrow`]ed as. To key large records on a cheap embedded
+/// field, implement those traits for the record in terms of that field, so that lookups can be
+/// made by the field alone. The same implementations let a [`HashSet`](crate::HashSet) of records
+/// act as a map from the field to the full record:
+///
+/// ```
+/// use flurry::HashSet;
+/// use std::borrow::Borrow;
+/// use std::cmp::Ordering;
+/// use std::hash::{Hash, Hasher};
+///
+/// #[derive(Clone)]
+/// struct Record {
+///     id: u64,
+///     name: String,
+/// }
+///
+/// impl Hash for Record {
+///     fn hash<H: Hasher>(&self, state: &mut H) {
+///         // must hash exactly like the `u64` we borrow as
+///         self.id.hash(state);
+///     }
+/// }
+///
+/// impl PartialEq for Record {
+///     fn eq(&self, other: &Self) -> bool {
+///         self.id == other.id
+///     }
+/// }
+///
+/// impl Eq for Record {}
+///
+/// impl PartialOrd for Record {
+///     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+///         Some(self.cmp(other))
+///     }
+/// }
+///
+/// impl Ord for Record {
+///     fn cmp(&self, other: &Self) -> Ordering {
+///         self.id.cmp(&other.id)
+///     }
+/// }
+///
+/// impl Borrow<u64> for Record {
+///     fn borrow(&self) -> &u64 {
+///         &self.id
+///     }
+/// }
+///
+/// let records = HashSet::new();
+/// let guard = records.guard();
+/// records.insert(Record { id: 7, name: String::from("seven") }, &guard);
+///
+/// assert_eq!(records.get(&7, &guard).map(|r| r.name.as_str()), Some("seven"));
+/// ```
+///
 /// [notes in the crate-level documentation]: index.html#a-note-on-guard-and-memory-use
 /// [`Guards`]: index.html#a-note-on-guard-and-memory-use
 pub struct HashMap<K, V, S = crate::DefaultHashBuilder> {
     /// The array of bins. Lazily initialized upon first insertion.
     /// Size is always a power of two. Accessed directly by iterators.
-    table: Atomic<Table<K, V>>,
+    pub(crate) table: Atomic<Table<K, V>>,
 
     /// The next table to use; non-null only while resizing.
     next_table: Atomic<Table<K, V>>,
@@ -95,6 +179,12 @@ pub struct HashMap<K, V, S = crate::DefaultHashBuilder> {
 
     count: AtomicIsize,
 
+    /// The number of times the table has been replaced by a larger or smaller one.
+    resize_count: AtomicUsize,
+
+    /// Callbacks to invoke with the old and new capacity whenever the table has been replaced.
+    resize_observers: RwLock<Vec<ResizeObserver>>,
+
     /// Table initialization and resizing control.  When negative, the
     /// table is being initialized or resized: -1 for initialization,
     /// else -(1 + the number of active resizing threads).  Otherwise,
@@ -103,6 +193,21 @@ pub struct HashMap<K, V, S = crate::DefaultHashBuilder> {
     /// next element count value upon which to resize the table.
     size_ctl: AtomicIsize,
 
+    /// The fraction of a table's capacity that may be filled before the table is resized.
+    load_factor: f64,
+
+    /// The fraction of a table's capacity below which the table may be shrunk.
+    ///
+    /// This is always smaller than `load_factor`, so that a table that was just grown (or shrunk)
+    /// is not immediately shrunk (or grown) again.
+    shrink_factor: f64,
+
+    /// The latest version that was handed out to a node whose value was set.
+    ///
+    /// See [`HashMap::iter_since`] for how versions are assigned.
+    #[cfg(feature = "versioned")]
+    version: AtomicU64,
+
     /// Collector that all `Guard` references used for operations on this map must be tied to. It
     /// is important that they all assocate with the _same_ `Collector`, otherwise you end up with
     /// unsoundness as described in https://github.com/jonhoo/flurry/issues/46. Specifically, a
@@ -129,9 +234,22 @@ pub struct HashMap<K, V, S = crate::DefaultHashBuilder> {
     /// We avoid that by checking that every external guard that is passed in is associated with
     /// the `Collector` that was specified when the map was created (which may be the global
     /// collector).
-    collector: Collector,
+    ///
+    /// The collector is reference-counted so that several maps can share one, see
+    /// [`HashMap::with_shared_collector`].
+    pub(crate) collector: Arc<Collector>,
 
     build_hasher: S,
+
+    /// Makes the map `Send` and `Sync` only if the keys and values are, like a
+    /// `std::collections::HashMap`.
+    ///
+    /// All other fields are thread-safe no matter what `K` and `V` are, since the entries are
+    /// only reachable through atomic pointers. That alone would be sound, since every method that
+    /// puts keys or values into the map requires them to be `Send + Sync`, so a map of, say,
+    /// `Rc`s is always empty. But a map that is `Send` only when its entries are is what users
+    /// expect, and it keeps the soundness of the map from hinging on those bounds alone.
+    _marker: PhantomData<(K, V)>,
 }
 
 #[derive(Eq, PartialEq, Debug)]
@@ -168,7 +286,83 @@ impl<'a, T> PutResult<'a, T> {
     }
 }
 
+/// A bin that was reserved with a `BinEntry::Reservation` by `HashMap::reserve_bin`.
+///
+/// When dropped, the reservation in `table[bini]` is replaced with `fill` (which is null unless a
+/// value was successfully computed), the reservation's lock is released, and the reservation
+/// itself is retired. This happens even if the computation panics, so that a reservation can
+/// never be left behind in the table.
+struct ReservedBin<'g, K, V> {
+    table: &'g Table<K, V>,
+    bini: usize,
+    reservation: Shared<'g, BinEntry<K, V>>,
+    lock: Option<BinLockGuard<'g>>,
+    fill: Shared<'g, BinEntry<K, V>>,
+    guard: &'g Guard<'g>,
+}
+
+impl<K, V> Drop for ReservedBin<'_, K, V> {
+    fn drop(&mut self) {
+        // fill the bin before we release the lock, so that the writers waiting for it find the
+        // bin changed
+        self.table.store_bin(self.bini, self.fill);
+        drop(self.lock.take());
+
+        // safety: the reservation is no longer reachable through the table, and the bin was only
+        // reachable through the table. any thread that read the reservation from the bin before
+        // we replaced it did so while holding a guard, and so is included in the reference count.
+        unsafe { self.guard.retire_shared(self.reservation) };
+    }
+}
+
+/// The outcome of trying to lock a bin with `HashMap::lock_bin`.
+enum LockedBin<'g> {
+    /// The bin is locked, and still holds the entry it held when it was locked.
+    Locked(BinLockGuard<'g>),
+    /// The bin is empty.
+    Empty,
+    /// The bin has been moved to the next table.
+    Moved,
+    /// The bin changed before it could be locked, so it should be read again.
+    Changed,
+}
+
+/// Runtime statistics about a [`HashMap`], returned by [`HashMap::stats`].
+///
+/// The fields are read one at a time, so they are not necessarily consistent with each other if
+/// the map is modified concurrently.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct MapStats {
+    /// The number of entries in the map, as returned by [`HashMap::len`].
+    pub len: usize,
+    /// The number of bins in the map's current table, as returned by [`HashMap::capacity`].
+    pub capacity: usize,
+    /// The number of times the table has been resized, either because it grew or because it was
+    /// shrunk. Allocating the initial table does not count as a resize.
+    pub resize_count: usize,
+}
+
 /// The error type for the [`HashMap::try_insert`] method.
+///
+/// The value that could not be inserted is handed back by move, so it can be reused, for example
+/// to retry the insert under a different key after losing a race.
+///
+/// # Examples
+///
+/// ```
+/// use flurry::HashMap;
+///
+/// let map = HashMap::new();
+/// let guard = map.guard();
+/// map.insert(1, String::from("a"), &guard);
+///
+/// let err = map.try_insert(1, String::from("b"), &guard).unwrap_err();
+/// assert_eq!(err.current, "a");
+/// // get the rejected value back without cloning it
+/// let value: String = err.not_inserted;
+/// assert_eq!(map.try_insert(2, value, &guard), Ok(&String::from("b")));
+/// ```
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct TryInsertError<'a, V> {
     /// A reference to the current value mapped to the key.
@@ -200,6 +394,189 @@ where
     }
 }
 
+/// A builder for a [`HashMap`] with a non-default configuration.
+///
+/// This `struct` is created by [`HashMap::builder`].
+///
+/// # Examples
+///
+/// ```
+/// use flurry::HashMap;
+///
+/// let map: HashMap<i32, &str> = HashMap::builder()
+///     .capacity(100)
+///     .load_factor(0.5)
+///     .build();
+/// map.pin().insert(1, "a");
+/// ```
+pub struct HashMapBuilder<K, V, S = crate::DefaultHashBuilder> {
+    capacity: usize,
+    load_factor: f64,
+    shrink_factor: Option<f64>,
+    hash_builder: S,
+    _marker: PhantomData<fn() -> (K, V)>,
+}
+
+impl<K, V, S> Default for HashMapBuilder<K, V, S>
+where
+    S: Default,
+{
+    fn default() -> Self {
+        Self {
+            capacity: 0,
+            load_factor: DEFAULT_LOAD_FACTOR,
+            shrink_factor: None,
+            hash_builder: S::default(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<K, V, S> Clone for HashMapBuilder<K, V, S>
+where
+    S: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            capacity: self.capacity,
+            load_factor: self.load_factor,
+            shrink_factor: self.shrink_factor,
+            hash_builder: self.hash_builder.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<K, V, S> Debug for HashMapBuilder<K, V, S>
+where
+    S: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HashMapBuilder")
+            .field("capacity", &self.capacity)
+            .field("load_factor", &self.load_factor)
+            .field("shrink_factor", &self.shrink_factor)
+            .field("hash_builder", &self.hash_builder)
+            .finish()
+    }
+}
+
+impl<K, V, S> HashMapBuilder<K, V, S> {
+    /// Sets the number of elements the map should be able to hold without resizing.
+    ///
+    /// See [`HashMap::with_capacity`] for details.
+    #[must_use]
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Sets the fraction of the map's capacity that may be filled before the map is resized.
+    ///
+    /// The default load factor is 0.75. A higher load factor makes the map use less memory, at
+    /// the cost of more collisions, and therefore slower lookups and more contention between
+    /// writers of the same bin. A lower load factor reduces collisions, but makes the map use more
+    /// memory and resize more often while it grows.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `load_factor` is not strictly between 0 and 1.
+    #[must_use]
+    pub fn load_factor(mut self, load_factor: f64) -> Self {
+        assert!(
+            load_factor > 0.0 && load_factor < 1.0,
+            "load factor must be in (0.0, 1.0), got {}",
+            load_factor
+        );
+        self.load_factor = load_factor;
+        self
+    }
+
+    /// Sets the fraction of the map's capacity below which the map may be shrunk.
+    ///
+    /// [`HashMap::shrink_to_fit`] and [`HashMap::shrink_to`] leave the table alone unless fewer
+    /// than this fraction of its bins would be in use. Keeping the shrink factor well below the
+    /// [load factor](HashMapBuilder::load_factor) makes resizing hysteretic: a map whose size
+    /// hovers around a resize threshold, say because entries are constantly inserted and removed,
+    /// does not alternate between growing and shrinking. By default, the shrink factor is a third
+    /// of the load factor, which is 0.25 for the default load factor of 0.75.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shrink_factor` is not strictly between 0 and 1. [`build`](HashMapBuilder::build)
+    /// panics if the shrink factor is not smaller than the load factor.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map: HashMap<usize, usize> = HashMap::builder()
+    ///     .capacity(1000)
+    ///     .shrink_factor(0.1)
+    ///     .build();
+    /// let guard = map.guard();
+    /// let capacity = map.capacity(&guard);
+    /// for i in 0..capacity / 5 {
+    ///     map.insert(i, i, &guard);
+    /// }
+    ///
+    /// // a fifth of the bins are in use, which is above the shrink factor
+    /// map.shrink_to_fit(&guard);
+    /// assert_eq!(map.capacity(&guard), capacity);
+    /// ```
+    #[must_use]
+    pub fn shrink_factor(mut self, shrink_factor: f64) -> Self {
+        assert!(
+            shrink_factor > 0.0 && shrink_factor < 1.0,
+            "shrink factor must be in (0.0, 1.0), got {}",
+            shrink_factor
+        );
+        self.shrink_factor = Some(shrink_factor);
+        self
+    }
+
+    /// Sets the hasher the map will use to hash keys.
+    ///
+    /// See [`HashMap::with_hasher`] for details.
+    pub fn hasher<S2>(self, hash_builder: S2) -> HashMapBuilder<K, V, S2> {
+        HashMapBuilder {
+            capacity: self.capacity,
+            load_factor: self.load_factor,
+            shrink_factor: self.shrink_factor,
+            hash_builder,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Creates the configured map.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a [shrink factor](HashMapBuilder::shrink_factor) was set that is not smaller than
+    /// the load factor.
+    pub fn build(self) -> HashMap<K, V, S> {
+        let mut map = HashMap::with_hasher(self.hash_builder);
+        map.load_factor = self.load_factor;
+        map.shrink_factor = match self.shrink_factor {
+            Some(shrink_factor) => {
+                assert!(
+                    shrink_factor < self.load_factor,
+                    "shrink factor ({}) must be smaller than the load factor ({})",
+                    shrink_factor,
+                    self.load_factor
+                );
+                shrink_factor
+            }
+            None => self.load_factor / 3.0,
+        };
+        if self.capacity != 0 {
+            map.presize(self.capacity);
+        }
+        map
+    }
+}
+
 // ===
 // the following methods only see Ks and Vs if there have been inserts.
 // modifications to the map are all guarded by thread-safety bounds (Send + Sync ).
@@ -208,6 +585,25 @@ where
 // ===
 
 impl<K, V> HashMap<K, V, crate::DefaultHashBuilder> {
+    /// Creates a builder for a map with a non-default capacity, hasher or load factor.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    /// use std::collections::hash_map::RandomState;
+    ///
+    /// let map: HashMap<&str, i32, _> = HashMap::builder()
+    ///     .capacity(10)
+    ///     .hasher(RandomState::new())
+    ///     .load_factor(0.9)
+    ///     .build();
+    /// map.pin().insert("a", 1);
+    /// ```
+    pub fn builder() -> HashMapBuilder<K, V> {
+        HashMapBuilder::default()
+    }
+
     /// Creates an empty `HashMap`.
     ///
     /// The hash map is initially created with a capacity of 0, so it will not allocate until it
@@ -252,6 +648,19 @@ impl<K, V, S> Default for HashMap<K, V, S>
 where
     S: Default,
 {
+    /// Creates an empty map, with the `Default` value for the hasher.
+    ///
+    /// Unless another hasher is named, that is a [`DefaultHashBuilder`](crate::DefaultHashBuilder).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::{DefaultHashBuilder, HashMap};
+    ///
+    /// let map: HashMap<i32, &str> = HashMap::default();
+    /// let _: &DefaultHashBuilder = map.hasher();
+    /// assert!(map.pin().is_empty());
+    /// ```
     fn default() -> Self {
         Self::with_hasher(S::default())
     }
@@ -281,9 +690,16 @@ impl<K, V, S> HashMap<K, V, S> {
             next_table: Atomic::null(),
             transfer_index: AtomicIsize::new(0),
             count: AtomicIsize::new(0),
+            resize_count: AtomicUsize::new(0),
+            resize_observers: RwLock::new(Vec::new()),
             size_ctl: AtomicIsize::new(0),
+            load_factor: DEFAULT_LOAD_FACTOR,
+            shrink_factor: DEFAULT_SHRINK_FACTOR,
+            #[cfg(feature = "versioned")]
+            version: AtomicU64::new(0),
             build_hasher: hash_builder,
-            collector: Collector::new(),
+            collector: Arc::new(Collector::new()),
+            _marker: PhantomData,
         }
     }
 
@@ -327,6 +743,47 @@ impl<K, V, S> HashMap<K, V, S> {
     /// constructed using guards produced by `collector`.
     #[must_use]
     pub fn with_collector(mut self, collector: Collector) -> Self {
+        self.collector = Arc::new(collector);
+        self
+    }
+
+    /// Associate a [`Collector`] that is shared with other maps with this map.
+    ///
+    /// All maps built with the same shared collector accept each other's guards, so a single
+    /// [`Guard`] obtained with [`Collector::enter`] can be passed to [`HashMap::with_guard`] (or to
+    /// any method that takes a `&Guard`) on each of them. This saves pinning once per map when an
+    /// operation touches several related maps.
+    ///
+    /// The collector is kept alive for as long as any map that uses it, and garbage retired by
+    /// one of the maps is freed only once no guard from the collector protects it anymore. That
+    /// means a guard held on one map also delays the reclamation of memory from all the other
+    /// maps that share its collector. Since retired keys and values may then outlive the map that
+    /// retired them, they must be `'static`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::{Collector, HashMap};
+    /// use std::sync::Arc;
+    ///
+    /// let collector = Arc::new(Collector::new());
+    /// let users = HashMap::new().with_shared_collector(Arc::clone(&collector));
+    /// let sessions = HashMap::new().with_shared_collector(Arc::clone(&collector));
+    ///
+    /// // one guard covers operations on both maps
+    /// let guard = collector.enter();
+    /// let users = users.with_guard(&guard);
+    /// let sessions = sessions.with_guard(&guard);
+    /// users.insert(1, "alice");
+    /// sessions.insert("42", 1);
+    /// assert_eq!(users.get(sessions.get("42").unwrap()), Some(&"alice"));
+    /// ```
+    #[must_use]
+    pub fn with_shared_collector(mut self, collector: Arc<Collector>) -> Self
+    where
+        K: 'static,
+        V: 'static,
+    {
         self.collector = collector;
         self
     }
@@ -339,16 +796,157 @@ impl<K, V, S> HashMap<K, V, S> {
         self.collector.enter()
     }
 
+    /// Returns a reference to the map's [`BuildHasher`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    /// use std::hash::{BuildHasher, Hash, Hasher};
+    ///
+    /// let map: HashMap<i32, i32> = HashMap::new();
+    /// let mut hasher = map.hasher().build_hasher();
+    /// 42.hash(&mut hasher);
+    /// let _hash = hasher.finish();
+    /// ```
+    pub fn hasher(&self) -> &S {
+        &self.build_hasher
+    }
+
     #[inline]
-    fn check_guard(&self, guard: &Guard<'_>) {
+    pub(crate) fn check_guard(&self, guard: &Guard<'_>) {
         // guard.collector() may be `None` if it is unprotected
         if let Some(c) = guard.collector() {
             assert!(Collector::ptr_eq(c, &self.collector));
         }
     }
 
+    /// Returns the number of elements at which a table with `n` bins should be resized.
+    fn resize_threshold(&self, n: usize) -> isize {
+        // n - n * (1 - load_factor), which is n - (n >> 2) for the default load factor of ¾
+        (n - (n as f64 * (1.0 - self.load_factor)) as usize) as isize
+    }
+
+    /// Marks the value of `node` as being replaced. The bin of `node` must be locked.
+    ///
+    /// Together with `finish_update`, this makes sure that a change is reported by
+    /// [`HashMap::iter_since`] for every version that was current before the change finished: while
+    /// the value is being replaced, the node is reported regardless of the version asked for, and
+    /// the version the node ends up with is handed out only after the new value is in place.
+    #[inline]
+    fn begin_update(&self, node: &Node<K, V>) {
+        #[cfg(feature = "versioned")]
+        node.version.store(PENDING_VERSION, Ordering::SeqCst);
+        #[cfg(not(feature = "versioned"))]
+        let _ = node;
+    }
+
+    /// Stamps `node` with a new version once its new value has been stored, or, for a new node,
+    /// once the node has been linked into its bin. The bin of `node` must be locked.
+    #[inline]
+    fn finish_update(&self, node: &Node<K, V>) {
+        #[cfg(feature = "versioned")]
+        node.version.store(
+            self.version.fetch_add(1, Ordering::SeqCst) + 1,
+            Ordering::SeqCst,
+        );
+        #[cfg(not(feature = "versioned"))]
+        let _ = node;
+    }
+
+    /// Like `finish_update`, but for a new node that was linked into a bin that is not locked by
+    /// the current thread.
+    #[inline]
+    fn finish_unlocked_insert(&self, node: &Node<K, V>) {
+        #[cfg(feature = "versioned")]
+        {
+            let version = self.version.fetch_add(1, Ordering::SeqCst) + 1;
+            // if a resize copied the node in the meantime, the copy got a version of its own, and
+            // if another writer replaced the value in the meantime, it stamps the node with a
+            // version it got after its new value was in place. either way, leave the node alone.
+            let _ = node.version.compare_exchange(
+                PENDING_VERSION,
+                version,
+                Ordering::SeqCst,
+                Ordering::SeqCst,
+            );
+        }
+        #[cfg(not(feature = "versioned"))]
+        let _ = node;
+    }
+
+    /// Stamps the node that `find_or_put_tree_val` just inserted into `tree_bin`. The bin must
+    /// still be locked.
+    #[inline]
+    fn finish_tree_insert(&self, tree_bin: &TreeBin<K, V>, guard: &Guard<'_>) {
+        #[cfg(feature = "versioned")]
+        {
+            // new tree nodes are always inserted at the front of the bin's list of nodes
+            let first = tree_bin.first.load(Ordering::SeqCst, guard);
+            // safety: the node was inserted while we held the guard, and it cannot be removed
+            // while we hold the bin lock. structurally, TreeNodes always point to TreeNodes, so
+            // this is sound.
+            self.finish_update(&unsafe { TreeNode::get_tree_node(first) }.node);
+        }
+        #[cfg(not(feature = "versioned"))]
+        let _ = (tree_bin, guard);
+    }
+
+    /// Gives `to`, a copy of `from`, the version of `from`. The bin of `from` must be locked.
+    #[inline]
+    fn copy_version(&self, from: &Node<K, V>, to: &Node<K, V>) {
+        #[cfg(feature = "versioned")]
+        {
+            let version = from.version.load(Ordering::SeqCst);
+            let version = if version == PENDING_VERSION {
+                // writers that hold the bin lock finish their update before releasing it, so
+                // `from` is a new node whose writer has not stamped it yet. the copy is made after
+                // `from` was linked in, so it can be stamped right away.
+                self.version.fetch_add(1, Ordering::SeqCst) + 1
+            } else {
+                version
+            };
+            to.version.store(version, Ordering::SeqCst);
+        }
+        #[cfg(not(feature = "versioned"))]
+        let _ = (from, to);
+    }
+
+    /// Returns the number of elements below which a table with `n` bins may be shrunk.
+    fn shrink_threshold(&self, n: usize) -> usize {
+        (n as f64 * self.shrink_factor) as usize
+    }
+
+    /// Returns the number of bins a table needs to accommodate `size` elements.
+    fn capacity_for(&self, size: usize) -> usize {
+        // round the requested capacity to the next power of two from 1.5 * size + 1 for the
+        // default load factor. other load factors scale the headroom, so that a presized table is
+        // equally close to its resize threshold.
+        // TODO: find out if this is neccessary
+        let headroom = 1.5 * DEFAULT_LOAD_FACTOR / self.load_factor - 1.0;
+        let size = size as f64 + (size as f64 * headroom).floor() + 1.0;
+        if size >= MAXIMUM_CAPACITY as f64 {
+            MAXIMUM_CAPACITY
+        } else {
+            std::cmp::min(MAXIMUM_CAPACITY, (size as usize).next_power_of_two())
+        }
+    }
+
+    /// Checks that a table with `n` bins can be allocated, by allocating (and freeing) the memory
+    /// for its bins.
+    fn try_alloc_bins(n: usize) -> Result<(), TryReserveError> {
+        let mut bins: Vec<Atomic<BinEntry<K, V>>> = Vec::new();
+        bins.try_reserve_exact(n)
+            .map_err(|_| TryReserveError::AllocError)
+    }
+
     /// Returns the number of entries in the map.
     ///
+    /// This is a single atomic load of the map's entry counter, so it is cheap enough to call on
+    /// hot paths. The counter is updated right after each insertion or removal completes, so the
+    /// returned value is exact as long as no other thread is modifying the map, but may briefly
+    /// lag behind operations that are still in progress on other threads.
+    ///
     /// # Examples
     ///
     /// ```
@@ -385,9 +983,26 @@ impl<K, V, S> HashMap<K, V, S> {
         self.len() == 0
     }
 
-    #[cfg(test)]
-    /// Returns the capacity of the map.
-    fn capacity(&self, guard: &Guard<'_>) -> usize {
+    /// Returns the number of bins in the map's current table, or `0` if the table has not been
+    /// allocated yet.
+    ///
+    /// Note that this is _not_ the number of elements the map can hold without resizing. The map
+    /// resizes once the number of elements reaches its load factor (3/4 by default) of its
+    /// capacity.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// let guard = map.guard();
+    /// assert_eq!(map.capacity(&guard), 0);
+    ///
+    /// map.insert("a", 1, &guard);
+    /// assert!(map.capacity(&guard) >= 1);
+    /// ```
+    pub fn capacity(&self, guard: &Guard<'_>) -> usize {
         self.check_guard(guard);
         let table = self.table.load(Ordering::Relaxed, guard);
 
@@ -400,231 +1015,974 @@ impl<K, V, S> HashMap<K, V, S> {
         }
     }
 
-    /// Returns the stamp bits for resizing a table of size n.
-    /// Must be negative when shifted left by `RESIZE_STAMP_SHIFT`.
-    fn resize_stamp(n: usize) -> isize {
-        n.leading_zeros() as isize | (1_isize << (RESIZE_STAMP_BITS - 1))
-    }
-
-    /// An iterator visiting all key-value pairs in arbitrary order.
+    /// Returns an estimate of the number of bytes the map has allocated for its table and its
+    /// entries.
     ///
-    /// The iterator element type is `(&'g K, &'g V)`.
-    pub fn iter<'g>(&'g self, guard: &'g Guard<'_>) -> Iter<'g, K, V> {
+    /// This counts the current table, and for each entry the node that holds it and the
+    /// allocation that holds its value. It does not count heap memory owned by the keys or
+    /// values themselves (such as the buffer of a `String`), the extra bookkeeping of bins that
+    /// have been turned into trees, a table that a concurrent resize is moving entries into, or
+    /// garbage that is waiting to be reclaimed. It is meant to show flurry's own overhead per
+    /// entry, next to the size of the payload.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// let guard = map.guard();
+    /// assert_eq!(map.memory_usage(&guard), 0);
+    ///
+    /// for i in 0..1000u64 {
+    ///     map.insert(i, i, &guard);
+    /// }
+    /// // each entry is stored next to its hash and bin lock, and the value is allocated on its own
+    /// assert!(map.memory_usage(&guard) > 1000 * 2 * std::mem::size_of::<u64>());
+    /// ```
+    pub fn memory_usage(&self, guard: &Guard<'_>) -> usize {
         self.check_guard(guard);
         let table = self.table.load(Ordering::SeqCst, guard);
-        let node_iter = NodeIter::new(table, guard);
-        Iter { node_iter, guard }
+        let table = if table.is_null() {
+            0
+        } else {
+            // safety: we loaded `table` under the `guard`, so it must still be valid here
+            let bins = unsafe { table.deref() }.len();
+            std::mem::size_of::<Linked<Table<K, V>>>()
+                + bins * std::mem::size_of::<Atomic<BinEntry<K, V>>>()
+        };
+        let entry =
+            std::mem::size_of::<Linked<BinEntry<K, V>>>() + std::mem::size_of::<Linked<V>>();
+        table + self.len() * entry
     }
 
-    /// An iterator visiting all keys in arbitrary order.
+    /// Returns `true` if the map is currently moving its entries to a new table, because it is
+    /// growing, shrinking, or being cleared with [`clear_fast`](HashMap::clear_fast).
     ///
-    /// The iterator element type is `&'g K`.
-    pub fn keys<'g>(&'g self, guard: &'g Guard<'_>) -> Keys<'g, K, V> {
+    /// This is only a snapshot: the resize may finish, or a new one start, right after this
+    /// returns. Writers that back off while this returns `true` leave the threads that are
+    /// moving bins free to finish sooner.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// let guard = map.guard();
+    /// map.insert(1, "a", &guard);
+    ///
+    /// // nobody else is using the map, so no resize can be under way
+    /// assert!(!map.is_resizing(&guard));
+    /// ```
+    pub fn is_resizing(&self, guard: &Guard<'_>) -> bool {
         self.check_guard(guard);
-        let table = self.table.load(Ordering::SeqCst, guard);
-        let node_iter = NodeIter::new(table, guard);
-        Keys { node_iter }
+        !self.next_table.load(Ordering::SeqCst, guard).is_null()
     }
 
-    /// An iterator visiting all values in arbitrary order.
+    /// Returns the number of elements in the map together with the number of bins in the table
+    /// that those elements were counted in.
     ///
-    /// The iterator element type is `&'g V`.
-    pub fn values<'g>(&'g self, guard: &'g Guard<'_>) -> Values<'g, K, V> {
+    /// Calling [`len`](HashMap::len) and [`capacity`](HashMap::capacity) separately may straddle
+    /// a resize, and so pair the length of the map with the capacity of a different table. This
+    /// method instead retries until it reads the same table both before and after reading the
+    /// length. Since writers update the length independently of resizes, the length may still
+    /// briefly exceed the point at which the table is resized while a resize is under way.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map: HashMap<_, _> = (0..100).map(|i| (i, i)).collect();
+    /// let guard = map.guard();
+    ///
+    /// let (len, capacity) = map.len_and_capacity(&guard);
+    /// assert_eq!(len, 100);
+    /// assert_eq!(capacity, map.capacity(&guard));
+    /// ```
+    pub fn len_and_capacity(&self, guard: &Guard<'_>) -> (usize, usize) {
         self.check_guard(guard);
-        let table = self.table.load(Ordering::SeqCst, guard);
-        let node_iter = NodeIter::new(table, guard);
-        Values { node_iter, guard }
+        let (table, len) = self.stable_table_and_len(guard);
+        if table.is_null() {
+            return (len, 0);
+        }
+        // Safety: we loaded `table` under the `guard`,
+        // so it must still be valid here
+        (len, unsafe { table.deref() }.len())
     }
 
-    fn init_table<'g>(&'g self, guard: &'g Guard<'_>) -> Shared<'g, Table<K, V>> {
+    /// Reads the current table and the number of entries, such that no resize finished between
+    /// the two reads.
+    fn stable_table_and_len<'g>(&self, guard: &'g Guard<'_>) -> (Shared<'g, Table<K, V>>, usize) {
         loop {
             let table = self.table.load(Ordering::SeqCst, guard);
-            // safety: we loaded the table while the thread was marked as active.
-            // table won't be deallocated until the guard is dropped at the earliest.
-            if !table.is_null() && !unsafe { table.deref() }.is_empty() {
-                break table;
-            }
-            // try to allocate the table
-            let mut sc = self.size_ctl.load(Ordering::SeqCst);
-            if sc < 0 {
-                // we lost the initialization race; just spin
-                std::thread::yield_now();
-                continue;
+            let len = self.len();
+            if self.table.load(Ordering::SeqCst, guard) == table {
+                return (table, len);
             }
+            // a resize finished in between, so try again with the new table
+        }
+    }
 
-            if self
-                .size_ctl
-                .compare_exchange(sc, -1, Ordering::SeqCst, Ordering::Relaxed)
-                .is_ok()
-            {
-                // we get to do it!
-                let mut table = self.table.load(Ordering::SeqCst, guard);
-
-                // safety: we loaded the table while the thread was marked as active.
-                // table won't be deallocated until the guard is dropped at the earliest.
-                if table.is_null() || unsafe { table.deref() }.is_empty() {
-                    let n = if sc > 0 {
-                        sc as usize
-                    } else {
-                        DEFAULT_CAPACITY
-                    };
-                    table = Shared::boxed(Table::new(n, &self.collector), &self.collector);
-                    self.table.store(table, Ordering::SeqCst);
-                    sc = load_factor!(n as isize)
-                }
-                self.size_ctl.store(sc, Ordering::SeqCst);
-                break table;
-            }
+    /// Returns runtime statistics about the map, such as how often it has been resized.
+    ///
+    /// A [`resize_count`](MapStats::resize_count) that keeps climbing while the length of the map
+    /// stays roughly the same is a sign of resize thrashing, for example from repeatedly growing
+    /// and shrinking the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::with_capacity(16);
+    /// let guard = map.guard();
+    /// for i in 0..100 {
+    ///     map.insert(i, i, &guard);
+    /// }
+    ///
+    /// let stats = map.stats();
+    /// assert_eq!(stats.len, 100);
+    /// assert_eq!(stats.capacity, map.capacity(&guard));
+    /// assert!(stats.resize_count > 0);
+    /// ```
+    pub fn stats(&self) -> MapStats {
+        let guard = self.collector.enter();
+        let (len, capacity) = self.len_and_capacity(&guard);
+        MapStats {
+            len,
+            capacity,
+            resize_count: self.resize_count.load(Ordering::Relaxed),
         }
     }
 
-    /// Presize the table to accommodate the given number of elements.
-    fn presize(&mut self, size: usize) {
-        // NOTE: this is a stripped-down version of try_presize for use only when we _know_ that
-        // the table is new, and that therefore we won't have to help out with transfers or deal
-        // with contending initializations.
+    /// Registers a callback that is invoked with the old and the new capacity of the map every
+    /// time the map finishes resizing, whether it grew or was shrunk.
+    ///
+    /// The callback runs on the thread that completed the resize, after the new table has been
+    /// installed and outside of any bin lock, so it may safely access the map, including
+    /// modifying it. Since that thread is in the middle of a map operation, the callback should
+    /// nevertheless be quick. Allocating the initial table does not count as a resize.
+    ///
+    /// Callbacks are not carried over to clones of the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let map = HashMap::with_capacity(16);
+    /// let resizes = Arc::new(Mutex::new(Vec::new()));
+    /// let log = resizes.clone();
+    /// map.on_resize(move |old, new| log.lock().unwrap().push((old, new)));
+    ///
+    /// let guard = map.guard();
+    /// let capacity = map.capacity(&guard);
+    /// for i in 0..100 {
+    ///     map.insert(i, i, &guard);
+    /// }
+    ///
+    /// let resizes = resizes.lock().unwrap();
+    /// assert_eq!(resizes.first(), Some(&(capacity, capacity * 2)));
+    /// assert_eq!(resizes.last().unwrap().1, map.capacity(&guard));
+    /// ```
+    pub fn on_resize<F>(&self, f: F)
+    where
+        F: Fn(usize, usize) + Send + Sync + 'static,
+    {
+        self.resize_observers.write().push(Arc::new(f));
+    }
 
-        // safety: we are creating this map, so no other thread can access it,
-        // while we are initializing it.
-        let guard = unsafe { Guard::unprotected() };
+    fn notify_resize(&self, old_capacity: usize, new_capacity: usize) {
+        let observers = self.resize_observers.read();
+        if observers.is_empty() {
+            return;
+        }
+        // release the lock before running the callbacks, so that they may register more callbacks
+        let observers = observers.clone();
+        for observer in observers {
+            observer(old_capacity, new_capacity);
+        }
+    }
 
-        let requested_capacity = if size >= MAXIMUM_CAPACITY / 2 {
-            MAXIMUM_CAPACITY
-        } else {
-            // round the requested_capacity to the next power of to from 1.5 * size + 1
-            // TODO: find out if this is neccessary
-            let size = size + (size >> 1) + 1;
+    /// Returns the stamp bits for resizing a table of size n.
+    /// Must be negative when shifted left by `RESIZE_STAMP_SHIFT`.
+    fn resize_stamp(n: usize) -> isize {
+        n.leading_zeros() as isize | (1_isize << (RESIZE_STAMP_BITS - 1))
+    }
 
-            std::cmp::min(MAXIMUM_CAPACITY, size.next_power_of_two())
-        } as usize;
+    /// An iterator visiting all key-value pairs in arbitrary order.
+    ///
+    /// The iterator element type is `(&'g K, &'g V)`.
+    pub fn iter<'g>(&'g self, guard: &'g Guard<'_>) -> Iter<'g, K, V> {
+        self.check_guard(guard);
+        let table = self.table.load(Ordering::SeqCst, guard);
+        let node_iter = NodeIter::new(table, guard);
+        Iter {
+            node_iter,
+            guard,
+            remaining: self.len(),
+        }
+    }
 
-        // sanity check that the map has indeed not been set up already
-        assert_eq!(self.size_ctl.load(Ordering::SeqCst), 0);
-        assert!(self.table.load(Ordering::SeqCst, &guard).is_null());
+    /// Returns some entry of the map, or `None` if the map is empty.
+    ///
+    /// The bins are scanned from the first one, and the scan stops at the first entry found, so
+    /// this is a cheap way to check that the map is non-empty while also getting a sample entry.
+    /// The entry is not chosen at random: as long as the map does not change, repeated calls
+    /// return the same entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// let guard = map.guard();
+    /// assert_eq!(map.any_entry(&guard), None);
+    ///
+    /// map.insert(1, "a", &guard);
+    /// assert_eq!(map.any_entry(&guard), Some((&1, &"a")));
+    /// ```
+    pub fn any_entry<'g>(&'g self, guard: &'g Guard<'_>) -> Option<(&'g K, &'g V)> {
+        self.check_guard(guard);
+        let table = self.table.load(Ordering::SeqCst, guard);
+        let node = NodeIter::new(table, guard).next()?;
+        let value = node.value.load(Ordering::SeqCst, guard);
+        // safety: the value is not dropped until after the guard is dropped
+        Some((&node.key, unsafe { value.deref() }))
+    }
 
-        // the table has not yet been initialized, so we can just create it
-        // with as many bins as were requested
+    /// Returns up to `k` entries of the map, chosen uniformly at random using `rng`.
+    ///
+    /// The entries are picked by reservoir sampling over a single traversal of the map, so this
+    /// takes time linear in the size of the map, but only allocates space for `k` entries. Like
+    /// [`iter`](HashMap::iter), the traversal is weakly consistent: entries that are inserted or
+    /// removed concurrently may or may not be considered. If the map has at most `k` entries, all
+    /// of them are returned. The order of the returned entries is unspecified.
+    ///
+    /// This method is only available with the `rand` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map: HashMap<_, _> = (0..100).map(|i| (i, i * 2)).collect();
+    /// let guard = map.guard();
+    ///
+    /// let sample = map.sample(10, &mut rand::thread_rng(), &guard);
+    /// assert_eq!(sample.len(), 10);
+    /// assert!(sample.iter().all(|&(k, v)| *v == k * 2));
+    /// ```
+    #[cfg(feature = "rand")]
+    pub fn sample<'g, R>(
+        &'g self,
+        k: usize,
+        rng: &mut R,
+        guard: &'g Guard<'_>,
+    ) -> Vec<(&'g K, &'g V)>
+    where
+        R: rand::Rng + ?Sized,
+    {
+        let mut reservoir = Vec::with_capacity(k.min(self.len()));
+        if k == 0 {
+            return reservoir;
+        }
 
-        // create a table with `new_capacity` empty bins
-        let new_table = Shared::boxed(
-            Table::new(requested_capacity, &self.collector),
-            &self.collector,
-        );
+        for (i, entry) in self.iter(guard).enumerate() {
+            if i < k {
+                reservoir.push(entry);
+            } else {
+                // keep the i-th entry with probability k / (i + 1)
+                let j = rng.gen_range(0..=i);
+                if j < k {
+                    reservoir[j] = entry;
+                }
+            }
+        }
+        reservoir
+    }
 
-        // store the new table to `self.table`
-        self.table.store(new_table, Ordering::SeqCst);
+    /// An iterator visiting all key-value pairs in arbitrary order, along with the length of the
+    /// map when the iterator was created.
+    ///
+    /// Like [`len_and_capacity`](HashMap::len_and_capacity), the length is read in between two
+    /// reads of the same table pointer, and the iterator walks that table, so the length is not
+    /// from before or after a resize that the iterator does not see. The returned length is also
+    /// the initial lower bound of the iterator's [`size_hint`](Iterator::size_hint). Concurrent
+    /// inserts and removals can still change the number of entries the iterator actually yields.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map: HashMap<_, _> = (0..10).map(|i| (i, i)).collect();
+    /// let guard = map.guard();
+    ///
+    /// let (len, iter) = map.iter_with_len(&guard);
+    /// let mut entries = Vec::with_capacity(len);
+    /// entries.extend(iter);
+    /// assert_eq!(entries.len(), len);
+    /// ```
+    pub fn iter_with_len<'g>(&'g self, guard: &'g Guard<'_>) -> (usize, Iter<'g, K, V>) {
+        self.check_guard(guard);
+        let (table, len) = self.stable_table_and_len(guard);
+        let node_iter = NodeIter::new(table, guard);
+        (
+            len,
+            Iter {
+                node_iter,
+                guard,
+                remaining: len,
+            },
+        )
+    }
 
-        // resize the table once it is 75% full
-        let new_load_to_resize_at = load_factor!(requested_capacity as isize);
+    /// An iterator visiting all keys in arbitrary order.
+    ///
+    /// The iterator element type is `&'g K`.
+    pub fn keys<'g>(&'g self, guard: &'g Guard<'_>) -> Keys<'g, K, V> {
+        self.check_guard(guard);
+        let table = self.table.load(Ordering::SeqCst, guard);
+        let node_iter = NodeIter::new(table, guard);
+        Keys {
+            node_iter,
+            remaining: self.len(),
+        }
+    }
 
-        // store the next load at which the table should resize to it's size_ctl field
-        // and thus release the initialization "lock"
-        self.size_ctl.store(new_load_to_resize_at, Ordering::SeqCst);
+    /// An iterator visiting all values in arbitrary order.
+    ///
+    /// The iterator element type is `&'g V`.
+    pub fn values<'g>(&'g self, guard: &'g Guard<'_>) -> Values<'g, K, V> {
+        self.check_guard(guard);
+        let table = self.table.load(Ordering::SeqCst, guard);
+        let node_iter = NodeIter::new(table, guard);
+        Values {
+            node_iter,
+            guard,
+            remaining: self.len(),
+        }
     }
-}
 
-// ===
-// the following methods require Clone and Ord, since they ultimately call
-// `transfer`, which needs to be able to clone keys and work with tree bins.
-// however, they do _not_ need to require thread-safety bounds (Send + Sync +
-// 'static) since if the bounds do not hold, the map is empty, so no keys or
-// values will be transfered anyway.
-// ===
+    /// An iterator visiting all key-value pairs in arbitrary order, along with the hash of each
+    /// key.
+    ///
+    /// The hash is the one the map computed with its [`BuildHasher`] when the key was inserted,
+    /// and which it stores next to each entry, so this saves rehashing the keys when, for
+    /// example, building a secondary index with the same hasher.
+    ///
+    /// The iterator element type is `(u64, &'g K, &'g V)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    /// use std::hash::{BuildHasher, Hash, Hasher};
+    ///
+    /// let map = HashMap::new();
+    /// let guard = map.guard();
+    /// map.insert("a", 1, &guard);
+    ///
+    /// for (hash, key, _) in map.iter_hashed(&guard) {
+    ///     let mut hasher = map.hasher().build_hasher();
+    ///     key.hash(&mut hasher);
+    ///     assert_eq!(hash, hasher.finish());
+    /// }
+    /// ```
+    pub fn iter_hashed<'g>(&'g self, guard: &'g Guard<'_>) -> IterHashed<'g, K, V> {
+        self.check_guard(guard);
+        let table = self.table.load(Ordering::SeqCst, guard);
+        let node_iter = NodeIter::new(table, guard);
+        IterHashed {
+            node_iter,
+            guard,
+            remaining: self.len(),
+        }
+    }
 
-impl<K, V, S> HashMap<K, V, S>
-where
-    K: Clone + Ord,
-{
-    /// Tries to presize table to accommodate the given number of elements.
-    fn try_presize(&self, size: usize, guard: &Guard<'_>) {
-        let requested_capacity = if size >= MAXIMUM_CAPACITY / 2 {
-            MAXIMUM_CAPACITY
-        } else {
-            // round the requested_capacity to the next power of to from 1.5 * size + 1
-            // TODO: find out if this is neccessary
-            let size = size + (size >> 1) + 1;
+    /// Returns the latest version handed out by the map.
+    ///
+    /// Every insert or update of an entry stamps that entry with a new version, and versions
+    /// only ever grow. Pass the value returned here to a later call to
+    /// [`iter_since`](HashMap::iter_since) to visit the entries that changed in between.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// let guard = map.guard();
+    /// assert_eq!(map.current_version(), 0);
+    ///
+    /// map.insert(1, "a", &guard);
+    /// map.insert(1, "b", &guard);
+    /// assert_eq!(map.current_version(), 2);
+    /// ```
+    #[cfg(feature = "versioned")]
+    pub fn current_version(&self) -> u64 {
+        self.version.load(Ordering::SeqCst)
+    }
 
-            std::cmp::min(MAXIMUM_CAPACITY, size.next_power_of_two())
-        } as isize;
+    /// An iterator visiting the key-value pairs that were inserted or updated after `version`,
+    /// in arbitrary order.
+    ///
+    /// This is meant for incremental consumers, such as a replica or a cache that is kept in sync
+    /// with the map: read [`current_version`](HashMap::current_version) _before_ iterating, and
+    /// pass it to the next call. Every change that finishes after that read is then reported by
+    /// the next call, so each change is seen at least once. An entry that is being changed while
+    /// the iterator visits it is always yielded, and may be yielded again by the next call.
+    ///
+    /// Only the latest value of each entry is yielded, and removals are not reported. Resizes do
+    /// not change the version of an entry.
+    ///
+    /// The iterator element type is `(&'g K, &'g V)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// let guard = map.guard();
+    /// map.insert(1, "a", &guard);
+    /// map.insert(2, "b", &guard);
+    ///
+    /// let seen = map.current_version();
+    /// map.insert(2, "c", &guard);
+    /// map.insert(3, "d", &guard);
+    ///
+    /// let mut changed: Vec<_> = map.iter_since(seen, &guard).collect();
+    /// changed.sort();
+    /// assert_eq!(changed, [(&2, &"c"), (&3, &"d")]);
+    /// ```
+    #[cfg(feature = "versioned")]
+    pub fn iter_since<'g>(&'g self, version: u64, guard: &'g Guard<'_>) -> IterSince<'g, K, V> {
+        self.check_guard(guard);
+        let table = self.table.load(Ordering::SeqCst, guard);
+        let node_iter = NodeIter::new(table, guard);
+        IterSince {
+            node_iter,
+            guard,
+            since: version,
+        }
+    }
 
-        loop {
-            let size_ctl = self.size_ctl.load(Ordering::SeqCst);
-            if size_ctl < 0 {
-                break;
-            }
+    /// An iterator visiting clones of all key-value pairs in arbitrary order.
+    ///
+    /// Each pair is cloned as it is visited, so the yielded items are owned and do not borrow
+    /// from the guard. This is handy for handing entries to code that outlives the guard, such as
+    /// another thread or an async task.
+    ///
+    /// The iterator element type is `(K, V)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    /// use std::sync::mpsc;
+    ///
+    /// let map = HashMap::new();
+    /// map.pin().insert(1, String::from("a"));
+    ///
+    /// let (tx, rx) = mpsc::channel();
+    /// for entry in map.pin().iter_cloned() {
+    ///     tx.send(entry).unwrap();
+    /// }
+    /// drop(tx);
+    ///
+    /// let received: Vec<(i32, String)> = rx.iter().collect();
+    /// assert_eq!(received, [(1, String::from("a"))]);
+    /// ```
+    pub fn iter_cloned<'g>(&'g self, guard: &'g Guard<'_>) -> IterCloned<'g, K, V>
+    where
+        K: Clone,
+        V: Clone,
+    {
+        IterCloned {
+            inner: self.iter(guard),
+        }
+    }
 
-            let table = self.table.load(Ordering::SeqCst, guard);
+    /// An iterator visiting clones of all keys in arbitrary order.
+    ///
+    /// See [`HashMap::iter_cloned`] for details.
+    ///
+    /// The iterator element type is `K`.
+    pub fn keys_cloned<'g>(&'g self, guard: &'g Guard<'_>) -> KeysCloned<'g, K, V>
+    where
+        K: Clone,
+    {
+        KeysCloned {
+            inner: self.keys(guard),
+        }
+    }
 
-            // The current capacity == the number of bins in the current table
-            let current_capactity = if table.is_null() {
-                0
-            } else {
-                unsafe { table.deref() }.len()
-            };
+    /// An iterator visiting clones of all values in arbitrary order.
+    ///
+    /// See [`HashMap::iter_cloned`] for details.
+    ///
+    /// The iterator element type is `V`.
+    pub fn values_cloned<'g>(&'g self, guard: &'g Guard<'_>) -> ValuesCloned<'g, K, V>
+    where
+        V: Clone,
+    {
+        ValuesCloned {
+            inner: self.values(guard),
+        }
+    }
 
-            if current_capactity == 0 {
-                // the table has not yet been initialized, so we can just create it
-                // with as many bins as were requested
+    /// An iterator visiting the key-value pairs whose keys fall within `range`.
+    ///
+    /// The bins are not sorted, so this still walks the whole map, and the entries are yielded in
+    /// arbitrary order rather than in key order. Like [`iter`](HashMap::iter), it follows bins
+    /// that are being moved by a resize.
+    ///
+    /// The iterator element type is `(&'g K, &'g V)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map: HashMap<_, _> = (0..10).map(|i| (i, i * 10)).collect();
+    /// let guard = map.guard();
+    ///
+    /// let mut in_range: Vec<_> = map.range(3..6, &guard).collect();
+    /// in_range.sort();
+    /// assert_eq!(in_range, [(&3, &30), (&4, &40), (&5, &50)]);
+    /// ```
+    pub fn range<'g, Q, R>(&'g self, range: R, guard: &'g Guard<'_>) -> Range<'g, K, V, Q, R>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+        R: RangeBounds<Q>,
+    {
+        Range {
+            iter: self.iter(guard),
+            range,
+            _marker: PhantomData,
+        }
+    }
 
-                // since the map is uninitialized, size_ctl describes the initial capacity
-                let initial_capacity = size_ctl;
+    /// An iterator visiting the bins of the map, each of which yields the key-value pairs in that
+    /// bin.
+    ///
+    /// This lets you process entries whose keys hash to the same bin together. Which entries share
+    /// a bin is an implementation detail: it depends on the hasher and on the current size of the
+    /// table, and changes whenever the map is resized. Bins are taken from the table as it was when
+    /// this method was called, and entries that a concurrent resize moves while you iterate are
+    /// still grouped by the bin they had in that table. Empty bins are skipped.
+    ///
+    /// The iterator element type is [`Bin<'g, K, V>`](Bin), which is itself an iterator over
+    /// `(&'g K, &'g V)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map: HashMap<_, _> = (0..100).map(|i| (i, i)).collect();
+    /// let guard = map.guard();
+    ///
+    /// let mut entries = 0;
+    /// for bin in map.bins(&guard) {
+    ///     let batch: Vec<_> = bin.collect();
+    ///     assert!(!batch.is_empty());
+    ///     entries += batch.len();
+    /// }
+    /// assert_eq!(entries, 100);
+    /// ```
+    pub fn bins<'g>(&'g self, guard: &'g Guard<'_>) -> Bins<'g, K, V> {
+        self.check_guard(guard);
+        let table = self.table.load(Ordering::SeqCst, guard);
+        Bins {
+            // safety: flurry guarantees that a table read under a guard is never dropped or moved
+            // until after that guard is dropped.
+            table: (!table.is_null()).then(|| unsafe { table.deref() }),
+            index: 0,
+            guard,
+        }
+    }
 
-                // the new capacity is either the requested capacity or the initial capacity (size_ctl)
-                let new_capacity = requested_capacity.max(initial_capacity) as usize;
+    /// Collects all key-value pairs into a `Vec`, sorted by key.
+    ///
+    /// This is meant for tests and debugging output that need a canonical ordering, and is not
+    /// intended for hot paths: it walks the whole map, allocates, and sorts on every call.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map: HashMap<_, _> = (0..5).rev().map(|i| (i, i * 10)).collect();
+    /// let guard = map.guard();
+    ///
+    /// assert_eq!(
+    ///     map.debug_iter_sorted(&guard),
+    ///     [(&0, &0), (&1, &10), (&2, &20), (&3, &30), (&4, &40)]
+    /// );
+    /// ```
+    pub fn debug_iter_sorted<'g>(&'g self, guard: &'g Guard<'_>) -> Vec<(&'g K, &'g V)>
+    where
+        K: Ord,
+    {
+        let mut entries: Vec<_> = self.iter(guard).collect();
+        entries.sort_by_key(|&(k, _)| k);
+        entries
+    }
 
-                // try to aquire the initialization "lock" to indicate that we are initializing the table.
-                if self
-                    .size_ctl
-                    .compare_exchange(size_ctl, -1, Ordering::SeqCst, Ordering::Relaxed)
-                    .is_err()
-                {
-                    // somebody else is already initializing the table (or has already finished).
-                    continue;
-                }
+    /// Creates a consuming iterator visiting all keys in arbitrary order.
+    ///
+    /// The map cannot be used after calling this. The iterator element type is `K`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// map.pin().insert(String::from("a"), 1);
+    /// map.pin().insert(String::from("b"), 2);
+    ///
+    /// let mut keys: Vec<String> = map.into_keys().collect();
+    /// keys.sort();
+    /// assert_eq!(keys, ["a", "b"]);
+    /// ```
+    pub fn into_keys(self) -> IntoKeys<K, V> {
+        IntoKeys {
+            inner: self.into_iter(),
+        }
+    }
 
-                // we got the initialization `lock`; Make sure the table is still unitialized
-                // (or is the same table with 0 bins we read earlier, althought that should not be the case)
-                if self.table.load(Ordering::SeqCst, guard) != table {
-                    // NOTE: this could probably be `!self.table.load(...).is_null()`
-                    // if we decide that tables can never have 0 bins.
+    /// Creates a consuming iterator visiting all values in arbitrary order.
+    ///
+    /// The map cannot be used after calling this. The iterator element type is `V`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// map.pin().insert(1, String::from("a"));
+    /// map.pin().insert(2, String::from("b"));
+    ///
+    /// let mut values: Vec<String> = map.into_values().collect();
+    /// values.sort();
+    /// assert_eq!(values, ["a", "b"]);
+    /// ```
+    pub fn into_values(self) -> IntoValues<K, V> {
+        IntoValues {
+            inner: self.into_iter(),
+        }
+    }
 
-                    // the table is already initialized; Write the `size_ctl` value it had back to it's
-                    // `size_ctl` field to release the initialization "lock"
-                    self.size_ctl.store(size_ctl, Ordering::SeqCst);
-                    continue;
-                }
+    /// Calls `f` on each key-value pair in the map, in arbitrary order.
+    ///
+    /// This is the same as `map.iter(guard).for_each(|(k, v)| f(k, v))`, and visits the same
+    /// entries as [`iter`](HashMap::iter) does.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// let guard = map.guard();
+    /// map.insert("a", 1, &guard);
+    /// map.insert("b", 2, &guard);
+    ///
+    /// let mut sum = 0;
+    /// map.for_each(|_, v| sum += v, &guard);
+    /// assert_eq!(sum, 3);
+    /// ```
+    pub fn for_each<F>(&self, mut f: F, guard: &Guard<'_>)
+    where
+        F: FnMut(&K, &V),
+    {
+        self.iter(guard).for_each(|(k, v)| f(k, v));
+    }
 
-                // create a table with `new_capacity` empty bins
-                let new_table =
-                    Shared::boxed(Table::new(new_capacity, &self.collector), &self.collector);
+    /// Folds every key-value pair in the map into an accumulator, in arbitrary order.
+    ///
+    /// This is the same as `map.iter(guard).fold(init, |acc, (k, v)| f(acc, k, v))`. The order
+    /// in which entries are visited is unspecified, so `f` should not depend on it. Entries
+    /// inserted or removed concurrently may or may not be visited.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// let guard = map.guard();
+    /// map.insert("a", 1, &guard);
+    /// map.insert("b", 2, &guard);
+    ///
+    /// assert_eq!(map.fold(0, |sum, _, v| sum + v, &guard), 3);
+    /// ```
+    pub fn fold<B, F>(&self, init: B, mut f: F, guard: &Guard<'_>) -> B
+    where
+        F: FnMut(B, &K, &V) -> B,
+    {
+        self.iter(guard).fold(init, |acc, (k, v)| f(acc, k, v))
+    }
 
-                // store the new table to `self.table`
-                let old_table = self.table.swap(new_table, Ordering::SeqCst, guard);
+    /// Collects the results of `f` for every key-value pair for which it returns `Some`, in
+    /// arbitrary order.
+    ///
+    /// This is the same as `map.iter(guard).filter_map(|(k, v)| f(k, v)).collect()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// let guard = map.guard();
+    /// map.insert("a", 1, &guard);
+    /// map.insert("b", 2, &guard);
+    /// map.insert("c", 3, &guard);
+    ///
+    /// let mut odd = map.collect_map(|k, v| (v % 2 == 1).then(|| k.to_uppercase()), &guard);
+    /// odd.sort();
+    /// assert_eq!(odd, ["A", "C"]);
+    /// ```
+    pub fn collect_map<F, T>(&self, mut f: F, guard: &Guard<'_>) -> Vec<T>
+    where
+        F: FnMut(&K, &V) -> Option<T>,
+    {
+        self.iter(guard).filter_map(|(k, v)| f(k, v)).collect()
+    }
 
-                // old_table should be `null`, since we don't ever initialize a table with 0 bins
-                // and this branch only happens if table has not yet been initialized or it's length is 0.
-                assert!(old_table.is_null());
+    /// Returns the number of key-value pairs for which `f` returns `true`.
+    ///
+    /// This is the same as `map.iter(guard).filter(|(k, v)| f(k, v)).count()`. If the map is
+    /// resized concurrently, each entry is still visited exactly once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// let guard = map.guard();
+    /// map.insert("a", 1, &guard);
+    /// map.insert("b", 2, &guard);
+    /// map.insert("c", 3, &guard);
+    ///
+    /// assert_eq!(map.count_if(|_, v| v % 2 == 1, &guard), 2);
+    /// ```
+    pub fn count_if<F>(&self, mut f: F, guard: &Guard<'_>) -> usize
+    where
+        F: FnMut(&K, &V) -> bool,
+    {
+        self.iter(guard).filter(|&(k, v)| f(k, v)).count()
+    }
 
-                // TODO: if we allow tables with 0 bins. `defer_destroy` `old_table` if it's not `null`:
-                // if !old_table.is_null() {
-                //     // TODO: safety argument, for why this is okay
-                //     unsafe { guard.defer_destroy(old_table) }
-                // }
+    /// Returns `true` if the map maps one or more keys to the given value.
+    ///
+    /// This walks the map like [`values`](HashMap::values), so it takes time linear in the size
+    /// of the map, and stops at the first match.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// let guard = map.guard();
+    /// map.insert("a", 1, &guard);
+    ///
+    /// assert!(map.contains_value(&1, &guard));
+    /// assert!(!map.contains_value(&2, &guard));
+    /// ```
+    pub fn contains_value(&self, value: &V, guard: &Guard<'_>) -> bool
+    where
+        V: PartialEq,
+    {
+        self.values(guard).any(|v| v == value)
+    }
 
-                // resize the table once it is 75% full
-                let new_load_to_resize_at = load_factor!(new_capacity as isize);
+    fn init_table<'g>(&'g self, guard: &'g Guard<'_>) -> Shared<'g, Table<K, V>> {
+        loop {
+            let table = self.table.load(Ordering::SeqCst, guard);
+            // safety: we loaded the table while the thread was marked as active.
+            // table won't be deallocated until the guard is dropped at the earliest.
+            if !table.is_null() && !unsafe { table.deref() }.is_empty() {
+                break table;
+            }
+            // try to allocate the table
+            let mut sc = self.size_ctl.load(Ordering::SeqCst);
+            if sc < 0 {
+                // we lost the initialization race; just spin
+                std::thread::yield_now();
+                continue;
+            }
 
-                // store the next load at which the table should resize to it's size_ctl field
-                // and thus release the initialization "lock"
-                self.size_ctl.store(new_load_to_resize_at, Ordering::SeqCst);
-            } else if requested_capacity <= size_ctl || current_capactity >= MAXIMUM_CAPACITY {
-                // Either the `requested_capacity` was smaller than or equal to the load we would resize at (size_ctl)
-                // and we don't need to resize, since our load factor will still be acceptable if we don't
+            if self
+                .size_ctl
+                .compare_exchange(sc, -1, Ordering::SeqCst, Ordering::Relaxed)
+                .is_ok()
+            {
+                // we get to do it!
+                let mut table = self.table.load(Ordering::SeqCst, guard);
 
-                // Or it was larger than the `MAXIMUM_CAPACITY` of the map and we refuse
-                // to resize to an invalid capacity
-                break;
-            } else if table == self.table.load(Ordering::SeqCst, guard) {
+                // safety: we loaded the table while the thread was marked as active.
+                // table won't be deallocated until the guard is dropped at the earliest.
+                if table.is_null() || unsafe { table.deref() }.is_empty() {
+                    let n = if sc > 0 {
+                        sc as usize
+                    } else {
+                        DEFAULT_CAPACITY
+                    };
+                    table = Shared::boxed(Table::new(n, &self.collector), &self.collector);
+                    self.table.store(table, Ordering::SeqCst);
+                    sc = self.resize_threshold(n)
+                }
+                self.size_ctl.store(sc, Ordering::SeqCst);
+                break table;
+            }
+        }
+    }
+
+    /// Presize the table to accommodate the given number of elements.
+    fn presize(&mut self, size: usize) {
+        // NOTE: this is a stripped-down version of try_presize for use only when we _know_ that
+        // the table is new, and that therefore we won't have to help out with transfers or deal
+        // with contending initializations.
+
+        // safety: we are creating this map, so no other thread can access it,
+        // while we are initializing it.
+        let guard = unsafe { Guard::unprotected() };
+
+        let requested_capacity = self.capacity_for(size);
+
+        // sanity check that the map has indeed not been set up already
+        assert_eq!(self.size_ctl.load(Ordering::SeqCst), 0);
+        assert!(self.table.load(Ordering::SeqCst, &guard).is_null());
+
+        // the table has not yet been initialized, so we can just create it
+        // with as many bins as were requested
+
+        // create a table with `new_capacity` empty bins
+        let new_table = Shared::boxed(
+            Table::new(requested_capacity, &self.collector),
+            &self.collector,
+        );
+
+        // store the new table to `self.table`
+        self.table.store(new_table, Ordering::SeqCst);
+
+        // resize the table once it reaches the load factor
+        let new_load_to_resize_at = self.resize_threshold(requested_capacity);
+
+        // store the next load at which the table should resize to it's size_ctl field
+        // and thus release the initialization "lock"
+        self.size_ctl.store(new_load_to_resize_at, Ordering::SeqCst);
+    }
+}
+
+// ===
+// the following methods require Clone and Ord, since they ultimately call
+// `transfer`, which needs to be able to clone keys and work with tree bins.
+// however, they do _not_ need to require thread-safety bounds (Send + Sync +
+// 'static) since if the bounds do not hold, the map is empty, so no keys or
+// values will be transfered anyway.
+// ===
+
+impl<K, V, S> HashMap<K, V, S>
+where
+    K: Clone + Ord,
+{
+    /// Tries to presize table to accommodate the given number of elements.
+    fn try_presize(&self, size: usize, guard: &Guard<'_>) {
+        let requested_capacity = self.capacity_for(size) as isize;
+
+        loop {
+            let size_ctl = self.size_ctl.load(Ordering::SeqCst);
+            if size_ctl < 0 {
+                break;
+            }
+
+            let table = self.table.load(Ordering::SeqCst, guard);
+
+            // The current capacity == the number of bins in the current table
+            let current_capactity = if table.is_null() {
+                0
+            } else {
+                unsafe { table.deref() }.len()
+            };
+
+            if current_capactity == 0 {
+                // the table has not yet been initialized, so we can just create it
+                // with as many bins as were requested
+
+                // since the map is uninitialized, size_ctl describes the initial capacity
+                let initial_capacity = size_ctl;
+
+                // the new capacity is either the requested capacity or the initial capacity (size_ctl)
+                let new_capacity = requested_capacity.max(initial_capacity) as usize;
+
+                // try to aquire the initialization "lock" to indicate that we are initializing the table.
+                if self
+                    .size_ctl
+                    .compare_exchange(size_ctl, -1, Ordering::SeqCst, Ordering::Relaxed)
+                    .is_err()
+                {
+                    // somebody else is already initializing the table (or has already finished).
+                    continue;
+                }
+
+                // we got the initialization `lock`; Make sure the table is still unitialized
+                // (or is the same table with 0 bins we read earlier, althought that should not be the case)
+                if self.table.load(Ordering::SeqCst, guard) != table {
+                    // NOTE: this could probably be `!self.table.load(...).is_null()`
+                    // if we decide that tables can never have 0 bins.
+
+                    // the table is already initialized; Write the `size_ctl` value it had back to it's
+                    // `size_ctl` field to release the initialization "lock"
+                    self.size_ctl.store(size_ctl, Ordering::SeqCst);
+                    continue;
+                }
+
+                // create a table with `new_capacity` empty bins
+                let new_table =
+                    Shared::boxed(Table::new(new_capacity, &self.collector), &self.collector);
+
+                // store the new table to `self.table`
+                let old_table = self.table.swap(new_table, Ordering::SeqCst, guard);
+
+                // old_table should be `null`, since we don't ever initialize a table with 0 bins
+                // and this branch only happens if table has not yet been initialized or it's length is 0.
+                assert!(old_table.is_null());
+
+                // TODO: if we allow tables with 0 bins. `defer_destroy` `old_table` if it's not `null`:
+                // if !old_table.is_null() {
+                //     // TODO: safety argument, for why this is okay
+                //     unsafe { guard.defer_destroy(old_table) }
+                // }
+
+                // resize the table once it reaches the load factor
+                let new_load_to_resize_at = self.resize_threshold(new_capacity);
+
+                // store the next load at which the table should resize to it's size_ctl field
+                // and thus release the initialization "lock"
+                self.size_ctl.store(new_load_to_resize_at, Ordering::SeqCst);
+            } else if requested_capacity <= size_ctl || current_capactity >= MAXIMUM_CAPACITY {
+                // Either the `requested_capacity` was smaller than or equal to the load we would resize at (size_ctl)
+                // and we don't need to resize, since our load factor will still be acceptable if we don't
+
+                // Or it was larger than the `MAXIMUM_CAPACITY` of the map and we refuse
+                // to resize to an invalid capacity
+                break;
+            } else if table == self.table.load(Ordering::SeqCst, guard) {
                 // The table is initialized, try to resize it to the requested capacity
 
                 let rs: isize = Self::resize_stamp(current_capactity) << RESIZE_STAMP_SHIFT;
@@ -707,6 +2065,7 @@ where
                     .compare_exchange(next_index, next_bound, Ordering::SeqCst, Ordering::Relaxed)
                     .is_ok()
                 {
+                    TRANSFER_STRIDES.with(|strides| strides.set(strides.get() + 1));
                     bound = next_bound;
                     i = next_index;
                     advance = false;
@@ -721,6 +2080,7 @@ where
                     // this branch is only taken for one thread partaking in the resize!
                     self.next_table.store(Shared::null(), Ordering::SeqCst);
                     let now_garbage = self.table.swap(next_table_ptr, Ordering::SeqCst, guard);
+                    self.resize_count.fetch_add(1, Ordering::Relaxed);
                     // safety: need to guarantee that now_garbage is no longer reachable. more
                     // specifically, no thread that executes _after_ this line can ever get a
                     // reference to now_garbage.
@@ -748,7 +2108,8 @@ where
                     // that thread drops its guard at the earliest.
                     unsafe { guard.retire_shared(now_garbage) };
                     self.size_ctl
-                        .store(((n as isize) << 1) - ((n as isize) >> 1), Ordering::SeqCst);
+                        .store(self.resize_threshold(n << 1), Ordering::SeqCst);
+                    self.notify_resize(n, n << 1);
                     return;
                 }
 
@@ -832,8 +2193,6 @@ where
                     // yes, it is still the head, so we can now "own" the bin
                     // note that there can still be readers in the bin!
 
-                    // TODO: ReservationNode
-
                     let mut run_bit = head.hash & n as u64;
                     let mut last_run = bin;
                     let mut p = bin;
@@ -891,15 +2250,14 @@ where
                             &mut high_bin
                         };
 
-                        *link = Shared::boxed(
-                            BinEntry::Node(Node::with_next(
-                                node.hash,
-                                node.key.clone(),
-                                node.value.clone(),
-                                Atomic::from(*link),
-                            )),
-                            &self.collector,
+                        let copy = Node::with_next(
+                            node.hash,
+                            node.key.clone(),
+                            node.value.clone(),
+                            Atomic::from(*link),
                         );
+                        self.copy_version(node, &copy);
+                        *link = Shared::boxed(BinEntry::Node(copy), &self.collector);
 
                         p = node.next.load(Ordering::SeqCst, guard);
                     }
@@ -966,6 +2324,7 @@ where
                             Atomic::null(),
                             Atomic::null(),
                         );
+                        self.copy_version(&tree_node.node, &new_node.node);
                         let run_bit = hash & n as u64;
                         if run_bit == 0 {
                             new_node.prev.store(low_tail, Ordering::Relaxed);
@@ -1090,6 +2449,10 @@ where
                 BinEntry::TreeNode(_) => unreachable!(
                     "The head of a bin cannot be a TreeNode directly without BinEntry::Tree"
                 ),
+                BinEntry::Reservation(_) => {
+                    table.wait_for_reservation(i, bin, guard);
+                    continue;
+                }
             }
         }
     }
@@ -1196,139 +2559,2043 @@ where
                 if self.transfer_index.load(Ordering::SeqCst) <= 0 {
                     break;
                 }
-
-                // try to join!
-                if self
-                    .size_ctl
-                    .compare_exchange(sc, sc + 1, Ordering::SeqCst, Ordering::Relaxed)
-                    .is_ok()
-                {
-                    self.transfer(table, nt, guard);
+
+                // try to join!
+                if self
+                    .size_ctl
+                    .compare_exchange(sc, sc + 1, Ordering::SeqCst, Ordering::Relaxed)
+                    .is_ok()
+                {
+                    self.transfer(table, nt, guard);
+                }
+            } else if self
+                .size_ctl
+                .compare_exchange(sc, rs + 2, Ordering::SeqCst, Ordering::Relaxed)
+                .is_ok()
+            {
+                // a resize is needed, but has not yet started
+                // TODO: figure out why this is rs + 2, not just rs
+                // NOTE: this also applies to `try_presize`
+                self.transfer(table, Shared::null(), guard);
+            }
+
+            // another resize may be needed!
+            count = self.count.load(Ordering::SeqCst);
+        }
+    }
+
+    /// Tries to reserve capacity for at least `additional` more elements to be inserted in the
+    /// `HashMap`.
+    ///
+    /// The collection may reserve more space to avoid frequent reallocations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map: HashMap<&str, i32> = HashMap::new();
+    ///
+    /// map.pin().reserve(10);
+    /// ```
+    ///
+    /// # Notes
+    ///
+    /// Reserving does not panic in flurry. If the new size is invalid, no
+    /// reallocation takes place.
+    pub fn reserve(&self, additional: usize, guard: &Guard<'_>) {
+        self.check_guard(guard);
+        let absolute = self.len() + additional;
+        self.try_presize(absolute, guard);
+    }
+
+    /// Tries to reserve capacity for at least `additional` more elements to be inserted in the
+    /// `HashMap`, returning an error instead of aborting if that is not possible.
+    ///
+    /// This is best-effort, like [`try_insert_alloc`](HashMap::try_insert_alloc). Before
+    /// resizing, it checks that the requested capacity is valid, and probes the allocator by
+    /// allocating (and freeing) the memory for the new bin table. The table itself is then still
+    /// allocated infallibly by the resize, so the check cannot account for memory that other
+    /// threads allocate in between. Like [`reserve`](HashMap::reserve), it also does not wait for
+    /// a resize that another thread is already running, so `Ok(())` does not guarantee that the
+    /// capacity has been reserved.
+    ///
+    /// # Errors
+    ///
+    /// If the requested capacity exceeds the maximum capacity of the map,
+    /// [`TryReserveError::CapacityOverflow`] is returned. If the allocator reports a failure,
+    /// [`TryReserveError::AllocError`] is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::{HashMap, TryReserveError};
+    ///
+    /// let map: HashMap<&str, i32> = HashMap::new();
+    /// let guard = map.guard();
+    ///
+    /// assert_eq!(map.try_reserve(10, &guard), Ok(()));
+    /// assert!(map.capacity(&guard) >= 10);
+    /// assert_eq!(
+    ///     map.try_reserve(usize::MAX, &guard),
+    ///     Err(TryReserveError::CapacityOverflow)
+    /// );
+    /// ```
+    pub fn try_reserve(&self, additional: usize, guard: &Guard<'_>) -> Result<(), TryReserveError> {
+        self.check_guard(guard);
+        let absolute = self
+            .len()
+            .checked_add(additional)
+            .ok_or(TryReserveError::CapacityOverflow)?;
+        if absolute > MAXIMUM_CAPACITY {
+            return Err(TryReserveError::CapacityOverflow);
+        }
+
+        // make sure that the largest table the resize will allocate can actually be allocated
+        let requested_capacity = self.capacity_for(absolute);
+        if requested_capacity > self.capacity(guard) {
+            Self::try_alloc_bins(requested_capacity)?;
+        }
+
+        self.try_presize(absolute, guard);
+        Ok(())
+    }
+
+    /// Allocates the map's table with room for at least `capacity` elements in total before this
+    /// method returns.
+    ///
+    /// This is meant for cold loads from many threads at once: without it, all the loading threads
+    /// start out inserting into the same small table and then contend on growing it several times
+    /// over. Unlike [`reserve`](HashMap::reserve), `capacity` is the total number of elements rather
+    /// than the number of additional ones, and if another thread is in the middle of a resize, this
+    /// method helps finish that resize and tries again instead of giving up. Once it returns, the
+    /// map will not resize again until it holds more elements than its load factor allows for the
+    /// preallocated table.
+    ///
+    /// Capacities beyond the largest table the map supports are capped at that table size.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// let guard = map.guard();
+    ///
+    /// map.preallocate(1000, &guard);
+    /// let capacity = map.capacity(&guard);
+    /// assert!(capacity >= 1000);
+    ///
+    /// for i in 0..1000 {
+    ///     map.insert(i, i, &guard);
+    /// }
+    /// assert_eq!(map.capacity(&guard), capacity);
+    /// ```
+    pub fn preallocate(&self, capacity: usize, guard: &Guard<'_>) {
+        self.check_guard(guard);
+        loop {
+            self.try_presize(capacity, guard);
+            // try_presize only returns without a resize in progress once the table is big enough
+            if self.size_ctl.load(Ordering::SeqCst) >= 0 {
+                return;
+            }
+
+            // another thread is initializing or resizing the table, so help it along first
+            let table = self.table.load(Ordering::SeqCst, guard);
+            self.help_transfer(table, guard);
+            std::thread::yield_now();
+        }
+    }
+}
+
+// ===
+// the following methods never introduce new items (so they do not need the thread-safety bounds),
+// but they _do_ perform lookups, which require hashing and equality.
+// ===
+
+impl<K, V, S> HashMap<K, V, S>
+where
+    K: Hash + Ord,
+    S: BuildHasher,
+{
+    #[inline]
+    pub(crate) fn hash<Q: ?Sized + Hash>(&self, key: &Q) -> u64 {
+        let mut h = self.build_hasher.build_hasher();
+        key.hash(&mut h);
+        h.finish()
+    }
+
+    fn get_node<'g, Q>(&'g self, key: &Q, guard: &'g Guard<'_>) -> Option<&'g Node<K, V>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Ord,
+    {
+        let table = self.table.load(Ordering::SeqCst, guard);
+        if table.is_null() {
+            return None;
+        }
+
+        // safety: we loaded the table while holding a guard.
+        // table won't be deallocated until we drop our guard
+        // at the earliest.
+        let table = unsafe { table.deref() };
+        if table.is_empty() {
+            return None;
+        }
+
+        let h = self.hash(key);
+        let bini = table.bini(h);
+        let bin = table.bin(bini, guard);
+        if bin.is_null() {
+            return None;
+        }
+
+        // safety: bin is a valid pointer.
+        //
+        // there are three cases when a bin pointer is invalidated:
+        //
+        //  1. if the table was resized, bin is a move entry, and the resize has completed. in
+        //     that case, the table (and all its heads) have already been retired.
+        //  2. if the table is being resized, bin may be swapped with a move entry. the old bin
+        //     will only be retired after that happens.
+        //  3. when elements are inserted into or removed from the map, bin may be changed into
+        //     or from a TreeBin from or into a regular, linear bin. the old bin will be
+        //     retired only once that happens.
+        //
+        // in all cases, we held the guard when we got the reference to the bin. if any such
+        // swap happened, it must have happened _after_ we read. since we did the read while
+        // the current thread was marked as active, we must be included in the reference count,
+        // and the drop must happen _after_ we decrement the count (i.e drop our guard).
+        let node = table.find(unsafe { bin.deref() }, h, key, guard);
+        if node.is_null() {
+            return None;
+        }
+
+        // safety: we loaded the bin while holding a guard, so any retirements
+        // must have seen us as active, and any future retirements must see us as active.
+        // the bin and its nodes cannot be dropped until at least after we drop our guard.
+        let node = unsafe { node.deref() };
+        Some(match **node {
+            BinEntry::Node(ref n) => n,
+            BinEntry::TreeNode(ref tn) => &tn.node,
+            _ => panic!("`Table::find` should always return a Node"),
+        })
+    }
+
+    /// Like `get_key_value`, but with a caller-supplied `hash`, and `is_match` in place of key
+    /// comparison.
+    ///
+    /// Since `is_match` says nothing about the ordering of keys, tree bins are searched linearly.
+    pub(crate) fn get_key_value_hashed<'g, F>(
+        &'g self,
+        hash: u64,
+        mut is_match: F,
+        guard: &'g Guard<'_>,
+    ) -> Option<(&'g K, &'g V)>
+    where
+        F: FnMut(&K) -> bool,
+    {
+        self.check_guard(guard);
+        let mut table = self.table.load(Ordering::SeqCst, guard);
+        loop {
+            if table.is_null() {
+                return None;
+            }
+            // safety: we loaded the table while holding a guard, and any later table is only
+            // retired after the tables that point to it.
+            let t = unsafe { table.deref() };
+            if t.is_empty() {
+                return None;
+            }
+            let bin = t.bin(t.bini(hash), guard);
+            if bin.is_null() {
+                return None;
+            }
+
+            // safety: the bin was read under the guard; see `get_node`.
+            let mut node = match **unsafe { bin.deref() } {
+                BinEntry::Moved => {
+                    table = t.next_table(guard);
+                    continue;
+                }
+                BinEntry::Reservation(_) => return None,
+                BinEntry::Tree(ref tree_bin) => tree_bin.first.load(Ordering::SeqCst, guard),
+                BinEntry::Node(_) | BinEntry::TreeNode(_) => bin,
+            };
+
+            while !node.is_null() {
+                // safety: nodes are only retired after the bin they belong to, which in turn is
+                // protected by the guard.
+                let n = match **unsafe { node.deref() } {
+                    BinEntry::Node(ref n) => n,
+                    BinEntry::TreeNode(ref tn) => &tn.node,
+                    _ => unreachable!("bins only link to nodes"),
+                };
+                if n.hash == hash && is_match(&n.key) {
+                    let v = n.value.load(Ordering::SeqCst, guard);
+                    assert!(!v.is_null());
+                    // safety: the value is protected by the guard, just like the node.
+                    return Some((&n.key, &**unsafe { v.deref() }));
+                }
+                node = n.next.load(Ordering::SeqCst, guard);
+            }
+            return None;
+        }
+    }
+
+    /// Returns `true` if the map contains a value for the specified key.
+    ///
+    /// The key may be any borrowed form of the map's key type, but
+    /// [`Hash`] and [`Ord`] on the borrowed form *must* match those for
+    /// the key type.
+    ///
+    /// [`Ord`]: std::cmp::Ord
+    /// [`Hash`]: std::hash::Hash
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// let mref = map.pin();
+    /// mref.insert(1, "a");
+    /// assert_eq!(mref.contains_key(&1), true);
+    /// assert_eq!(mref.contains_key(&2), false);
+    /// ```
+    pub fn contains_key<Q>(&self, key: &Q, guard: &Guard<'_>) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Ord,
+    {
+        self.check_guard(guard);
+        self.get(key, guard).is_some()
+    }
+
+    /// Returns a reference to the value corresponding to the key.
+    ///
+    /// The key may be any borrowed form of the map's key type, but
+    /// [`Hash`] and [`Ord`] on the borrowed form *must* match those for
+    /// the key type.
+    ///
+    /// [`Ord`]: std::cmp::Ord
+    /// [`Hash`]: std::hash::Hash
+    ///
+    /// To obtain a `Guard`, use [`HashMap::guard`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// let mref = map.pin();
+    /// mref.insert(1, "a");
+    /// assert_eq!(mref.get(&1), Some(&"a"));
+    /// assert_eq!(mref.get(&2), None);
+    /// ```
+    #[inline]
+    pub fn get<'g, Q>(&'g self, key: &Q, guard: &'g Guard<'_>) -> Option<&'g V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Ord,
+    {
+        self.check_guard(guard);
+        let node = self.get_node(key, guard)?;
+
+        let v = node.value.load(Ordering::SeqCst, guard);
+        assert!(!v.is_null());
+        // safety: the lifetime of the reference is bound to the guard
+        // supplied which means that the memory will not be modified
+        // until at least after the guard goes out of scope
+        unsafe { v.as_ref().map(|linked| &**linked) }
+    }
+
+    /// Returns the key-value pair corresponding to `key`.
+    ///
+    /// Returns `None` if this map contains no mapping for `key`.
+    ///
+    /// The key may be any borrowed form of the map's key type, but
+    /// [`Hash`] and [`Ord`] on the borrowed form *must* match those for
+    /// the key type.
+    ///
+    /// [`Ord`]: std::cmp::Ord
+    /// [`Hash`]: std::hash::Hash
+    #[inline]
+    pub fn get_key_value<'g, Q>(&'g self, key: &Q, guard: &'g Guard<'_>) -> Option<(&'g K, &'g V)>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Ord,
+    {
+        self.check_guard(guard);
+        let node = self.get_node(key, guard)?;
+
+        let v = node.value.load(Ordering::SeqCst, guard);
+        assert!(!v.is_null());
+        // safety: the lifetime of the reference is bound to the guard
+        // supplied which means that the memory will not be modified
+        // until at least after the guard goes out of scope
+        unsafe { v.as_ref() }.map(|v| (&node.key, &**v))
+    }
+
+    /// Returns a reference to the key stored in the map for `key`.
+    ///
+    /// Returns `None` if this map contains no mapping for `key`. This is like
+    /// [`get_key_value`](HashMap::get_key_value), but does not look at the value, which is handy
+    /// when the map is used to intern keys and only the stored instance is of interest.
+    ///
+    /// The key may be any borrowed form of the map's key type, but
+    /// [`Hash`] and [`Ord`] on the borrowed form *must* match those for
+    /// the key type.
+    ///
+    /// [`Ord`]: std::cmp::Ord
+    /// [`Hash`]: std::hash::Hash
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    /// use std::sync::Arc;
+    ///
+    /// let interned = HashMap::new();
+    /// let guard = interned.guard();
+    /// interned.insert(Arc::<str>::from("flurry"), (), &guard);
+    ///
+    /// let a = interned.get_key("flurry", &guard).unwrap();
+    /// let b = interned.get_key("flurry", &guard).unwrap();
+    /// assert!(Arc::ptr_eq(a, b));
+    /// assert_eq!(interned.get_key("other", &guard), None);
+    /// ```
+    #[inline]
+    pub fn get_key<'g, Q>(&'g self, key: &Q, guard: &'g Guard<'_>) -> Option<&'g K>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Ord,
+    {
+        self.check_guard(guard);
+        self.get_node(key, guard).map(|node| &node.key)
+    }
+
+    /// Returns a clone of the value corresponding to the key.
+    ///
+    /// This is like [`get_cloned`](HashMap::get_cloned), but uses a guard that the caller already
+    /// holds instead of pinning the map for every call.
+    ///
+    /// The key may be any borrowed form of the map's key type, but
+    /// [`Hash`] and [`Ord`] on the borrowed form *must* match those for
+    /// the key type.
+    ///
+    /// [`Ord`]: std::cmp::Ord
+    /// [`Hash`]: std::hash::Hash
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// let guard = map.guard();
+    /// map.insert(1, String::from("a"), &guard);
+    ///
+    /// let value = map.get_cloned_guarded(&1, &guard);
+    /// drop(guard);
+    /// assert_eq!(value, Some(String::from("a")));
+    /// ```
+    #[inline]
+    pub fn get_cloned_guarded<Q>(&self, key: &Q, guard: &Guard<'_>) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Ord,
+        V: Clone,
+    {
+        self.get(key, guard).cloned()
+    }
+
+    /// Creates a builder for looking up an entry by a precomputed `hash`.
+    ///
+    /// This is useful when the hash of a key is already known, for example because it was
+    /// obtained from [`iter_hashed`](HashMap::iter_hashed), or because the key is only available
+    /// in a form that cannot be borrowed from `K`. The builder then finds the entry by comparing
+    /// keys with a caller-supplied closure instead of [`Ord`].
+    ///
+    /// `hash` must be the hash that the map's [`hasher`](HashMap::hasher) computes for the key
+    /// that is looked up; otherwise the lookup will not find it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// let guard = map.guard();
+    /// map.insert(1, "a", &guard);
+    ///
+    /// let (hash, key, _) = map.iter_hashed(&guard).next().unwrap();
+    /// assert_eq!(map.raw_entry(hash, &guard).from_key(key), Some((&1, &"a")));
+    /// ```
+    pub fn raw_entry<'g>(
+        &'g self,
+        hash: u64,
+        guard: &'g Guard<'_>,
+    ) -> RawEntryBuilder<'g, K, V, S> {
+        self.check_guard(guard);
+        RawEntryBuilder::new(self, hash, guard)
+    }
+
+    /// Returns references to the values corresponding to each of the given `keys`.
+    ///
+    /// This is equivalent to calling [`HashMap::get`] for each key with the same guard. The
+    /// lookups are independent, so the results are not a consistent snapshot if the map is
+    /// modified concurrently. Duplicate keys are allowed.
+    ///
+    /// The key may be any borrowed form of the map's key type, but
+    /// [`Hash`] and [`Ord`] on the borrowed form *must* match those for
+    /// the key type.
+    ///
+    /// [`Ord`]: std::cmp::Ord
+    /// [`Hash`]: std::hash::Hash
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// let guard = map.guard();
+    /// map.insert(1, "a", &guard);
+    /// map.insert(2, "b", &guard);
+    /// assert_eq!(
+    ///     map.get_many([&1, &3, &2], &guard),
+    ///     [Some(&"a"), None, Some(&"b")]
+    /// );
+    /// ```
+    pub fn get_many<'g, Q, const N: usize>(
+        &'g self,
+        keys: [&Q; N],
+        guard: &'g Guard<'_>,
+    ) -> [Option<&'g V>; N]
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Ord,
+    {
+        self.check_guard(guard);
+        keys.map(|key| self.get(key, guard))
+    }
+
+    /// Returns `true` if the map contains a value for the specified key, pinning the current
+    /// thread only for the duration of the lookup.
+    ///
+    /// This is a convenience for one-off lookups. Every call pins and unpins the current thread,
+    /// which is considerably more expensive than the lookup itself for small maps, so if you are
+    /// doing several operations in a row, reuse a single guard or [`HashMapRef`](crate::HashMapRef)
+    /// and call [`HashMap::contains_key`] instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// map.pin().insert(1, "a");
+    /// assert!(map.contains_key_pinned(&1));
+    /// assert!(!map.contains_key_pinned(&2));
+    /// ```
+    pub fn contains_key_pinned<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Ord,
+    {
+        self.contains_key(key, &self.guard())
+    }
+
+    /// Returns a clone of the value corresponding to the key, pinning the current thread only for
+    /// the duration of the lookup.
+    ///
+    /// Since the value is cloned, it can be used after the guard is gone. See
+    /// [`HashMap::contains_key_pinned`] for the cost of pinning on every call; callers that
+    /// already hold a guard can use [`HashMap::get_cloned_guarded`] instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// map.pin().insert(1, String::from("a"));
+    /// assert_eq!(map.get_cloned(&1), Some(String::from("a")));
+    /// assert_eq!(map.get_cloned(&2), None);
+    /// ```
+    pub fn get_cloned<Q>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Ord,
+        V: Clone,
+    {
+        self.get(key, &self.guard()).cloned()
+    }
+
+    /// Returns `true` if this map and `other` contain the same key-value pairs, using guards that
+    /// the caller already holds for each of the two maps.
+    ///
+    /// This is what the [`PartialEq`] implementation does, except that it does not have to pin
+    /// fresh guards for both maps.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let a = HashMap::new();
+    /// let b = HashMap::new();
+    /// let (guard_a, guard_b) = (a.guard(), b.guard());
+    ///
+    /// a.insert(1, "a", &guard_a);
+    /// b.insert(1, "a", &guard_b);
+    /// assert!(a.guarded_eq(&b, &guard_a, &guard_b));
+    ///
+    /// b.insert(2, "b", &guard_b);
+    /// assert!(!a.guarded_eq(&b, &guard_a, &guard_b));
+    /// ```
+    pub fn guarded_eq(&self, other: &Self, our_guard: &Guard<'_>, their_guard: &Guard<'_>) -> bool
+    where
+        V: PartialEq,
+    {
+        self.check_guard(our_guard);
+        other.check_guard(their_guard);
+        if self.len() != other.len() {
+            return false;
+        }
+
+        self.iter(our_guard)
+            .all(|(key, value)| other.get(key, their_guard).map_or(false, |v| *value == *v))
+    }
+
+    pub(crate) fn std_eq<S2>(
+        &self,
+        other: &std::collections::HashMap<K, V, S2>,
+        guard: &Guard<'_>,
+    ) -> bool
+    where
+        V: PartialEq,
+        S2: BuildHasher,
+    {
+        if self.len() != other.len() {
+            return false;
+        }
+
+        self.iter(guard)
+            .all(|(key, value)| other.get(key).map_or(false, |v| *value == *v))
+    }
+}
+
+// ===
+// the following methods only ever _remove_ items, but never introduce them, so they do not need
+// the thread-safety bounds.
+// ===
+
+impl<K, V, S> HashMap<K, V, S>
+where
+    K: Clone + Ord,
+{
+    /// Clears the map, removing all key-value pairs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::new();
+    ///
+    /// map.pin().insert(1, "a");
+    /// map.pin().clear();
+    /// assert!(map.pin().is_empty());
+    /// ```
+    pub fn clear(&self, guard: &Guard<'_>) {
+        // Negative number of deletions
+        let mut delta = 0;
+        let mut idx = 0usize;
+
+        let mut table = self.table.load(Ordering::SeqCst, guard);
+        // Safety: self.table is a valid pointer because we checked it above.
+        while !table.is_null() && idx < unsafe { table.deref() }.len() {
+            let tab = unsafe { table.deref() };
+            let raw_node = tab.bin(idx, guard);
+            if raw_node.is_null() {
+                idx += 1;
+                continue;
+            }
+            // Safety: node is a valid pointer because we checked
+            // it in the above if stmt.
+            match **unsafe { raw_node.deref() } {
+                BinEntry::Moved => {
+                    table = self.help_transfer(table, guard);
+                    // start from the first bin again in the new table
+                    idx = 0;
+                }
+                BinEntry::Node(ref node) => {
+                    let head_lock = node.lock.lock();
+                    // need to check that this is _still_ the head
+                    let current_head = tab.bin(idx, guard);
+                    if current_head != raw_node {
+                        // nope -- try the bin again
+                        continue;
+                    }
+                    // we now own the bin
+                    // unlink it from the map to prevent others from entering it
+                    // NOTE: The Java code stores the null bin _after_ the loop, and thus also has
+                    // to hold the lock until that point. However, after the store happens new
+                    // threads and threads waiting on the lock will read the new bin, so we can
+                    // drop the lock early and do the counting and garbage collection outside the
+                    // critical section.
+                    tab.store_bin(idx, Shared::null());
+                    drop(head_lock);
+                    // next, walk the nodes of the bin and free the nodes and their values as we go
+                    // note that we do not free the head node yet, since we're holding the lock it contains
+                    let mut p = node.next.load(Ordering::SeqCst, guard);
+                    while !p.is_null() {
+                        delta -= 1;
+                        p = {
+                            // safety: we loaded p under guard, and guard is still pinned, so p has not been dropped.
+                            let node = unsafe { p.deref() }
+                                .as_node()
+                                .expect("entry following Node should always be a Node");
+                            let next = node.next.load(Ordering::SeqCst, guard);
+                            let value = node.value.load(Ordering::SeqCst, guard);
+                            // NOTE: do not use the reference in `node` after this point!
+
+                            // free the node's value
+                            // safety: any thread that sees this p's value must have read the bin before we stored null
+                            // into it above. it must also have already been marked as active. therefore, the
+                            // defer_destroy below won't be executed until that thread's guard is dropped, at which
+                            // point it holds no outstanding references to the value anyway.
+                            unsafe { guard.retire_shared(value) };
+                            // free the bin entry itself
+                            // safety: same argument as for value above.
+                            unsafe { guard.retire_shared(p) };
+                            next
+                        };
+                    }
+                    // finally, we can drop the head node and its value
+                    let value = node.value.load(Ordering::SeqCst, guard);
+                    // NOTE: do not use the reference in `node` after this point!
+                    // safety: same as the argument for being allowed to free the nodes beyond the head above
+                    unsafe { guard.retire_shared(value) };
+                    unsafe { guard.retire_shared(raw_node) };
+                    delta -= 1;
+                    idx += 1;
+                }
+                BinEntry::Tree(ref tree_bin) => {
+                    let bin_lock = tree_bin.lock.lock();
+                    // need to check that this is _still_ the correct bin
+                    let current_head = tab.bin(idx, guard);
+                    if current_head != raw_node {
+                        // nope -- try the bin again
+                        continue;
+                    }
+                    // we now own the bin
+                    // unlink it from the map to prevent others from entering it
+                    // NOTE: The Java code stores the null bin _after_ the loop, and thus also has
+                    // to hold the lock until that point. However, after the store happens new
+                    // threads and threads waiting on the lock will read the new bin, so we can
+                    // drop the lock early and do the counting and garbage collection outside the
+                    // critical section.
+                    tab.store_bin(idx, Shared::null());
+                    drop(bin_lock);
+                    // next, walk the nodes of the bin and count how many values we remove
+                    let mut p = tree_bin.first.load(Ordering::SeqCst, guard);
+                    while !p.is_null() {
+                        delta -= 1;
+                        p = {
+                            // safety: we read under our guard, at which point the tree
+                            // structure was valid. Since our guard marks the current thread
+                            // as active, the TreeNodes remain valid for at least as long as
+                            // we hold onto the guard.
+                            // Structurally, TreeNodes always point to TreeNodes, so this is sound.
+                            let tree_node = unsafe { TreeNode::get_tree_node(p) };
+                            // NOTE: we do not drop the TreeNodes or their
+                            // values here, since they will be dropped together
+                            // with the containing TreeBin (`tree_bin`) in its
+                            // `drop`
+                            tree_node.node.next.load(Ordering::SeqCst, guard)
+                        };
+                    }
+                    // safety: same as in the BinEntry::Node case above
+                    unsafe { guard.retire_shared(raw_node) };
+                    idx += 1;
+                }
+                BinEntry::TreeNode(_) => unreachable!(
+                    "The head of a bin cannot be a TreeNode directly without BinEntry::Tree"
+                ),
+                BinEntry::Reservation(_) => {
+                    tab.wait_for_reservation(idx, raw_node, guard);
+                    continue;
+                }
+            };
+        }
+
+        if delta != 0 {
+            self.add_count(delta, None, guard);
+        }
+    }
+
+    /// Clears the map by swapping in a fresh, empty table of the same capacity.
+    ///
+    /// Unlike [`HashMap::clear`], which hands every removed entry to the garbage collector
+    /// individually, this retires all entries of the old table at once, which makes clearing a
+    /// large map much cheaper. Readers that are still traversing the old table are unaffected:
+    /// like any other garbage, the old entries are only dropped once all guards that may have
+    /// observed them are gone.
+    ///
+    /// While the map is being cleared, it cannot be resized, and writers that reach a bin which
+    /// has already been cleared continue in the new table. Entries that are inserted concurrently
+    /// may or may not be cleared.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// let guard = map.guard();
+    /// for i in 0..100 {
+    ///     map.insert(i, i, &guard);
+    /// }
+    ///
+    /// let capacity = map.capacity(&guard);
+    /// map.clear_fast(&guard);
+    /// assert!(map.is_empty());
+    /// assert_eq!(map.capacity(&guard), capacity);
+    /// ```
+    pub fn clear_fast(&self, guard: &Guard<'_>) {
+        self.check_guard(guard);
+        let table = loop {
+            let table = self.table.load(Ordering::SeqCst, guard);
+            if table.is_null() {
+                // the table has not been allocated yet, so there is nothing to clear
+                return;
+            }
+
+            let size_ctl = self.size_ctl.load(Ordering::SeqCst);
+            if size_ctl < 0 {
+                // the table is currently being resized. help out if we can, otherwise wait for the
+                // resize to finish before we try to claim it ourselves.
+                if self.help_transfer(table, guard) == table {
+                    std::thread::yield_now();
+                }
+                continue;
+            }
+
+            // claim the resize, just like `shrink_to` does, so that we are the only thread that
+            // moves bins out of the table.
+            // safety: table was read while `guard` was held, so it has not been dropped.
+            let n = unsafe { table.deref() }.len();
+            let rs = Self::resize_stamp(n) << RESIZE_STAMP_SHIFT;
+            if self
+                .size_ctl
+                .compare_exchange(size_ctl, rs + 1, Ordering::SeqCst, Ordering::Relaxed)
+                .is_err()
+            {
+                continue;
+            }
+            if self.table.load(Ordering::SeqCst, guard) != table {
+                // the table was resized after we read it; release the resize and try again
+                self.size_ctl.store(size_ctl, Ordering::SeqCst);
+                continue;
+            }
+            break table;
+        };
+
+        // safety: see above. we hold the resize, so the table cannot be replaced under us.
+        let t = unsafe { table.deref() };
+        let n = t.len();
+        let next_table_ptr = Shared::boxed(Table::new(n, &self.collector), &self.collector);
+        let now_garbage = self
+            .next_table
+            .swap(next_table_ptr, Ordering::SeqCst, guard);
+        assert!(now_garbage.is_null());
+        // no other thread may help with this resize
+        self.transfer_index.store(0, Ordering::SeqCst);
+
+        // the bins we take out of the table are collected in a table of their own, which is never
+        // shared, and which is retired along with all of its entries once we are done.
+        let graveyard = Table::new(n, &self.collector);
+        let mut removed = 0;
+        let mut i = n;
+        while i > 0 {
+            let bini = i - 1;
+            let bin = t.bin(bini, guard);
+            if bin.is_null() {
+                if t.cas_bin(
+                    bini,
+                    Shared::null(),
+                    t.get_moved(next_table_ptr, guard),
+                    guard,
+                )
+                .is_ok()
+                {
+                    i -= 1;
+                }
+                continue;
+            }
+
+            // safety: bin is a valid pointer. see the equivalent comment in `transfer`.
+            match **unsafe { bin.deref() } {
+                BinEntry::Moved => {
+                    unreachable!("only the clearing thread moves bins, and it moves each bin once")
+                }
+                BinEntry::Node(ref head) => {
+                    let head_lock = head.lock.lock();
+                    // need to check that this is _still_ the head
+                    if t.bin(bini, guard) != bin {
+                        // nope -- try again
+                        continue;
+                    }
+                    t.store_bin(bini, t.get_moved(next_table_ptr, guard));
+                    drop(head_lock);
+
+                    graveyard.store_bin(bini, bin);
+                    let mut p = bin;
+                    while !p.is_null() {
+                        removed += 1;
+                        // safety: the nodes of the bin are only retired along with the graveyard
+                        // below, and we still hold our guard.
+                        p = unsafe { p.deref() }
+                            .as_node()
+                            .unwrap()
+                            .next
+                            .load(Ordering::SeqCst, guard);
+                    }
+                    i -= 1;
+                }
+                BinEntry::Tree(ref tree_bin) => {
+                    let bin_lock = tree_bin.lock.lock();
+                    // need to check that this is _still_ the correct bin
+                    if t.bin(bini, guard) != bin {
+                        // nope -- try again
+                        continue;
+                    }
+                    t.store_bin(bini, t.get_moved(next_table_ptr, guard));
+                    drop(bin_lock);
+
+                    graveyard.store_bin(bini, bin);
+                    let mut p = tree_bin.first.load(Ordering::SeqCst, guard);
+                    while !p.is_null() {
+                        removed += 1;
+                        // safety: same as for BinEntry::Node
+                        p = unsafe { TreeNode::get_tree_node(p) }
+                            .node
+                            .next
+                            .load(Ordering::SeqCst, guard);
+                    }
+                    i -= 1;
+                }
+                BinEntry::TreeNode(_) => unreachable!(
+                    "The head of a bin cannot be a TreeNode directly without BinEntry::Tree"
+                ),
+                BinEntry::Reservation(_) => {
+                    t.wait_for_reservation(bini, bin, guard);
+                    continue;
+                }
+            }
+        }
+
+        self.next_table.store(Shared::null(), Ordering::SeqCst);
+        let now_garbage = self.table.swap(next_table_ptr, Ordering::SeqCst, guard);
+        // safety: the argument is the same as for the old table at the end of `transfer`. the
+        // old table now only contains BinEntry::Moved, so retiring it does not free any entries.
+        unsafe { guard.retire_shared(now_garbage) };
+
+        // safety: the bins in the graveyard are no longer reachable from the map, since the old
+        // table that contained them has just been retired, and their bins were replaced by
+        // BinEntry::Moved before that. any thread that still holds a reference into them must
+        // have obtained it while marked as active, so it is included in the reference count.
+        let graveyard = Shared::boxed(graveyard, &self.collector);
+        unsafe {
+            guard.retire(graveyard.as_ptr(), |mut link| {
+                // safety: `graveyard` is a `Table<K, V>`, and `retire` guarantees that we have
+                // unique access to it at this point, and so also to all of its entries.
+                let mut table = Linked::into_inner(*Box::from_raw(link.cast::<Table<K, V>>()));
+                table.drop_bins();
+            })
+        };
+
+        self.size_ctl
+            .store(self.resize_threshold(n), Ordering::SeqCst);
+        self.add_count(-removed, None, guard);
+    }
+}
+
+// ===
+// the following methods _do_ introduce items into the map, and so must require that the keys and
+// values are thread safe, and can be garbage collected at a later time.
+// ===
+
+impl<K, V, S> HashMap<K, V, S>
+where
+    K: Sync + Send + Clone + Hash + Ord,
+    V: Sync + Send,
+    S: BuildHasher,
+{
+    /// Inserts a key-value pair into the map.
+    ///
+    /// If the map did not have this key present, [`None`] is returned.
+    ///
+    /// If the map did have this key present, the value is updated, and the old
+    /// value is returned. The key is left unchanged. See the [std-collections
+    /// documentation] for more.
+    ///
+    /// [`None`]: std::option::Option::None
+    /// [std-collections documentation]: https://doc.rust-lang.org/std/collections/index.html#insert-and-complex-keys
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// assert_eq!(map.pin().insert(37, "a"), None);
+    /// assert_eq!(map.pin().is_empty(), false);
+    ///
+    /// // you can also re-use a map pin like so:
+    /// let mref = map.pin();
+    ///
+    /// mref.insert(37, "b");
+    /// assert_eq!(mref.insert(37, "c"), Some(&"b"));
+    /// assert_eq!(mref.get(&37), Some(&"c"));
+    /// ```
+    pub fn insert<'g>(&'g self, key: K, value: V, guard: &'g Guard<'_>) -> Option<&'g V> {
+        self.check_guard(guard);
+        self.put(key, value, false, guard).before()
+    }
+
+    /// Inserts a key-value pair into the map, and returns a clone of the value it replaced (if
+    /// any).
+    ///
+    /// As with [`HashMap::remove_owned`], other threads may still be reading the replaced value,
+    /// so it cannot be moved out of the map. It is cloned instead, and the original is dropped
+    /// once no guard can observe it any more. If you only need the old value while `guard` is
+    /// held, [`HashMap::insert`] avoids the clone.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// let guard = map.guard();
+    ///
+    /// assert_eq!(map.insert_owned(37, String::from("a"), &guard), None);
+    /// let old = map.insert_owned(37, String::from("b"), &guard);
+    /// drop(guard);
+    /// assert_eq!(old, Some(String::from("a")));
+    /// ```
+    pub fn insert_owned(&self, key: K, value: V, guard: &Guard<'_>) -> Option<V>
+    where
+        V: Clone,
+    {
+        self.insert(key, value, guard).cloned()
+    }
+
+    /// Inserts a key-value pair into the map, and reports whether doing so moved bins for a
+    /// resize.
+    ///
+    /// This behaves exactly like [`insert`](HashMap::insert), and the first element of the
+    /// returned tuple is what `insert` would have returned. An insert that runs into a resize,
+    /// or that pushes the map past its resize threshold, helps move bins to the new table before
+    /// it returns, which can take a while for large maps. The second element is `true` if this
+    /// call did any of that work.
+    ///
+    /// The transfer itself cannot be paused, but an async caller on a shared executor can use
+    /// the flag to yield (for example with `tokio::task::yield_now().await`) after an insert
+    /// that did resize work, so that a single task does not keep a worker thread busy with
+    /// several resizes in a row.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::with_capacity(16);
+    /// let guard = map.guard();
+    ///
+    /// let mut helped = false;
+    /// for i in 0..64 {
+    ///     let (old, resized) = map.insert_cooperative(i, i, &guard);
+    ///     assert_eq!(old, None);
+    ///     helped |= resized;
+    /// }
+    /// // the map had to grow, and this thread was the only one around to do it
+    /// assert!(helped);
+    /// ```
+    pub fn insert_cooperative<'g>(
+        &'g self,
+        key: K,
+        value: V,
+        guard: &'g Guard<'_>,
+    ) -> (Option<&'g V>, bool) {
+        self.check_guard(guard);
+        let before = TRANSFER_STRIDES.with(|strides| strides.get());
+        let old = self.put(key, value, false, guard).before();
+        let after = TRANSFER_STRIDES.with(|strides| strides.get());
+        (old, after != before)
+    }
+
+    /// Inserts a key-value pair into the map unless the key already exists.
+    ///
+    /// If the map does not contain the key, the key-value pair is inserted
+    /// and this method returns `Ok`.
+    ///
+    /// If the map does contain the key, the map is left unchanged and this
+    /// method returns `Err`.
+    ///
+    /// [std-collections documentation]: https://doc.rust-lang.org/std/collections/index.html#insert-and-complex-keys
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::{HashMap, TryInsertError};
+    ///
+    /// let map = HashMap::new();
+    /// let mref = map.pin();
+    ///
+    /// mref.insert(37, "a");
+    /// assert_eq!(
+    ///     mref.try_insert(37, "b"),
+    ///     Err(TryInsertError { current: &"a", not_inserted: &"b"})
+    /// );
+    /// assert_eq!(mref.try_insert(42, "c"), Ok(&"c"));
+    /// assert_eq!(mref.get(&37), Some(&"a"));
+    /// assert_eq!(mref.get(&42), Some(&"c"));
+    /// ```
+    #[inline]
+    pub fn try_insert<'g>(
+        &'g self,
+        key: K,
+        value: V,
+        guard: &'g Guard<'_>,
+    ) -> Result<&'g V, TryInsertError<'g, V>> {
+        match self.put(key, value, true, guard) {
+            PutResult::Exists {
+                current,
+                not_inserted,
+            } => Err(TryInsertError {
+                current,
+                not_inserted: Linked::into_inner(*not_inserted),
+            }),
+            PutResult::Inserted { new } => Ok(new),
+            PutResult::Replaced { .. } => {
+                unreachable!("no_replacement cannot result in PutResult::Replaced")
+            }
+        }
+    }
+
+    /// Inserts a key-value pair into the map, returning an error instead of aborting if the
+    /// table that the insert would have to allocate cannot be allocated.
+    ///
+    /// This behaves like [`insert`](HashMap::insert), except that if the insert may allocate a
+    /// new table, it first checks that the memory for the largest table it may allocate is
+    /// available. If it is not, the map is left unchanged, and [`TryReserveError::AllocError`] is
+    /// returned; `key` and `value` are dropped. An insert may allocate the map's first table, push
+    /// the map past its resize threshold, or, while the table is small, make a bin long enough
+    /// that the table is grown instead. If another thread is resizing the map, the insert helps
+    /// finish that resize, which does not allocate, and then checks the new table's threshold.
+    ///
+    /// Since the largest table is checked, the check may fail even if the table the insert ends
+    /// up allocating would fit.
+    ///
+    /// Note that this only covers the bin table, which is by far the largest allocation an insert
+    /// can make. The entry itself is still allocated infallibly, and the check cannot account for
+    /// memory that other threads allocate between the check and the resize.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// let guard = map.guard();
+    ///
+    /// assert_eq!(map.try_insert_alloc(37, "a", &guard), Ok(None));
+    /// assert_eq!(map.try_insert_alloc(37, "b", &guard), Ok(Some(&"a")));
+    /// ```
+    pub fn try_insert_alloc<'g>(
+        &'g self,
+        key: K,
+        value: V,
+        guard: &'g Guard<'_>,
+    ) -> Result<Option<&'g V>, TryReserveError> {
+        self.check_guard(guard);
+        let sc = self.size_ctl.load(Ordering::SeqCst);
+        let n = self.capacity(guard);
+        // the largest table the insert may allocate
+        let largest = if n == 0 {
+            // the insert will allocate the initial table, which size_ctl holds the size of
+            if sc > 0 {
+                sc as usize
+            } else {
+                DEFAULT_CAPACITY
+            }
+        } else if n < MIN_TREEIFY_CAPACITY {
+            // if the insert makes its bin too long, `treeify_bin` presizes the table instead of
+            // treeifying the bin, which may grow it by more than one step
+            self.capacity_for(n << 1)
+        } else if sc < 0 {
+            // a resize is in progress. helping to finish it does not allocate, but the insert may
+            // then start the next resize, of the table that is twice the current size
+            n.saturating_mul(4)
+        } else if self.len() as isize + 1 >= sc {
+            // the insert may start a resize, which allocates a table twice the current size
+            n << 1
+        } else {
+            0
+        };
+        let largest = std::cmp::min(largest, MAXIMUM_CAPACITY);
+        if largest > n {
+            Self::try_alloc_bins(largest)?;
+        }
+        Ok(self.put(key, value, false, guard).before())
+    }
+
+    /// Returns a reference to the value for `key`, inserting `value` first if the key is not
+    /// already present.
+    ///
+    /// Unlike [`HashMap::try_insert`], this does not consider an existing value an error. If the
+    /// key is present, `value` is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// let guard = map.guard();
+    ///
+    /// assert_eq!(map.get_or_insert(37, "a", &guard), &"a");
+    /// assert_eq!(map.get_or_insert(37, "b", &guard), &"a");
+    /// ```
+    pub fn get_or_insert<'g>(&'g self, key: K, value: V, guard: &'g Guard<'_>) -> &'g V {
+        self.check_guard(guard);
+        match self.put(key, value, true, guard) {
+            PutResult::Exists { current, .. } => current,
+            PutResult::Inserted { new } => new,
+            PutResult::Replaced { .. } => {
+                unreachable!("no_replacement cannot result in PutResult::Replaced")
+            }
+        }
+    }
+
+    /// Returns a reference to the value for `key`, inserting the result of `f` first if the key
+    /// is not already present.
+    ///
+    /// `f` is only called if the key is absent, and at most once. This has the same atomicity
+    /// guarantees (and caveats) as [`HashMap::compute_if_absent`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// let guard = map.guard();
+    ///
+    /// assert_eq!(map.get_or_insert_with(37, || "a", &guard), &"a");
+    /// assert_eq!(map.get_or_insert_with(37, || unreachable!(), &guard), &"a");
+    /// ```
+    pub fn get_or_insert_with<'g, F>(&'g self, key: K, f: F, guard: &'g Guard<'_>) -> &'g V
+    where
+        F: FnOnce() -> V,
+    {
+        self.compute_if_absent(key, |_| f(), guard)
+    }
+
+    /// Returns a reference to the value for `key`, inserting the result of calling `f` with the
+    /// key first if the key is not already present.
+    ///
+    /// This is [`HashMap::get_or_insert_with`] for values that depend on their key. `f` is only
+    /// called if the key is absent, and at most once. This has the same atomicity guarantees (and
+    /// caveats) as [`HashMap::compute_if_absent`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// let guard = map.guard();
+    ///
+    /// assert_eq!(map.get_or_insert_with_key("a", |k| k.len(), &guard), &1);
+    /// assert_eq!(map.get_or_insert_with_key("a", |_| unreachable!(), &guard), &1);
+    /// ```
+    pub fn get_or_insert_with_key<'g, F>(&'g self, key: K, f: F, guard: &'g Guard<'_>) -> &'g V
+    where
+        F: FnOnce(&K) -> V,
+    {
+        self.compute_if_absent(key, f, guard)
+    }
+
+    /// Returns a reference to the value for `key`, inserting the result of `f` first if the key
+    /// is not already present and `f` succeeds.
+    ///
+    /// This is [`HashMap::get_or_insert_with`] for values whose construction may fail. `f` is only
+    /// called if the key is absent, and at most once. It runs while the bin that `key` falls into
+    /// is locked, so if another thread inserts `key` first, its value is returned and `f` is not
+    /// called. If `f` returns an error, the map is left unchanged and the error is returned.
+    ///
+    /// # Panics
+    ///
+    /// Like [`HashMap::compute_if_absent`], this method panics if `f` modifies the map in the
+    /// bin that is locked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// let guard = map.guard();
+    ///
+    /// assert_eq!(map.get_or_try_insert_with("a", || "1".parse::<i32>(), &guard), Ok(&1));
+    /// assert!(map.get_or_try_insert_with("b", || "x".parse::<i32>(), &guard).is_err());
+    /// assert_eq!(map.len(), 1);
+    ///
+    /// // the key is present, so `f` is not called
+    /// assert_eq!(map.get_or_try_insert_with("a", || "x".parse::<i32>(), &guard), Ok(&1));
+    /// ```
+    pub fn get_or_try_insert_with<'g, F, E>(
+        &'g self,
+        key: K,
+        f: F,
+        guard: &'g Guard<'_>,
+    ) -> Result<&'g V, E>
+    where
+        F: FnOnce() -> Result<V, E>,
+    {
+        self.check_guard(guard);
+        self.try_compute_if_absent(key, |_| f(), guard)
+    }
+
+    /// Gets the given key's corresponding entry in the map for in-place manipulation.
+    ///
+    /// The returned [`Entry`] reflects whether the key was present at the time of the lookup.
+    /// Since other threads may concurrently modify the map, operations on the entry that insert
+    /// a value go through the same bin-locking path as [`HashMap::try_insert`], and so never
+    /// overwrite a value that another thread inserted in the meantime.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map: HashMap<&str, usize> = HashMap::new();
+    /// let guard = map.guard();
+    ///
+    /// for word in "the quick brown fox jumps over the lazy dog the end".split(' ') {
+    ///     map.entry(word, &guard).and_modify(|_, n| n + 1).or_insert(1);
+    /// }
+    ///
+    /// assert_eq!(map.get("the", &guard), Some(&3));
+    /// assert_eq!(map.get("fox", &guard), Some(&1));
+    /// ```
+    pub fn entry<'g>(&'g self, key: K, guard: &'g Guard<'_>) -> Entry<'g, K, V, S> {
+        self.check_guard(guard);
+        let value = self.get(&key, guard);
+        Entry::new(key, value, self, guard)
+    }
+
+    /// Creates a builder for looking up or inserting an entry by a precomputed `hash`.
+    ///
+    /// This is the writing counterpart of [`raw_entry`](HashMap::raw_entry). Like with
+    /// [`entry`](HashMap::entry), inserting through the returned entry never overwrites a value
+    /// that another thread inserted in the meantime.
+    ///
+    /// `hash` must be the hash that the map's [`hasher`](HashMap::hasher) computes for the key
+    /// that is looked up or inserted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::{HashMap, RawEntryMut};
+    /// use std::hash::{BuildHasher, Hash, Hasher};
+    ///
+    /// let map: HashMap<String, usize> = HashMap::new();
+    /// let guard = map.guard();
+    ///
+    /// for word in "the quick brown fox jumps over the lazy dog".split(' ') {
+    ///     let mut hasher = map.hasher().build_hasher();
+    ///     word.hash(&mut hasher);
+    ///     let hash = hasher.finish();
+    ///
+    ///     match map.raw_entry_mut(hash, &guard).from_key(word) {
+    ///         RawEntryMut::Occupied(entry) => assert_eq!(entry.key(), "the"),
+    ///         RawEntryMut::Vacant(entry) => {
+    ///             entry.insert(word.to_string(), word.len());
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(map.len(), 8);
+    /// assert_eq!(map.get("quick", &guard), Some(&5));
+    /// ```
+    pub fn raw_entry_mut<'g>(
+        &'g self,
+        hash: u64,
+        guard: &'g Guard<'_>,
+    ) -> RawEntryBuilderMut<'g, K, V, S> {
+        self.check_guard(guard);
+        RawEntryBuilderMut::new(self, hash, guard)
+    }
+
+    /// Inserts `key` with the precomputed `hash`, unless an equal key is already present.
+    ///
+    /// Returns a reference to the value for the key after the insert.
+    pub(crate) fn insert_hashed<'g>(
+        &'g self,
+        hash: u64,
+        key: K,
+        value: V,
+        guard: &'g Guard<'_>,
+    ) -> &'g V {
+        debug_assert_eq!(hash, self.hash(&key), "key does not have the given hash");
+        match self.put_inner(hash, key, value, true, true, guard) {
+            PutResult::Exists {
+                current,
+                not_inserted,
+            } => {
+                drop(Linked::into_inner(*not_inserted));
+                current
+            }
+            PutResult::Inserted { new } => new,
+            PutResult::Replaced { .. } => {
+                unreachable!("no_replacement cannot result in PutResult::Replaced")
+            }
+        }
+    }
+
+    fn put<'g>(
+        &'g self,
+        key: K,
+        value: V,
+        no_replacement: bool,
+        guard: &'g Guard<'_>,
+    ) -> PutResult<'g, V> {
+        self.put_inner(self.hash(&key), key, value, no_replacement, true, guard)
+    }
+
+    /// Like `put`, but only considers resizing the table after the insertion if `resize` is set.
+    ///
+    /// `hash` must be the hash of `key`. It is usually computed with `self.hash`, but
+    /// [`RawVacantEntryMut`] supplies one that was computed by the caller.
+    fn put_inner<'g>(
+        &'g self,
+        hash: u64,
+        mut key: K,
+        value: V,
+        no_replacement: bool,
+        resize: bool,
+        guard: &'g Guard<'_>,
+    ) -> PutResult<'g, V> {
+        let mut table = self.table.load(Ordering::SeqCst, guard);
+        let mut bin_count;
+        let value = Shared::boxed(value, &self.collector);
+        let mut old_val = None;
+        loop {
+            // safety: see argument below for !is_null case
+            if table.is_null() || unsafe { table.deref() }.is_empty() {
+                table = self.init_table(guard);
+                continue;
+            }
+
+            // safety: table is a valid pointer.
+            //
+            // we are in one of three cases:
+            //
+            //  1. if table is the one we read before the loop, then we read it while holding the
+            //     guard, so it won't be dropped until after we drop that guard b/c this thread must
+            //     have been included in the reference count of a retirement.
+            //
+            //  2. if table is read by init_table, we did so while holding a guard, so the
+            //     argument is as for point 1. or, we allocated the table while holding a guard,
+            //     so the earliest it can be deallocated is after we drop our guard.
+            //
+            //  3. if table is set by a Moved node (below) through help_transfer, it will _either_
+            //     keep using `table` (which is fine by 1. and 2.), or use the `next_table` raw
+            //     pointer from inside the Moved. to see that if a Moved(t) is _read_, then t must
+            //     still be valid, see the safety comment on Table.next_table.
+            let t = unsafe { table.deref() };
+
+            let bini = t.bini(hash);
+            let mut bin = t.bin(bini, guard);
+            if bin.is_null() {
+                // fast path -- bin is empty so stick us at the front
+                let node =
+                    Shared::boxed(BinEntry::Node(Node::new(hash, key, value)), &self.collector);
+                match t.cas_bin(bini, bin, node, guard) {
+                    Ok(_old_null_ptr) => {
+                        // safety: we just linked in the node while holding the guard, so it
+                        // cannot be dropped until we drop the guard.
+                        self.finish_unlocked_insert(unsafe { node.deref() }.as_node().unwrap());
+                        self.add_count(1, resize.then(|| 0), guard);
+                        // safety: we have not moved the node's value since we placed it into
+                        // its `Atomic` in the very beginning of the method, so the ref is still
+                        // valid. since the value is not currently marked as garbage, and since
+                        // `value` was loaded under a guard, the returned reference will remain valid
+                        // for the guard's lifetime.
+                        return PutResult::Inserted {
+                            new: unsafe { value.deref() },
+                        };
+                    }
+                    Err(changed) => {
+                        assert!(!changed.current.is_null());
+                        bin = changed.current;
+                        if let BinEntry::Node(node) =
+                            Linked::into_inner(*unsafe { changed.new.into_box() })
+                        {
+                            key = node.key;
+                        } else {
+                            unreachable!("we declared node and it is a BinEntry::Node");
+                        }
+                    }
+                }
+            }
+
+            // slow path -- bin is non-empty
+            //
+            // safety: bin is a valid pointer.
+            //
+            // there are three cases when a bin pointer is invalidated:
+            //
+            //  1. if the table was resized, bin is a move entry, and the resize has completed. in
+            //     that case, the table (and all its heads) have already been retired.
+            //  2. if the table is being resized, bin may be swapped with a move entry. the old bin
+            //     will only be retired after that happens.
+            //  3. when elements are inserted into or removed from the map, bin may be changed into
+            //     or from a TreeBin from or into a regular, linear bin. the old bin will be
+            //     retired only once that happens.
+            //
+            // in all cases, we held the guard when we got the reference to the bin. if any such
+            // swap happened, it must have happened _after_ we read. since we did the read while
+            // the current thread was marked as active, we must be included in the reference count,
+            // and the drop must happen _after_ we decrement the count (i.e drop our guard).
+            match **unsafe { bin.deref() } {
+                BinEntry::Moved => {
+                    table = self.help_transfer(table, guard);
+                    continue;
+                }
+                BinEntry::Node(ref head)
+                    if no_replacement && head.hash == hash && head.key == key =>
+                {
+                    // fast path if replacement is disallowed and first bin matches
+                    let v = head.value.load(Ordering::SeqCst, guard);
+                    // safety (for v): since the value is present now, and we've held a guard from
+                    // the beginning of the search, the value cannot be dropped after we drop our guard.
+                    // safety (for value): since we never inserted the value in the tree, `value`
+                    // is the last remaining pointer to the initial value.
+                    return PutResult::Exists {
+                        current: unsafe { v.deref() },
+                        not_inserted: unsafe { value.into_box() },
+                    };
+                }
+                BinEntry::Node(ref head) => {
+                    // bin is non-empty, need to link into it, so we must take the lock
+                    let head_lock = head.lock.lock();
+
+                    // need to check that this is _still_ the head
+                    let current_head = t.bin(bini, guard);
+                    if current_head != bin {
+                        // nope -- try again from the start
+                        continue;
+                    }
+
+                    // yes, it is still the head, so we can now "own" the bin
+                    // note that there can still be readers in the bin!
+
+                    bin_count = 1;
+                    let mut p = bin;
+
+                    old_val = loop {
+                        // safety: we loaded the bin while holding a guard, so any retirements
+                        // must have seen us as active. the bin and its nodes cannot be dropped
+                        // until at least after we drop our guard.
+                        let n = unsafe { p.deref() }.as_node().unwrap();
+                        if n.hash == hash && n.key == key {
+                            // the key already exists in the map!
+                            let current_value = n.value.load(Ordering::SeqCst, guard);
+
+                            // safety: since the value is present now, and we've held a guard from
+                            // the beginning of the search, the value cannot be dropped until after
+                            // we drop our guard.
+                            let current_value = unsafe { current_value.deref() };
+
+                            if no_replacement {
+                                // the key is not absent, so don't update because of
+                                // `no_replacement`, we don't use the new value, so we need to clean
+                                // it up and return it back to the caller
+                                // safety: we own value and did not share it
+                                return PutResult::Exists {
+                                    current: current_value,
+                                    not_inserted: unsafe { value.into_box() },
+                                };
+                            } else {
+                                // update the value in the existing node
+                                self.begin_update(n);
+                                let now_garbage = n.value.swap(value, Ordering::SeqCst, guard);
+                                self.finish_update(n);
+                                // NOTE: now_garbage == current_value
+
+                                // safety: need to guarantee that now_garbage is no longer
+                                // reachable. more specifically, no thread that executes _after_
+                                // this line can ever get a reference to now_garbage.
+                                //
+                                // here are the possible cases:
+                                //
+                                //  - another thread already has a reference to now_garbage.
+                                //    they must have read it before the call to swap while
+                                //    marked as active (holding a guard), and are included in
+                                //    the reference count. therefore t won't be freed until _after_
+                                //    it decrements the reference count, which can only happen
+                                //    when that thread drops its guard, and with it, any reference
+                                //    to the value.
+                                //  - another thread is about to get a reference to this value.
+                                //    they execute _after_ the swap, and therefore do _not_ get a
+                                //    reference to now_garbage (they get `value` instead). there are
+                                //    no other ways to get to a value except through its Node's
+                                //    `value` field (which is what we swapped), so freeing
+                                //    now_garbage is fine.
+                                unsafe { guard.retire_shared(now_garbage) };
+                            }
+                            break Some(current_value);
+                        }
+
+                        // TODO: This Ordering can probably be relaxed due to the Mutex
+                        let next = n.next.load(Ordering::SeqCst, guard);
+                        if next.is_null() {
+                            // we're at the end of the bin -- stick the node here!
+                            let node = Shared::boxed(
+                                BinEntry::Node(Node::new(hash, key, value)),
+                                &self.collector,
+                            );
+                            n.next.store(node, Ordering::SeqCst);
+                            // safety: we just linked in the node, and hold the bin lock
+                            self.finish_update(unsafe { node.deref() }.as_node().unwrap());
+                            break None;
+                        }
+                        p = next;
+
+                        bin_count += 1;
+                    };
+                    drop(head_lock);
+                }
+                // NOTE: BinEntry::Tree(ref tree_bin) if no_replacement && head.hash == h && &head.key == key
+                // cannot occur as in the Java code, TreeBins have a special, indicator hash value
+                BinEntry::Tree(ref tree_bin) => {
+                    // bin is non-empty, need to link into it, so we must take the lock
+                    let head_lock = tree_bin.lock.lock();
+
+                    // need to check that this is _still_ the correct bin
+                    let current_head = t.bin(bini, guard);
+                    if current_head != bin {
+                        // nope -- try again from the start
+                        continue;
+                    }
+
+                    // yes, it is still the head, so we can now "own" the bin
+                    // note that there can still be readers in the bin!
+
+                    // we don't actually count bins, just set this low enough
+                    // that we don't try to treeify the bin later
+                    bin_count = 2;
+                    let p = tree_bin.find_or_put_tree_val(hash, key, value, guard, &self.collector);
+                    if p.is_null() {
+                        // no TreeNode was returned, so the key did not previously exist in the
+                        // TreeBin. This means it was successfully put there by the call above
+                        // and we are done.
+                        self.finish_tree_insert(tree_bin, guard);
+                        break;
+                    }
+                    // safety: the TreeBin was read under our guard, at which point the tree
+                    // structure was valid. Since our guard marks the current thread as active,
+                    // the TreeNodes remain valid for at least as long as we hold onto the
+                    // guard.
+                    // Structurally, TreeNodes always point to TreeNodes, so this is sound.
+                    let tree_node = unsafe { TreeNode::get_tree_node(p) };
+                    old_val = {
+                        let current_value = tree_node.node.value.load(Ordering::SeqCst, guard);
+                        // safety: since the value is present now, and we've held a guard from
+                        // the beginning of the search, the value cannot be dropped until after
+                        // we drop our guard.
+                        let current_value = unsafe { current_value.deref() };
+                        if no_replacement {
+                            // the key is not absent, so don't update because of
+                            // `no_replacement`, we don't use the new value, so we need to clean
+                            // it up and return it back to the caller
+                            // safety: we own value and did not share it
+                            return PutResult::Exists {
+                                current: current_value,
+                                not_inserted: unsafe { value.into_box() },
+                            };
+                        } else {
+                            self.begin_update(&tree_node.node);
+                            let now_garbage =
+                                tree_node.node.value.swap(value, Ordering::SeqCst, guard);
+                            self.finish_update(&tree_node.node);
+                            // NOTE: now_garbage == current_value
+
+                            // safety: need to guarantee that now_garbage is no longer
+                            // reachable. more specifically, no thread that executes _after_
+                            // this line can ever get a reference to now_garbage.
+                            //
+                            // here are the possible cases:
+                            //
+                            //  - another thread already has a reference to now_garbage.
+                            //    they must have read it before the call to swap while
+                            //    marked as active (holding a guard), and are included in
+                            //    the reference count. therefore t won't be freed until _after_
+                            //    it decrements the reference count, which can only happen
+                            //    when that thread drops its guard, and with it, any reference
+                            //    to the value.
+                            //  - another thread is about to get a reference to this value.
+                            //    they execute _after_ the swap, and therefore do _not_ get a
+                            //    reference to now_garbage (they get `value` instead). there are
+                            //    no other ways to get to a value except through its Node's
+                            //    `value` field (which is what we swapped), so freeing
+                            //    now_garbage is fine.
+                            unsafe { guard.retire_shared(now_garbage) };
+                        }
+                        Some(current_value)
+                    };
+                    drop(head_lock);
+                }
+                BinEntry::TreeNode(_) => unreachable!(
+                    "The head of a bin cannot be a TreeNode directly without BinEntry::Tree"
+                ),
+                BinEntry::Reservation(_) => {
+                    t.wait_for_reservation(bini, bin, guard);
+                    continue;
                 }
-            } else if self
-                .size_ctl
-                .compare_exchange(sc, rs + 2, Ordering::SeqCst, Ordering::Relaxed)
-                .is_ok()
-            {
-                // a resize is needed, but has not yet started
-                // TODO: figure out why this is rs + 2, not just rs
-                // NOTE: this also applies to `try_presize`
-                self.transfer(table, Shared::null(), guard);
             }
-
-            // another resize may be needed!
-            count = self.count.load(Ordering::SeqCst);
+            // NOTE: the Java code checks `bin_count` here because they also
+            // reach this point if the bin changed while obtaining the lock.
+            // However, our code doesn't (it uses continue) and `bin_count`
+            // _cannot_ be 0 at this point.
+            debug_assert_ne!(bin_count, 0);
+            // a bin that is too long for a small table makes `treeify_bin` grow the table instead,
+            // which the caller may not want us to do either
+            if bin_count >= TREEIFY_THRESHOLD && (resize || t.len() >= MIN_TREEIFY_CAPACITY) {
+                self.treeify_bin(t, bini, guard);
+            }
+            if let Some(old_val) = old_val {
+                return PutResult::Replaced {
+                    old: old_val,
+                    // safety: we have not moved the node's value since we placed it into
+                    // its `Atomic` in the very beginning of the method, so the ref is still
+                    // valid. since the value is not currently marked as garbage, and since
+                    // `value` was loaded under a guard, the returned reference will remain valid
+                    // for the guard's lifetime.
+                    new: unsafe { value.deref() },
+                };
+            }
+            break;
+        }
+        // increment count, since we only get here if we did not return an old (updated) value
+        debug_assert!(old_val.is_none());
+        self.add_count(1, resize.then(|| bin_count), guard);
+        PutResult::Inserted {
+            // safety: we have not moved the node's value since we placed it into
+            // its `Atomic` in the very beginning of the method, so the ref is still
+            // valid. since the value is not currently marked as garbage, and since
+            // `value` was loaded under a guard, the returned reference will remain valid
+            // for the guard's lifetime.
+            new: unsafe { value.deref() },
         }
     }
 
-    /// Tries to reserve capacity for at least `additional` more elements to be inserted in the
-    /// `HashMap`.
-    ///
-    /// The collection may reserve more space to avoid frequent reallocations.
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// use flurry::HashMap;
-    ///
-    /// let map: HashMap<&str, i32> = HashMap::new();
+    /// Inserts the given key-value pair, replacing both the key and the value of an existing
+    /// entry for an equal key. Returns the key that was replaced, if any.
     ///
-    /// map.pin().reserve(10);
-    /// ```
-    ///
-    /// # Notes
-    ///
-    /// Reserving does not panic in flurry. If the new size is invalid, no
-    /// reallocation takes place.
-    pub fn reserve(&self, additional: usize, guard: &Guard<'_>) {
+    /// Since keys are immutable, this replaces the node for the existing entry with a new node
+    /// under the bin lock, so that readers observe either the old or the new entry.
+    pub(crate) fn replace_key<'g>(
+        &'g self,
+        mut key: K,
+        value: V,
+        guard: &'g Guard<'_>,
+    ) -> Option<&'g K> {
         self.check_guard(guard);
-        let absolute = self.len() + additional;
-        self.try_presize(absolute, guard);
-    }
-}
+        let hash = self.hash(&key);
+        let mut table = self.table.load(Ordering::SeqCst, guard);
+        let value = Shared::boxed(value, &self.collector);
+        loop {
+            // safety: see argument in `put`
+            if table.is_null() || unsafe { table.deref() }.is_empty() {
+                table = self.init_table(guard);
+                continue;
+            }
 
-// ===
-// the following methods never introduce new items (so they do not need the thread-safety bounds),
-// but they _do_ perform lookups, which require hashing and equality.
-// ===
+            // safety: table is a valid pointer. see the equivalent comment in `put`.
+            let t = unsafe { table.deref() };
 
-impl<K, V, S> HashMap<K, V, S>
-where
-    K: Hash + Ord,
-    S: BuildHasher,
-{
-    #[inline]
-    fn hash<Q: ?Sized + Hash>(&self, key: &Q) -> u64 {
-        let mut h = self.build_hasher.build_hasher();
-        key.hash(&mut h);
-        h.finish()
-    }
+            let bini = t.bini(hash);
+            let mut bin = t.bin(bini, guard);
+            if bin.is_null() {
+                // fast path -- bin is empty so stick us at the front
+                let node =
+                    Shared::boxed(BinEntry::Node(Node::new(hash, key, value)), &self.collector);
+                match t.cas_bin(bini, bin, node, guard) {
+                    Ok(_old_null_ptr) => {
+                        // safety: we just linked in the node while holding the guard, so it
+                        // cannot be dropped until we drop the guard.
+                        self.finish_unlocked_insert(unsafe { node.deref() }.as_node().unwrap());
+                        self.add_count(1, Some(0), guard);
+                        return None;
+                    }
+                    Err(changed) => {
+                        assert!(!changed.current.is_null());
+                        bin = changed.current;
+                        if let BinEntry::Node(node) =
+                            Linked::into_inner(*unsafe { changed.new.into_box() })
+                        {
+                            key = node.key;
+                        } else {
+                            unreachable!("we declared node and it is a BinEntry::Node");
+                        }
+                    }
+                }
+            }
 
-    fn get_node<'g, Q>(&'g self, key: &Q, guard: &'g Guard<'_>) -> Option<&'g Node<K, V>>
-    where
-        K: Borrow<Q>,
-        Q: ?Sized + Hash + Ord,
-    {
-        let table = self.table.load(Ordering::SeqCst, guard);
-        if table.is_null() {
-            return None;
-        }
+            // safety: bin is a valid pointer. see the equivalent comment in `put`.
+            let bin_count = match **unsafe { bin.deref() } {
+                BinEntry::Moved => {
+                    table = self.help_transfer(table, guard);
+                    continue;
+                }
+                BinEntry::Node(ref head) => {
+                    let head_lock = head.lock.lock();
 
-        // safety: we loaded the table while holding a guard.
-        // table won't be deallocated until we drop our guard
-        // at the earliest.
-        let table = unsafe { table.deref() };
-        if table.is_empty() {
-            return None;
-        }
+                    // need to check that this is _still_ the head
+                    if t.bin(bini, guard) != bin {
+                        continue;
+                    }
 
-        let h = self.hash(key);
-        let bini = table.bini(h);
-        let bin = table.bin(bini, guard);
-        if bin.is_null() {
-            return None;
-        }
+                    let mut bin_count = 1;
+                    let mut e = bin;
+                    let mut pred: Shared<'_, BinEntry<K, V>> = Shared::null();
+                    loop {
+                        // safety: we loaded the bin while holding a guard, so any retirements
+                        // must have seen us as active. the bin and its nodes cannot be dropped
+                        // until at least after we drop our guard.
+                        let n = unsafe { e.deref() }.as_node().unwrap();
+                        let next = n.next.load(Ordering::SeqCst, guard);
+                        if n.hash == hash && n.key == key {
+                            // the key already exists in the map! swap in a new node in its place.
+                            let node = Shared::boxed(
+                                BinEntry::Node(Node::with_next(
+                                    hash,
+                                    key,
+                                    value,
+                                    Atomic::from(next),
+                                )),
+                                &self.collector,
+                            );
+                            if pred.is_null() {
+                                t.store_bin(bini, node);
+                            } else {
+                                // safety: as above
+                                unsafe { pred.deref() }
+                                    .as_node()
+                                    .unwrap()
+                                    .next
+                                    .store(node, Ordering::SeqCst);
+                            }
+                            // safety: we just linked in the node, and hold the bin lock
+                            self.finish_update(unsafe { node.deref() }.as_node().unwrap());
+                            drop(head_lock);
+
+                            let old_value = n.value.load(Ordering::SeqCst, guard);
+                            // safety: the old node and its value are no longer reachable through
+                            // the map. any thread that still has a reference to either must have
+                            // read it while marked as active, and so is included in the reference
+                            // count. the returned key stays valid until our guard is dropped for
+                            // the same reason.
+                            unsafe {
+                                guard.retire_shared(old_value);
+                                guard.retire_shared(e);
+                            }
+                            return Some(&n.key);
+                        }
 
-        // safety: bin is a valid pointer.
-        //
-        // there are three cases when a bin pointer is invalidated:
-        //
-        //  1. if the table was resized, bin is a move entry, and the resize has completed. in
-        //     that case, the table (and all its heads) have already been retired.
-        //  2. if the table is being resized, bin may be swapped with a move entry. the old bin
-        //     will only be retired after that happens.
-        //  3. when elements are inserted into or removed from the map, bin may be changed into
-        //     or from a TreeBin from or into a regular, linear bin. the old bin will be
-        //     retired only once that happens.
-        //
-        // in all cases, we held the guard when we got the reference to the bin. if any such
-        // swap happened, it must have happened _after_ we read. since we did the read while
-        // the current thread was marked as active, we must be included in the reference count,
-        // and the drop must happen _after_ we decrement the count (i.e drop our guard).
-        let node = table.find(unsafe { bin.deref() }, h, key, guard);
-        if node.is_null() {
-            return None;
-        }
+                        if next.is_null() {
+                            // we're at the end of the bin -- stick the node here!
+                            let node = Shared::boxed(
+                                BinEntry::Node(Node::new(hash, key, value)),
+                                &self.collector,
+                            );
+                            n.next.store(node, Ordering::SeqCst);
+                            // safety: we just linked in the node, and hold the bin lock
+                            self.finish_update(unsafe { node.deref() }.as_node().unwrap());
+                            break;
+                        }
+                        pred = e;
+                        e = next;
+                        bin_count += 1;
+                    }
+                    drop(head_lock);
+                    bin_count
+                }
+                BinEntry::Tree(ref tree_bin) => {
+                    let bin_lock = tree_bin.lock.lock();
 
-        // safety: we loaded the bin while holding a guard, so any retirements
-        // must have seen us as active, and any future retirements must see us as active.
-        // the bin and its nodes cannot be dropped until at least after we drop our guard.
-        let node = unsafe { node.deref() };
-        Some(match **node {
-            BinEntry::Node(ref n) => n,
-            BinEntry::TreeNode(ref tn) => &tn.node,
-            _ => panic!("`Table::find` should always return a Node"),
-        })
+                    // need to check that this is _still_ the correct bin
+                    if t.bin(bini, guard) != bin {
+                        continue;
+                    }
+
+                    let root = tree_bin.root.load(Ordering::SeqCst, guard);
+                    let p = if root.is_null() {
+                        Shared::null()
+                    } else {
+                        TreeNode::find_tree_node(root, hash, &key, guard)
+                    };
+                    if p.is_null() {
+                        // the key is not in the tree, so just add it
+                        let p =
+                            tree_bin.find_or_put_tree_val(hash, key, value, guard, &self.collector);
+                        debug_assert!(p.is_null());
+                        self.finish_tree_insert(tree_bin, guard);
+                        drop(bin_lock);
+                        // we don't actually count bins, just set this low enough
+                        // that we don't try to treeify the bin later
+                        2
+                    } else {
+                        // safety: the TreeBin was read under our guard, at which point the tree
+                        // structure was valid. Since our guard marks the current thread as
+                        // active, the TreeNodes remain valid for at least as long as we hold onto
+                        // the guard.
+                        // Structurally, TreeNodes always point to TreeNodes, so this is sound.
+                        let n = &unsafe { TreeNode::get_tree_node(p) }.node;
+                        let old_value = n.value.load(Ordering::SeqCst, guard);
+
+                        // readers may be traversing the tree without the lock, so we cannot
+                        // remove the old node and then insert the new one without the key
+                        // briefly going missing. instead, we build a new tree bin in which the
+                        // node for the old key is replaced, and swap it in all at once.
+                        let mut key = Some(key);
+                        let mut head = Shared::null();
+                        let mut tail = Shared::null();
+                        let mut replacement = Shared::null();
+                        let mut e = tree_bin.first.load(Ordering::SeqCst, guard);
+                        while !e.is_null() {
+                            // safety: as above
+                            let e_deref = &unsafe { TreeNode::get_tree_node(e) }.node;
+                            let new_tree_node = if e == p {
+                                TreeNode::new(
+                                    hash,
+                                    key.take().expect("there is only one node for each key"),
+                                    Atomic::from(value),
+                                    Atomic::null(),
+                                    Atomic::null(),
+                                )
+                            } else {
+                                // NOTE: cloning the value uses a load with Ordering::Relaxed, but
+                                // write access is synchronized through the bin lock
+                                let copy = TreeNode::new(
+                                    e_deref.hash,
+                                    e_deref.key.clone(),
+                                    e_deref.value.clone(),
+                                    Atomic::null(),
+                                    Atomic::null(),
+                                );
+                                self.copy_version(e_deref, &copy.node);
+                                copy
+                            };
+                            new_tree_node.prev.store(tail, Ordering::Relaxed);
+                            let new_tree_node =
+                                Shared::boxed(BinEntry::TreeNode(new_tree_node), &self.collector);
+                            if e == p {
+                                replacement = new_tree_node;
+                            }
+                            if tail.is_null() {
+                                head = new_tree_node;
+                            } else {
+                                // safety: if `tail` is not `null`, we have just created
+                                // it in the last iteration, thus the pointer is valid
+                                unsafe { tail.deref() }
+                                    .as_tree_node()
+                                    .unwrap()
+                                    .node
+                                    .next
+                                    .store(new_tree_node, Ordering::Relaxed);
+                            }
+                            tail = new_tree_node;
+                            e = e_deref.next.load(Ordering::SeqCst, guard);
+                        }
+                        t.store_bin(
+                            bini,
+                            Shared::boxed(
+                                BinEntry::Tree(TreeBin::new(
+                                    // safety: we have just created `head` and its `next`
+                                    // nodes and have never shared them
+                                    unsafe { head.into_box() },
+                                    guard,
+                                )),
+                                &self.collector,
+                            ),
+                        );
+                        // safety: we just linked in the new bin while holding the guard, so its
+                        // nodes cannot be dropped until we drop the guard.
+                        self.finish_unlocked_insert(
+                            &unsafe { TreeNode::get_tree_node(replacement) }.node,
+                        );
+                        // the old bin is now garbage, but all values except the old one are
+                        // re-used in the new bin
+                        // safety: the old bin is no longer reachable through the table. any
+                        // thread that is still reading it must have obtained it while marked as
+                        // active, and so is included in the reference count.
+                        unsafe { TreeBin::defer_drop_without_values(bin, guard) };
+                        drop(bin_lock);
+
+                        // safety: the old value is no longer reachable through the map. any
+                        // thread that still has a reference to it must have read it while marked
+                        // as active, and so is included in the reference count.
+                        unsafe { guard.retire_shared(old_value) };
+                        return Some(&n.key);
+                    }
+                }
+                BinEntry::TreeNode(_) => unreachable!(
+                    "The head of a bin cannot be a TreeNode directly without BinEntry::Tree"
+                ),
+                BinEntry::Reservation(_) => {
+                    t.wait_for_reservation(bini, bin, guard);
+                    continue;
+                }
+            };
+
+            if bin_count >= TREEIFY_THRESHOLD {
+                self.treeify_bin(t, bini, guard);
+            }
+            self.add_count(1, Some(bin_count), guard);
+            return None;
+        }
     }
 
-    /// Returns `true` if the map contains a value for the specified key.
+    fn put_all<I: Iterator<Item = (K, V)>>(&self, iter: I, guard: &Guard<'_>) {
+        for (key, value) in iter {
+            self.put(key, value, false, guard);
+        }
+    }
+
+    /// Inserts all key-value pairs from `iter` into the map, replacing the values of any keys
+    /// that are already present.
     ///
-    /// The key may be any borrowed form of the map's key type, but
-    /// [`Hash`] and [`Ord`] on the borrowed form *must* match those for
-    /// the key type.
+    /// Before loading, this reserves room for as many elements as the lower bound of the
+    /// iterator's [`size_hint`](Iterator::size_hint) promises. The inserts themselves never start
+    /// a resize, neither because the map grew past its load factor nor because a bin of a small
+    /// table grew long, so any capacity that is still missing once the iterator is exhausted is
+    /// added in one final resize, which moves the entries straight to a table of the size they
+    /// need. This
+    /// makes `bulk_insert` considerably cheaper than calling [`HashMap::insert`] in a loop when
+    /// growing a map by many elements.
     ///
-    /// [`Ord`]: std::cmp::Ord
-    /// [`Hash`]: std::hash::Hash
+    /// Inserts performed concurrently by other threads still resize the table as usual.
     ///
     /// # Examples
     ///
@@ -1336,30 +4603,46 @@ where
     /// use flurry::HashMap;
     ///
     /// let map = HashMap::new();
-    /// let mref = map.pin();
-    /// mref.insert(1, "a");
-    /// assert_eq!(mref.contains_key(&1), true);
-    /// assert_eq!(mref.contains_key(&2), false);
+    /// let guard = map.guard();
+    ///
+    /// map.bulk_insert((0..1000).map(|i| (i, i * 2)), &guard);
+    /// assert_eq!(map.len(), 1000);
+    /// assert_eq!(map.get(&500, &guard), Some(&1000));
     /// ```
-    pub fn contains_key<Q>(&self, key: &Q, guard: &Guard<'_>) -> bool
+    pub fn bulk_insert<I>(&self, iter: I, guard: &Guard<'_>)
     where
-        K: Borrow<Q>,
-        Q: ?Sized + Hash + Ord,
+        I: IntoIterator<Item = (K, V)>,
     {
         self.check_guard(guard);
-        self.get(key, guard).is_some()
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0, guard);
+        for (key, value) in iter {
+            self.put_inner(self.hash(&key), key, value, false, false, guard);
+        }
+        // catch up on the resizes the inserts above skipped, if the size hint fell short. the
+        // table is moved straight to the size the elements need, rather than doubled repeatedly.
+        let len = self.len();
+        if self.capacity_for(len) > self.capacity(guard) {
+            self.resize_eager(len, guard);
+        }
     }
 
-    /// Returns a reference to the value corresponding to the key.
+    /// If the specified `key` is not already associated with a value, attempts to compute its
+    /// value using the given `mapping_function` and inserts it into the map.
     ///
-    /// The key may be any borrowed form of the map's key type, but
-    /// [`Hash`] and [`Ord`] on the borrowed form *must* match those for
-    /// the key type.
+    /// The entire method invocation is performed atomically, so the function is applied at most
+    /// once per key. If the key is already present, the function is not invoked at all. Some
+    /// attempted update operations on this map by other threads may be blocked while computation
+    /// is in progress, so the computation should be short and simple, and must not attempt to
+    /// update any other mappings of this map.
     ///
-    /// [`Ord`]: std::cmp::Ord
-    /// [`Hash`]: std::hash::Hash
+    /// Returns the current (existing or computed) value associated with the specified `key`.
     ///
-    /// To obtain a `Guard`, use [`HashMap::guard`].
+    /// # Panics
+    ///
+    /// The bin that `key` falls into stays locked while the function runs. If the function
+    /// modifies the map in that same bin, for example by inserting or removing `key`, this method
+    /// panics rather than deadlocking.
     ///
     /// # Examples
     ///
@@ -1368,323 +4651,343 @@ where
     ///
     /// let map = HashMap::new();
     /// let mref = map.pin();
-    /// mref.insert(1, "a");
-    /// assert_eq!(mref.get(&1), Some(&"a"));
-    /// assert_eq!(mref.get(&2), None);
+    ///
+    /// assert_eq!(mref.compute_if_absent(1, |k| k * 10), &10);
+    /// // the key is present, so the function is not called
+    /// assert_eq!(mref.compute_if_absent(1, |_| unreachable!()), &10);
     /// ```
-    #[inline]
-    pub fn get<'g, Q>(&'g self, key: &Q, guard: &'g Guard<'_>) -> Option<&'g V>
+    pub fn compute_if_absent<'g, F>(
+        &'g self,
+        key: K,
+        mapping_function: F,
+        guard: &'g Guard<'_>,
+    ) -> &'g V
     where
-        K: Borrow<Q>,
-        Q: ?Sized + Hash + Ord,
+        F: FnOnce(&K) -> V,
     {
         self.check_guard(guard);
-        let node = self.get_node(key, guard)?;
-
-        let v = node.value.load(Ordering::SeqCst, guard);
-        assert!(!v.is_null());
-        // safety: the lifetime of the reference is bound to the guard
-        // supplied which means that the memory will not be modified
-        // until at least after the guard goes out of scope
-        unsafe { v.as_ref().map(|linked| &**linked) }
+        match self.try_compute_if_absent(key, |k| Ok::<_, Infallible>(mapping_function(k)), guard) {
+            Ok(value) => value,
+            Err(never) => match never {},
+        }
     }
 
-    /// Returns the key-value pair corresponding to `key`.
-    ///
-    /// Returns `None` if this map contains no mapping for `key`.
-    ///
-    /// The key may be any borrowed form of the map's key type, but
-    /// [`Hash`] and [`Ord`] on the borrowed form *must* match those for
-    /// the key type.
+    /// Reserves the empty bin `bini` of `t`, so that other writers to it wait until the returned
+    /// `ReservedBin` is filled or dropped.
     ///
-    /// [`Ord`]: std::cmp::Ord
-    /// [`Hash`]: std::hash::Hash
-    #[inline]
-    pub fn get_key_value<'g, Q>(&'g self, key: &Q, guard: &'g Guard<'_>) -> Option<(&'g K, &'g V)>
-    where
-        K: Borrow<Q>,
-        Q: ?Sized + Hash + Ord,
-    {
-        self.check_guard(guard);
-        let node = self.get_node(key, guard)?;
-
-        let v = node.value.load(Ordering::SeqCst, guard);
-        assert!(!v.is_null());
-        // safety: the lifetime of the reference is bound to the guard
-        // supplied which means that the memory will not be modified
-        // until at least after the guard goes out of scope
-        unsafe { v.as_ref() }.map(|v| (&node.key, &**v))
-    }
-
-    pub(crate) fn guarded_eq(
-        &self,
-        other: &Self,
-        our_guard: &Guard<'_>,
-        their_guard: &Guard<'_>,
-    ) -> bool
-    where
-        V: PartialEq,
-    {
-        if self.len() != other.len() {
-            return false;
+    /// Returns `None` if the bin was no longer empty.
+    fn reserve_bin<'g>(
+        &'g self,
+        t: &'g Table<K, V>,
+        bini: usize,
+        guard: &'g Guard<'_>,
+    ) -> Option<ReservedBin<'g, K, V>> {
+        let reservation = Shared::boxed(BinEntry::Reservation(BinLock::new()), &self.collector);
+        // we take the reservation's lock _before_ we place it in the bin, so that any other
+        // writer that finds the reservation has to wait for us to finish.
+        //
+        // safety: we just allocated the reservation, and it is only retired after it has been
+        // placed in and removed from the bin, while we are still holding our guard.
+        let lock = match **unsafe { reservation.deref() } {
+            BinEntry::Reservation(ref lock) => lock.lock(),
+            _ => unreachable!("we declared the reservation and it is a BinEntry::Reservation"),
+        };
+        if t.cas_bin(bini, Shared::null(), reservation, guard).is_err() {
+            drop(lock);
+            // safety: the reservation was never shared with any other thread
+            drop(unsafe { reservation.into_box() });
+            return None;
         }
 
-        self.iter(our_guard)
-            .all(|(key, value)| other.get(key, their_guard).map_or(false, |v| *value == *v))
+        Some(ReservedBin {
+            table: t,
+            bini,
+            reservation,
+            lock: Some(lock),
+            fill: Shared::null(),
+            guard,
+        })
     }
-}
-
-// ===
-// the following methods only ever _remove_ items, but never introduce them, so they do not need
-// the thread-safety bounds.
-// ===
 
-impl<K, V, S> HashMap<K, V, S>
-where
-    K: Clone + Ord,
-{
-    /// Clears the map, removing all key-value pairs.
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// use flurry::HashMap;
-    ///
-    /// let map = HashMap::new();
-    ///
-    /// map.pin().insert(1, "a");
-    /// map.pin().clear();
-    /// assert!(map.pin().is_empty());
-    /// ```
-    pub fn clear(&self, guard: &Guard<'_>) {
-        // Negative number of deletions
-        let mut delta = 0;
-        let mut idx = 0usize;
+    /// Fills a bin reserved with `reserve_bin` with a new node for `key` and `value`.
+    fn fill_reserved_bin<'g>(
+        &'g self,
+        mut reserved: ReservedBin<'g, K, V>,
+        hash: u64,
+        key: K,
+        value: V,
+    ) -> &'g V {
+        let guard = reserved.guard;
+        let value = Shared::boxed(value, &self.collector);
+        let fill = Shared::boxed(BinEntry::Node(Node::new(hash, key, value)), &self.collector);
+        reserved.fill = fill;
+        drop(reserved);
+
+        // safety: the node was linked in while we held the guard, so it cannot be dropped until
+        // we drop the guard.
+        self.finish_unlocked_insert(unsafe { fill.deref() }.as_node().unwrap());
+        self.add_count(1, Some(1), guard);
+        // safety: we have not moved the node's value since we placed it into its `Atomic` above,
+        // so the ref is still valid. since the value is not currently marked as garbage, and since
+        // it was allocated under our guard, the returned reference will remain valid for the
+        // guard's lifetime.
+        unsafe { value.deref() }
+    }
 
+    /// Like `compute_if_absent`, but the computation may fail, in which case nothing is inserted
+    /// and the error is returned to the caller.
+    fn try_compute_if_absent<'g, F, E>(
+        &'g self,
+        key: K,
+        mapping_function: F,
+        guard: &'g Guard<'_>,
+    ) -> Result<&'g V, E>
+    where
+        F: FnOnce(&K) -> Result<V, E>,
+    {
+        let hash = self.hash(&key);
         let mut table = self.table.load(Ordering::SeqCst, guard);
-        // Safety: self.table is a valid pointer because we checked it above.
-        while !table.is_null() && idx < unsafe { table.deref() }.len() {
-            let tab = unsafe { table.deref() };
-            let raw_node = tab.bin(idx, guard);
-            if raw_node.is_null() {
-                idx += 1;
+        let bin_count;
+        let value;
+        loop {
+            // safety: see argument below for !is_null case
+            if table.is_null() || unsafe { table.deref() }.is_empty() {
+                table = self.init_table(guard);
                 continue;
             }
-            // Safety: node is a valid pointer because we checked
-            // it in the above if stmt.
-            match **unsafe { raw_node.deref() } {
+
+            // safety: table is a valid pointer.
+            //
+            // we are in one of three cases:
+            //
+            //  1. if table is the one we read before the loop, then we read it while holding the
+            //     guard, so it won't be dropped until after we drop that guard b/c this thread must
+            //     have been included in the reference count of a retirement.
+            //
+            //  2. if table is read by init_table, we did so while holding a guard, so the
+            //     argument is as for point 1. or, we allocated the table while holding a guard,
+            //     so the earliest it can be deallocated is after we drop our guard.
+            //
+            //  3. if table is set by a Moved node (below) through help_transfer, it will _either_
+            //     keep using `table` (which is fine by 1. and 2.), or use the `next_table` raw
+            //     pointer from inside the Moved. to see that if a Moved(t) is _read_, then t must
+            //     still be valid, see the safety comment on Table.next_table.
+            let t = unsafe { table.deref() };
+
+            let bini = t.bini(hash);
+            let bin = t.bin(bini, guard);
+            if bin.is_null() {
+                // the bin is empty, so we reserve it while we compute the value
+                let reserved = match self.reserve_bin(t, bini, guard) {
+                    Some(reserved) => reserved,
+                    // someone else got to the bin first -- try again
+                    None => continue,
+                };
+                // if the computation fails, dropping the reservation empties the bin again
+                let value = mapping_function(&key)?;
+                return Ok(self.fill_reserved_bin(reserved, hash, key, value));
+            }
+
+            // slow path -- bin is non-empty
+            //
+            // safety: bin is a valid pointer.
+            //
+            // there are three cases when a bin pointer is invalidated:
+            //
+            //  1. if the table was resized, bin is a move entry, and the resize has completed. in
+            //     that case, the table (and all its heads) have already been retired.
+            //  2. if the table is being resized, bin may be swapped with a move entry. the old bin
+            //     will only be retired after that happens.
+            //  3. when elements are inserted into or removed from the map, bin may be changed into
+            //     or from a TreeBin from or into a regular, linear bin. the old bin will be
+            //     retired only once that happens.
+            //
+            // in all cases, we held the guard when we got the reference to the bin. if any such
+            // swap happened, it must have happened _after_ we read. since we did the read while
+            // the current thread was marked as active, we must be included in the reference count,
+            // and the drop must happen _after_ we decrement the count (i.e drop our guard).
+            match **unsafe { bin.deref() } {
                 BinEntry::Moved => {
                     table = self.help_transfer(table, guard);
-                    // start from the first bin again in the new table
-                    idx = 0;
+                    continue;
                 }
-                BinEntry::Node(ref node) => {
-                    let head_lock = node.lock.lock();
+                BinEntry::Node(ref head) if head.hash == hash && head.key == key => {
+                    // fast path if the first bin matches
+                    let v = head.value.load(Ordering::SeqCst, guard);
+                    // safety: since the value is present now, and we've held a guard from
+                    // the beginning of the search, the value cannot be dropped until after we
+                    // drop our guard.
+                    return Ok(unsafe { v.deref() });
+                }
+                BinEntry::Node(ref head) => {
+                    // bin is non-empty, need to link into it, so we must take the lock
+                    let head_lock = head.lock.lock();
+
                     // need to check that this is _still_ the head
-                    let current_head = tab.bin(idx, guard);
-                    if current_head != raw_node {
-                        // nope -- try the bin again
+                    let current_head = t.bin(bini, guard);
+                    if current_head != bin {
+                        // nope -- try again from the start
                         continue;
                     }
-                    // we now own the bin
-                    // unlink it from the map to prevent others from entering it
-                    // NOTE: The Java code stores the null bin _after_ the loop, and thus also has
-                    // to hold the lock until that point. However, after the store happens new
-                    // threads and threads waiting on the lock will read the new bin, so we can
-                    // drop the lock early and do the counting and garbage collection outside the
-                    // critical section.
-                    tab.store_bin(idx, Shared::null());
-                    drop(head_lock);
-                    // next, walk the nodes of the bin and free the nodes and their values as we go
-                    // note that we do not free the head node yet, since we're holding the lock it contains
-                    let mut p = node.next.load(Ordering::SeqCst, guard);
-                    while !p.is_null() {
-                        delta -= 1;
-                        p = {
-                            // safety: we loaded p under guard, and guard is still pinned, so p has not been dropped.
-                            let node = unsafe { p.deref() }
-                                .as_node()
-                                .expect("entry following Node should always be a Node");
-                            let next = node.next.load(Ordering::SeqCst, guard);
-                            let value = node.value.load(Ordering::SeqCst, guard);
-                            // NOTE: do not use the reference in `node` after this point!
 
-                            // free the node's value
-                            // safety: any thread that sees this p's value must have read the bin before we stored null
-                            // into it above. it must also have already been marked as active. therefore, the
-                            // defer_destroy below won't be executed until that thread's guard is dropped, at which
-                            // point it holds no outstanding references to the value anyway.
-                            unsafe { guard.retire_shared(value) };
-                            // free the bin entry itself
-                            // safety: same argument as for value above.
-                            unsafe { guard.retire_shared(p) };
-                            next
-                        };
-                    }
-                    // finally, we can drop the head node and its value
-                    let value = node.value.load(Ordering::SeqCst, guard);
-                    // NOTE: do not use the reference in `node` after this point!
-                    // safety: same as the argument for being allowed to free the nodes beyond the head above
-                    unsafe { guard.retire_shared(value) };
-                    unsafe { guard.retire_shared(raw_node) };
-                    delta -= 1;
-                    idx += 1;
+                    // yes, it is still the head, so we can now "own" the bin
+                    // note that there can still be readers in the bin!
+
+                    let mut count = 1;
+                    let mut p = bin;
+
+                    let n = loop {
+                        // safety: we loaded the bin while holding a guard, so any retirements
+                        // must have seen us as active. the bin and its nodes cannot be dropped
+                        // until at least after we drop our guard.
+                        let n = unsafe { p.deref() }.as_node().unwrap();
+                        if n.hash == hash && n.key == key {
+                            // the key already exists in the map!
+                            let current_value = n.value.load(Ordering::SeqCst, guard);
+
+                            // safety: since the value is present now, and we've held a guard from
+                            // the beginning of the search, the value cannot be dropped until after
+                            // we drop our guard.
+                            return Ok(unsafe { current_value.deref() });
+                        }
+
+                        // TODO: This Ordering can probably be relaxed due to the Mutex
+                        let next = n.next.load(Ordering::SeqCst, guard);
+                        if next.is_null() {
+                            break n;
+                        }
+                        p = next;
+
+                        count += 1;
+                    };
+
+                    // we're at the end of the bin and the key is not present -- compute the
+                    // value and stick the node here!
+                    let v = Shared::boxed(mapping_function(&key)?, &self.collector);
+                    let node =
+                        Shared::boxed(BinEntry::Node(Node::new(hash, key, v)), &self.collector);
+                    n.next.store(node, Ordering::SeqCst);
+                    // safety: we just linked in the node, and hold the bin lock
+                    self.finish_update(unsafe { node.deref() }.as_node().unwrap());
+                    drop(head_lock);
+
+                    bin_count = count;
+                    value = v;
                 }
                 BinEntry::Tree(ref tree_bin) => {
+                    // bin is non-empty, need to link into it, so we must take the lock
                     let bin_lock = tree_bin.lock.lock();
+
                     // need to check that this is _still_ the correct bin
-                    let current_head = tab.bin(idx, guard);
-                    if current_head != raw_node {
-                        // nope -- try the bin again
+                    let current_head = t.bin(bini, guard);
+                    if current_head != bin {
+                        // nope -- try again from the start
                         continue;
                     }
-                    // we now own the bin
-                    // unlink it from the map to prevent others from entering it
-                    // NOTE: The Java code stores the null bin _after_ the loop, and thus also has
-                    // to hold the lock until that point. However, after the store happens new
-                    // threads and threads waiting on the lock will read the new bin, so we can
-                    // drop the lock early and do the counting and garbage collection outside the
-                    // critical section.
-                    tab.store_bin(idx, Shared::null());
-                    drop(bin_lock);
-                    // next, walk the nodes of the bin and count how many values we remove
-                    let mut p = tree_bin.first.load(Ordering::SeqCst, guard);
-                    while !p.is_null() {
-                        delta -= 1;
-                        p = {
-                            // safety: we read under our guard, at which point the tree
-                            // structure was valid. Since our guard marks the current thread
-                            // as active, the TreeNodes remain valid for at least as long as
-                            // we hold onto the guard.
+
+                    // yes, it is still the head, so we can now "own" the bin
+                    // note that there can still be readers in the bin!
+
+                    let root = tree_bin.root.load(Ordering::SeqCst, guard);
+                    if !root.is_null() {
+                        let p = TreeNode::find_tree_node(root, hash, &key, guard);
+                        if !p.is_null() {
+                            // safety: the TreeBin was read under our guard, at which point the
+                            // tree structure was valid. Since our guard marks the current thread
+                            // as active, the TreeNodes remain valid for at least as long as we
+                            // hold onto the guard.
                             // Structurally, TreeNodes always point to TreeNodes, so this is sound.
-                            let tree_node = unsafe { TreeNode::get_tree_node(p) };
-                            // NOTE: we do not drop the TreeNodes or their
-                            // values here, since they will be dropped together
-                            // with the containing TreeBin (`tree_bin`) in its
-                            // `drop`
-                            tree_node.node.next.load(Ordering::SeqCst, guard)
-                        };
+                            let n = &unsafe { TreeNode::get_tree_node(p) }.node;
+                            let current_value = n.value.load(Ordering::SeqCst, guard);
+                            // safety: since the value is present now, and we've held a guard from
+                            // the beginning of the search, the value cannot be dropped until after
+                            // we drop our guard.
+                            return Ok(unsafe { current_value.deref() });
+                        }
                     }
-                    // safety: same as in the BinEntry::Node case above
-                    unsafe { guard.retire_shared(raw_node) };
-                    idx += 1;
+
+                    // the key is not present in the tree, so we compute the value and put it
+                    let v = Shared::boxed(mapping_function(&key)?, &self.collector);
+                    let p = tree_bin.find_or_put_tree_val(hash, key, v, guard, &self.collector);
+                    // we hold the bin lock, so no other thread can have inserted the key since
+                    // we searched for it above
+                    assert!(p.is_null());
+                    self.finish_tree_insert(tree_bin, guard);
+                    drop(bin_lock);
+
+                    // we don't actually count bins, just set this low enough
+                    // that we don't try to treeify the bin later
+                    bin_count = 2;
+                    value = v;
+                }
+                BinEntry::Reservation(_) => {
+                    t.wait_for_reservation(bini, bin, guard);
+                    continue;
                 }
                 BinEntry::TreeNode(_) => unreachable!(
                     "The head of a bin cannot be a TreeNode directly without BinEntry::Tree"
                 ),
-            };
-        }
+            }
 
-        if delta != 0 {
-            self.add_count(delta, None, guard);
+            if bin_count >= TREEIFY_THRESHOLD {
+                self.treeify_bin(t, bini, guard);
+            }
+            break;
         }
-    }
-}
-
-// ===
-// the following methods _do_ introduce items into the map, and so must require that the keys and
-// values are thread safe, and can be garbage collected at a later time.
-// ===
 
-impl<K, V, S> HashMap<K, V, S>
-where
-    K: Sync + Send + Clone + Hash + Ord,
-    V: Sync + Send,
-    S: BuildHasher,
-{
-    /// Inserts a key-value pair into the map.
-    ///
-    /// If the map did not have this key present, [`None`] is returned.
-    ///
-    /// If the map did have this key present, the value is updated, and the old
-    /// value is returned. The key is left unchanged. See the [std-collections
-    /// documentation] for more.
-    ///
-    /// [`None`]: std::option::Option::None
-    /// [std-collections documentation]: https://doc.rust-lang.org/std/collections/index.html#insert-and-complex-keys
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// use flurry::HashMap;
-    ///
-    /// let map = HashMap::new();
-    /// assert_eq!(map.pin().insert(37, "a"), None);
-    /// assert_eq!(map.pin().is_empty(), false);
-    ///
-    /// // you can also re-use a map pin like so:
-    /// let mref = map.pin();
-    ///
-    /// mref.insert(37, "b");
-    /// assert_eq!(mref.insert(37, "c"), Some(&"b"));
-    /// assert_eq!(mref.get(&37), Some(&"c"));
-    /// ```
-    pub fn insert<'g>(&'g self, key: K, value: V, guard: &'g Guard<'_>) -> Option<&'g V> {
-        self.check_guard(guard);
-        self.put(key, value, false, guard).before()
+        self.add_count(1, Some(bin_count), guard);
+        // safety: we have not moved the node's value since we placed it into its `Atomic`
+        // above, so the ref is still valid. since the value is not currently marked as garbage,
+        // and since it was allocated under our guard, the returned reference will remain valid
+        // for the guard's lifetime.
+        Ok(unsafe { value.deref() })
     }
 
-    /// Inserts a key-value pair into the map unless the key already exists.
-    ///
-    /// If the map does not contain the key, the key-value pair is inserted
-    /// and this method returns `Ok`.
+    /// If the value for the specified `key` is present, attempts to
+    /// compute a new mapping given the key and its current mapped value.
     ///
-    /// If the map does contain the key, the map is left unchanged and this
-    /// method returns `Err`.
+    /// The new mapping is computed by the `remapping_function`, which may
+    /// return `None` to signalize that the mapping should be removed.
+    /// The entire method invocation is performed atomically.
+    /// The supplied function is invoked exactly once per invocation of
+    /// this method if the key is present, else not at all.  Some
+    /// attempted update operations on this map by other threads may be
+    /// blocked while computation is in progress, so the computation
+    /// should be short and simple.
     ///
-    /// [std-collections documentation]: https://doc.rust-lang.org/std/collections/index.html#insert-and-complex-keys
+    /// Returns the new value associated with the specified `key`, or `None`
+    /// if no value for the specified `key` is present.
     ///
-    /// # Examples
+    /// The key may be any borrowed form of the map's key type, but
+    /// [`Hash`] and [`Ord`] on the borrowed form *must* match those for
+    /// the key type.
     ///
-    /// ```
-    /// use flurry::{HashMap, TryInsertError};
+    /// [`Ord`]: std::cmp::Ord
+    /// [`Hash`]: std::hash::Hash
     ///
-    /// let map = HashMap::new();
-    /// let mref = map.pin();
+    /// # Panics
     ///
-    /// mref.insert(37, "a");
-    /// assert_eq!(
-    ///     mref.try_insert(37, "b"),
-    ///     Err(TryInsertError { current: &"a", not_inserted: &"b"})
-    /// );
-    /// assert_eq!(mref.try_insert(42, "c"), Ok(&"c"));
-    /// assert_eq!(mref.get(&37), Some(&"a"));
-    /// assert_eq!(mref.get(&42), Some(&"c"));
-    /// ```
-    #[inline]
-    pub fn try_insert<'g>(
-        &'g self,
-        key: K,
-        value: V,
-        guard: &'g Guard<'_>,
-    ) -> Result<&'g V, TryInsertError<'g, V>> {
-        match self.put(key, value, true, guard) {
-            PutResult::Exists {
-                current,
-                not_inserted,
-            } => Err(TryInsertError {
-                current,
-                not_inserted: Linked::into_inner(*not_inserted),
-            }),
-            PutResult::Inserted { new } => Ok(new),
-            PutResult::Replaced { .. } => {
-                unreachable!("no_replacement cannot result in PutResult::Replaced")
-            }
-        }
-    }
-
-    fn put<'g>(
+    /// The bin that `key` falls into stays locked while the function runs. If the function
+    /// modifies the map in that same bin, for example by inserting or removing `key`, this method
+    /// panics rather than deadlocking.
+    pub fn compute_if_present<'g, Q, F>(
         &'g self,
-        mut key: K,
-        value: V,
-        no_replacement: bool,
+        key: &Q,
+        remapping_function: F,
         guard: &'g Guard<'_>,
-    ) -> PutResult<'g, V> {
+    ) -> Option<&'g V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Ord,
+        F: FnOnce(&K, &V) -> Option<V>,
+    {
+        self.check_guard(guard);
         let hash = self.hash(&key);
+
         let mut table = self.table.load(Ordering::SeqCst, guard);
+        let mut new_val = None;
+        let mut removed_node = false;
         let mut bin_count;
-        let value = Shared::boxed(value, &self.collector);
-        let mut old_val = None;
         loop {
             // safety: see argument below for !is_null case
             if table.is_null() || unsafe { table.deref() }.is_empty() {
@@ -1711,35 +5014,10 @@ where
             let t = unsafe { table.deref() };
 
             let bini = t.bini(hash);
-            let mut bin = t.bin(bini, guard);
+            let bin = t.bin(bini, guard);
             if bin.is_null() {
-                // fast path -- bin is empty so stick us at the front
-                let node =
-                    Shared::boxed(BinEntry::Node(Node::new(hash, key, value)), &self.collector);
-                match t.cas_bin(bini, bin, node, guard) {
-                    Ok(_old_null_ptr) => {
-                        self.add_count(1, Some(0), guard);
-                        // safety: we have not moved the node's value since we placed it into
-                        // its `Atomic` in the very beginning of the method, so the ref is still
-                        // valid. since the value is not currently marked as garbage, and since
-                        // `value` was loaded under a guard, the returned reference will remain valid
-                        // for the guard's lifetime.
-                        return PutResult::Inserted {
-                            new: unsafe { value.deref() },
-                        };
-                    }
-                    Err(changed) => {
-                        assert!(!changed.current.is_null());
-                        bin = changed.current;
-                        if let BinEntry::Node(node) =
-                            Linked::into_inner(*unsafe { changed.new.into_box() })
-                        {
-                            key = node.key;
-                        } else {
-                            unreachable!("we declared node and it is a BinEntry::Node");
-                        }
-                    }
-                }
+                // fast path -- bin is empty so key is not present
+                return None;
             }
 
             // slow path -- bin is non-empty
@@ -1765,20 +5043,6 @@ where
                     table = self.help_transfer(table, guard);
                     continue;
                 }
-                BinEntry::Node(ref head)
-                    if no_replacement && head.hash == hash && head.key == key =>
-                {
-                    // fast path if replacement is disallowed and first bin matches
-                    let v = head.value.load(Ordering::SeqCst, guard);
-                    // safety (for v): since the value is present now, and we've held a guard from
-                    // the beginning of the search, the value cannot be dropped after we drop our guard.
-                    // safety (for value): since we never inserted the value in the tree, `value`
-                    // is the last remaining pointer to the initial value.
-                    return PutResult::Exists {
-                        current: unsafe { v.deref() },
-                        not_inserted: unsafe { value.into_box() },
-                    };
-                }
                 BinEntry::Node(ref head) => {
                     // bin is non-empty, need to link into it, so we must take the lock
                     let head_lock = head.lock.lock();
@@ -1793,37 +5057,32 @@ where
                     // yes, it is still the head, so we can now "own" the bin
                     // note that there can still be readers in the bin!
 
-                    // TODO: ReservationNode
-
                     bin_count = 1;
                     let mut p = bin;
+                    let mut pred: Shared<'_, BinEntry<K, V>> = Shared::null();
 
-                    old_val = loop {
+                    new_val = loop {
                         // safety: we loaded the bin while holding a guard, so any retirements
                         // must have seen us as active. the bin and its nodes cannot be dropped
                         // until at least after we drop our guard.
                         let n = unsafe { p.deref() }.as_node().unwrap();
-                        if n.hash == hash && n.key == key {
+                        // TODO: This Ordering can probably be relaxed due to the Mutex
+                        let next = n.next.load(Ordering::SeqCst, guard);
+                        if n.hash == hash && n.key.borrow() == key {
                             // the key already exists in the map!
                             let current_value = n.value.load(Ordering::SeqCst, guard);
 
                             // safety: since the value is present now, and we've held a guard from
                             // the beginning of the search, the value cannot be dropped until after
                             // we drop our guard.
-                            let current_value = unsafe { current_value.deref() };
+                            let new_value =
+                                remapping_function(&n.key, unsafe { current_value.deref() });
 
-                            if no_replacement {
-                                // the key is not absent, so don't update because of
-                                // `no_replacement`, we don't use the new value, so we need to clean
-                                // it up and return it back to the caller
-                                // safety: we own value and did not share it
-                                return PutResult::Exists {
-                                    current: current_value,
-                                    not_inserted: unsafe { value.into_box() },
-                                };
-                            } else {
-                                // update the value in the existing node
+                            if let Some(value) = new_value {
+                                let value = Shared::boxed(value, &self.collector);
+                                self.begin_update(n);
                                 let now_garbage = n.value.swap(value, Ordering::SeqCst, guard);
+                                self.finish_update(n);
                                 // NOTE: now_garbage == current_value
 
                                 // safety: need to guarantee that now_garbage is no longer
@@ -1846,19 +5105,56 @@ where
                                 //    `value` field (which is what we swapped), so freeing
                                 //    now_garbage is fine.
                                 unsafe { guard.retire_shared(now_garbage) };
+
+                                // safety: since the value is present now, and we've held a guard from
+                                // the beginning of the search, the value cannot be dropped until after
+                                // we drop our guard.
+                                break Some(unsafe { value.deref() });
+                            } else {
+                                removed_node = true;
+                                // remove the BinEntry containing the removed key value pair from the bucket
+                                if !pred.is_null() {
+                                    // either by changing the pointer of the previous BinEntry, if present
+                                    // safety: see remove
+                                    unsafe { pred.deref() }
+                                        .as_node()
+                                        .unwrap()
+                                        .next
+                                        .store(next, Ordering::SeqCst);
+                                } else {
+                                    // or by setting the next node as the first BinEntry if there is no previous entry
+                                    t.store_bin(bini, next);
+                                }
+
+                                // in either case, mark the BinEntry as garbage, since it was just removed
+                                // safety: need to guarantee that the old value is no longer
+                                // reachable. more specifically, no thread that executes _after_
+                                // this line can ever get a reference to val.
+                                //
+                                // here are the possible cases:
+                                //
+                                //  - another thread already has a reference to now_garbage.
+                                //    they must have read it before the call to swap while
+                                //    marked as active (holding a guard), and are included in
+                                //    the reference count. therefore t won't be freed until _after_
+                                //    it decrements the reference count, which can only happen
+                                //    when that thread drops its guard, and with it, any reference
+                                //    to the value.
+                                //  - another thread is about to get a reference to this value.
+                                //    they execute _after_ the swap, and therefore do _not_ get a
+                                //    reference to now_garbage (they get `value` instead). there are
+                                //    no other ways to get to a value except through its Node's
+                                //    `value` field (which is what we swapped), so freeing
+                                //    now_garbage is fine.
+                                unsafe { guard.retire_shared(p) };
+                                unsafe { guard.retire_shared(current_value) };
+                                break None;
                             }
-                            break Some(current_value);
                         }
 
-                        // TODO: This Ordering can probably be relaxed due to the Mutex
-                        let next = n.next.load(Ordering::SeqCst, guard);
+                        pred = p;
                         if next.is_null() {
-                            // we're at the end of the bin -- stick the node here!
-                            let node = Shared::boxed(
-                                BinEntry::Node(Node::new(hash, key, value)),
-                                &self.collector,
-                            );
-                            n.next.store(node, Ordering::SeqCst);
+                            // we're at the end of the bin
                             break None;
                         }
                         p = next;
@@ -1867,13 +5163,11 @@ where
                     };
                     drop(head_lock);
                 }
-                // NOTE: BinEntry::Tree(ref tree_bin) if no_replacement && head.hash == h && &head.key == key
-                // cannot occur as in the Java code, TreeBins have a special, indicator hash value
                 BinEntry::Tree(ref tree_bin) => {
                     // bin is non-empty, need to link into it, so we must take the lock
-                    let head_lock = tree_bin.lock.lock();
+                    let bin_lock = tree_bin.lock.lock();
 
-                    // need to check that this is _still_ the correct bin
+                    // need to check that this is _still_ the head
                     let current_head = t.bin(bini, guard);
                     if current_head != bin {
                         // nope -- try again from the start
@@ -1882,126 +5176,410 @@ where
 
                     // yes, it is still the head, so we can now "own" the bin
                     // note that there can still be readers in the bin!
-
-                    // we don't actually count bins, just set this low enough
-                    // that we don't try to treeify the bin later
                     bin_count = 2;
-                    let p = tree_bin.find_or_put_tree_val(hash, key, value, guard, &self.collector);
-                    if p.is_null() {
-                        // no TreeNode was returned, so the key did not previously exist in the
-                        // TreeBin. This means it was successfully put there by the call above
-                        // and we are done.
+                    let root = tree_bin.root.load(Ordering::SeqCst, guard);
+                    if root.is_null() {
+                        // TODO: Is this even reachable?
+                        // The Java code has `NULL` checks for this, but in theory it should not be possible to
+                        // encounter a tree that has no root when we have its lock. It should always have at
+                        // least `UNTREEIFY_THRESHOLD` nodes. If it is indeed impossible we should replace
+                        // this with an assertion instead.
                         break;
                     }
-                    // safety: the TreeBin was read under our guard, at which point the tree
-                    // structure was valid. Since our guard marks the current thread as active,
-                    // the TreeNodes remain valid for at least as long as we hold onto the
-                    // guard.
-                    // Structurally, TreeNodes always point to TreeNodes, so this is sound.
-                    let tree_node = unsafe { TreeNode::get_tree_node(p) };
-                    old_val = {
-                        let current_value = tree_node.node.value.load(Ordering::SeqCst, guard);
-                        // safety: since the value is present now, and we've held a guard from
-                        // the beginning of the search, the value cannot be dropped until after
-                        // we drop our guard.
-                        let current_value = unsafe { current_value.deref() };
-                        if no_replacement {
-                            // the key is not absent, so don't update because of
-                            // `no_replacement`, we don't use the new value, so we need to clean
-                            // it up and return it back to the caller
-                            // safety: we own value and did not share it
-                            return PutResult::Exists {
-                                current: current_value,
-                                not_inserted: unsafe { value.into_box() },
-                            };
+                    new_val = {
+                        let p = TreeNode::find_tree_node(root, hash, key, guard);
+                        if p.is_null() {
+                            // the given key is not present in the map
+                            None
                         } else {
-                            let now_garbage =
-                                tree_node.node.value.swap(value, Ordering::SeqCst, guard);
-                            // NOTE: now_garbage == current_value
+                            // a node for the given key exists, so we try to update it
+                            // safety: the TreeBin was read under our guard, at which point the tree
+                            // structure was valid. Since our guard marks the current thread as active,
+                            // the TreeNodes remain valid for at least as long as we hold onto the
+                            // guard.
+                            // Structurally, TreeNodes always point to TreeNodes, so this is sound.
+                            let n = &unsafe { TreeNode::get_tree_node(p) }.node;
+                            let current_value = n.value.load(Ordering::SeqCst, guard);
+
+                            // safety: since the value is present now, and we've held a guard from
+                            // the beginning of the search, the value cannot be dropped until after
+                            // we drop our guard.
+                            let new_value =
+                                remapping_function(&n.key, unsafe { current_value.deref() });
+
+                            if let Some(value) = new_value {
+                                let value = Shared::boxed(value, &self.collector);
+                                self.begin_update(n);
+                                let now_garbage = n.value.swap(value, Ordering::SeqCst, guard);
+                                self.finish_update(n);
+                                // NOTE: now_garbage == current_value
+
+                                // safety: need to guarantee that now_garbage is no longer
+                                // reachable. more specifically, no thread that executes _after_
+                                // this line can ever get a reference to now_garbage.
+                                //
+                                // here are the possible cases:
+                                //
+                                //  - another thread already has a reference to now_garbage.
+                                //    they must have read it before the call to swap while
+                                //    marked as active (holding a guard), and are included in
+                                //    the reference count. therefore t won't be freed until _after_
+                                //    it decrements the reference count, which can only happen
+                                //    when that thread drops its guard, and with it, any reference
+                                //    to the value.
+                                //  - another thread is about to get a reference to this value.
+                                //    they execute _after_ the swap, and therefore do _not_ get a
+                                //    reference to now_garbage (they get `value` instead). there are
+                                //    no other ways to get to a value except through its Node's
+                                //    `value` field (which is what we swapped), so freeing
+                                //    now_garbage is fine.
+                                unsafe { guard.retire_shared(now_garbage) };
+                                // safety: since the value is present now, and we've held a guard from
+                                // the beginning of the search, the value cannot be dropped until after
+                                // we drop our guard.
+                                Some(unsafe { value.deref() })
+                            } else {
+                                removed_node = true;
+                                // remove the BinEntry::TreeNode containing the removed key value pair from the bucket
+                                // also drop the old value stored in the tree node, as it was removed from the map
+                                // safety: `p` and its value are either marked for garbage collection in `remove_tree_node`
+                                // directly, or we will `need_to_untreeify`. In the latter case, we `defer_destroy`
+                                // both `p` and its value below, after storing the linear bin. Thus, everything is
+                                // always marked for garbage collection _after_ it becomes unaccessible by other threads.
+                                let need_to_untreeify = unsafe {
+                                    tree_bin.remove_tree_node(p, true, guard, &self.collector)
+                                };
+                                if need_to_untreeify {
+                                    let linear_bin = self.untreeify(
+                                        tree_bin.first.load(Ordering::SeqCst, guard),
+                                        guard,
+                                    );
+                                    t.store_bin(bini, linear_bin);
+                                    // the old bin is now garbage, but its values are not,
+                                    // since they are re-used in the linear bin.
+                                    // safety: in the same way as for `now_garbage` above, any existing
+                                    // references to `bin` must have been obtained before storing the
+                                    // linear bin. These references were obtained while holding a
+                                    // guard, and are protected until they drop it and decrement
+                                    // the reference count. After the store, threads will
+                                    // always see the linear bin, so the cannot obtain new references either.
+                                    //
+                                    // The same holds for `p` and its value, which does not get dropped together
+                                    // with `bin` here since `remove_tree_node` indicated that the bin needs to
+                                    // be untreeified.
+                                    unsafe {
+                                        TreeBin::defer_drop_without_values(bin, guard);
+                                        guard.retire_shared(p);
+                                        guard.retire_shared(current_value);
+                                    }
+                                }
+                                None
+                            }
+                        }
+                    };
+                    drop(bin_lock);
+                }
+                BinEntry::TreeNode(_) => unreachable!(
+                    "The head of a bin cannot be a TreeNode directly without BinEntry::Tree"
+                ),
+                BinEntry::Reservation(_) => {
+                    t.wait_for_reservation(bini, bin, guard);
+                    continue;
+                }
+            }
+            // NOTE: the Java code checks `bin_count` here because they also
+            // reach this point if the bin changed while obtaining the lock.
+            // However, our code doesn't (it uses continue) and making this
+            // break conditional confuses the compiler about how many times we
+            // use the `remapping_function`.
+            debug_assert_ne!(bin_count, 0);
+            break;
+        }
+        if removed_node {
+            // decrement count
+            self.add_count(-1, Some(bin_count), guard);
+        }
+        new_val.map(|linked| &**linked)
+    }
+
+    /// If the value for the specified `key` is present, replaces it with a new value computed from
+    /// the current one.
+    ///
+    /// This is [`compute_if_present`](HashMap::compute_if_present) for the common case where the
+    /// entry is always updated and never removed. The function runs atomically under the lock of
+    /// the bin that `key` falls into, so it should be short and simple.
+    ///
+    /// Returns the new value associated with the specified `key`, or `None` if no value for the
+    /// specified `key` is present, in which case `f` is not called.
+    ///
+    /// The key may be any borrowed form of the map's key type, but
+    /// [`Hash`] and [`Ord`] on the borrowed form *must* match those for
+    /// the key type.
+    ///
+    /// # Panics
+    ///
+    /// The bin that `key` falls into stays locked while the function runs. If the function
+    /// modifies the map in that same bin, for example by inserting or removing `key`, this method
+    /// panics rather than deadlocking.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// let guard = map.guard();
+    /// map.insert("sessions", vec![1, 2], &guard);
+    ///
+    /// let sessions = map.update(
+    ///     "sessions",
+    ///     |old| {
+    ///         let mut new = old.clone();
+    ///         new.push(3);
+    ///         new
+    ///     },
+    ///     &guard,
+    /// );
+    /// assert_eq!(sessions, Some(&vec![1, 2, 3]));
+    /// assert_eq!(map.update("users", |old| old.clone(), &guard), None);
+    /// ```
+    ///
+    /// [`Ord`]: std::cmp::Ord
+    /// [`Hash`]: std::hash::Hash
+    pub fn update<'g, Q, F>(&'g self, key: &Q, f: F, guard: &'g Guard<'_>) -> Option<&'g V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Ord,
+        F: FnOnce(&V) -> V,
+    {
+        self.compute_if_present(key, |_, value| Some(f(value)), guard)
+    }
 
-                            // safety: need to guarantee that now_garbage is no longer
-                            // reachable. more specifically, no thread that executes _after_
-                            // this line can ever get a reference to now_garbage.
-                            //
-                            // here are the possible cases:
-                            //
-                            //  - another thread already has a reference to now_garbage.
-                            //    they must have read it before the call to swap while
-                            //    marked as active (holding a guard), and are included in
-                            //    the reference count. therefore t won't be freed until _after_
-                            //    it decrements the reference count, which can only happen
-                            //    when that thread drops its guard, and with it, any reference
-                            //    to the value.
-                            //  - another thread is about to get a reference to this value.
-                            //    they execute _after_ the swap, and therefore do _not_ get a
-                            //    reference to now_garbage (they get `value` instead). there are
-                            //    no other ways to get to a value except through its Node's
-                            //    `value` field (which is what we swapped), so freeing
-                            //    now_garbage is fine.
-                            unsafe { guard.retire_shared(now_garbage) };
-                        }
-                        Some(current_value)
-                    };
-                    drop(head_lock);
+    /// Swaps the values of `key_a` and `key_b`.
+    ///
+    /// Returns `false`, and leaves the map unchanged, if either key is not present. Both keys'
+    /// bins are locked while the values are swapped, so no other update to either key can happen
+    /// in between, and writers never see just one of the two values moved. Readers do not take
+    /// bin locks, though, so a reader that looks up both keys while the swap happens may see one
+    /// of them swapped and the other not yet, or the same value under both keys.
+    ///
+    /// The bins are always locked in the same order, so concurrent swaps cannot deadlock each
+    /// other.
+    ///
+    /// The key may be any borrowed form of the map's key type, but
+    /// [`Hash`] and [`Ord`] on the borrowed form *must* match those for
+    /// the key type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// let guard = map.guard();
+    /// map.insert("left", 1, &guard);
+    /// map.insert("right", 2, &guard);
+    ///
+    /// assert!(map.swap("left", "right", &guard));
+    /// assert_eq!(map.get("left", &guard), Some(&2));
+    /// assert_eq!(map.get("right", &guard), Some(&1));
+    ///
+    /// assert!(!map.swap("left", "up", &guard));
+    /// assert_eq!(map.get("left", &guard), Some(&2));
+    /// ```
+    ///
+    /// [`Ord`]: std::cmp::Ord
+    /// [`Hash`]: std::hash::Hash
+    pub fn swap<Q>(&self, key_a: &Q, key_b: &Q, guard: &Guard<'_>) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Ord,
+    {
+        self.check_guard(guard);
+        let hash_a = self.hash(key_a);
+        let hash_b = self.hash(key_b);
+
+        loop {
+            let table = self.table.load(Ordering::SeqCst, guard);
+            // while the map is being resized, either key may already have been moved to the next
+            // table, so find the table that currently holds each key's bin
+            let (t_a, depth_a) = match Self::resolve_bin_table(table, hash_a, guard) {
+                Some(found) => found,
+                None => return false,
+            };
+            let (t_b, depth_b) = match Self::resolve_bin_table(table, hash_b, guard) {
+                Some(found) => found,
+                None => return false,
+            };
+            let bini_a = t_a.bini(hash_a);
+            let bini_b = t_b.bini(hash_b);
+
+            // always lock the bin in the older table, or with the lower index, first, so that two
+            // threads that lock the same two bins cannot each end up holding the lock the other
+            // one is waiting for. resizes lock the bin in the older table first too.
+            let ((first_t, first), (second_t, second)) = if (depth_a, bini_a) <= (depth_b, bini_b) {
+                ((t_a, bini_a), (t_b, bini_b))
+            } else {
+                ((t_b, bini_b), (t_a, bini_a))
+            };
+            let first_lock = match self.lock_bin(first_t, first, guard) {
+                LockedBin::Locked(lock) => lock,
+                LockedBin::Empty => return false,
+                LockedBin::Moved | LockedBin::Changed => continue,
+            };
+            let second_lock = if std::ptr::eq(first_t, second_t) && second == first {
+                None
+            } else {
+                match self.lock_bin(second_t, second, guard) {
+                    LockedBin::Locked(lock) => Some(lock),
+                    LockedBin::Empty => return false,
+                    LockedBin::Moved | LockedBin::Changed => continue,
                 }
-                BinEntry::TreeNode(_) => unreachable!(
-                    "The head of a bin cannot be a TreeNode directly without BinEntry::Tree"
-                ),
+            };
+
+            // both bins are locked, so neither can be moved or changed until we are done
+            let (node_a, node_b) = match (
+                Self::find_in_locked_bin(t_a, bini_a, hash_a, key_a, guard),
+                Self::find_in_locked_bin(t_b, bini_b, hash_b, key_b, guard),
+            ) {
+                (Some(node_a), Some(node_b)) => (node_a, node_b),
+                _ => return false,
+            };
+            if !std::ptr::eq(node_a, node_b) {
+                self.begin_update(node_a);
+                self.begin_update(node_b);
+                let value_a = node_a.value.load(Ordering::SeqCst, guard);
+                let value_b = node_b.value.swap(value_a, Ordering::SeqCst, guard);
+                node_a.value.store(value_b, Ordering::SeqCst);
+                self.finish_update(node_a);
+                self.finish_update(node_b);
             }
-            // NOTE: the Java code checks `bin_count` here because they also
-            // reach this point if the bin changed while obtaining the lock.
-            // However, our code doesn't (it uses continue) and `bin_count`
-            // _cannot_ be 0 at this point.
-            debug_assert_ne!(bin_count, 0);
-            if bin_count >= TREEIFY_THRESHOLD {
-                self.treeify_bin(t, bini, guard);
+            drop(second_lock);
+            drop(first_lock);
+            return true;
+        }
+    }
+
+    /// Follows moved bins from `table` to the table that holds the bin for `hash`, and returns
+    /// it along with the number of moved bins that were followed.
+    ///
+    /// Returns `None` if there is no table, or it has no bins.
+    fn resolve_bin_table<'g>(
+        mut table: Shared<'g, Table<K, V>>,
+        hash: u64,
+        guard: &'g Guard<'_>,
+    ) -> Option<(&'g Table<K, V>, usize)> {
+        let mut depth = 0;
+        loop {
+            if table.is_null() {
+                return None;
             }
-            if let Some(old_val) = old_val {
-                return PutResult::Replaced {
-                    old: old_val,
-                    // safety: we have not moved the node's value since we placed it into
-                    // its `Atomic` in the very beginning of the method, so the ref is still
-                    // valid. since the value is not currently marked as garbage, and since
-                    // `value` was loaded under a guard, the returned reference will remain valid
-                    // for the guard's lifetime.
-                    new: unsafe { value.deref() },
-                };
+            // safety: we loaded the table, or followed a moved bin to it, while the thread was
+            // marked as active, so it won't be deallocated until we drop our guard at the
+            // earliest. see also `Table::find`.
+            let t = unsafe { table.deref() };
+            if t.is_empty() {
+                return None;
             }
-            break;
+            let bin = t.bin(t.bini(hash), guard);
+            // safety: bin is a valid pointer, see the comment on the same match in
+            // `replace_node_if`.
+            if bin.is_null() || !matches!(**unsafe { bin.deref() }, BinEntry::Moved) {
+                return Some((t, depth));
+            }
+            table = t.next_table(guard);
+            depth += 1;
         }
-        // increment count, since we only get here if we did not return an old (updated) value
-        debug_assert!(old_val.is_none());
-        self.add_count(1, Some(bin_count), guard);
-        PutResult::Inserted {
-            // safety: we have not moved the node's value since we placed it into
-            // its `Atomic` in the very beginning of the method, so the ref is still
-            // valid. since the value is not currently marked as garbage, and since
-            // `value` was loaded under a guard, the returned reference will remain valid
-            // for the guard's lifetime.
-            new: unsafe { value.deref() },
+    }
+
+    /// Locks bin `bini` of `t`, unless it is empty, moved, or a reservation.
+    ///
+    /// If another thread is computing the value for a reserved bin, this waits for it to finish,
+    /// and returns [`LockedBin::Changed`].
+    fn lock_bin<'g>(&self, t: &'g Table<K, V>, bini: usize, guard: &'g Guard<'_>) -> LockedBin<'g> {
+        let bin = t.bin(bini, guard);
+        if bin.is_null() {
+            return LockedBin::Empty;
+        }
+        // safety: bin is a valid pointer, see the comment on the same match in `replace_node_if`.
+        let lock = match **unsafe { bin.deref() } {
+            BinEntry::Moved => return LockedBin::Moved,
+            BinEntry::Node(ref head) => head.lock.lock(),
+            BinEntry::Tree(ref tree_bin) => tree_bin.lock.lock(),
+            BinEntry::TreeNode(_) => unreachable!(
+                "The head of a bin cannot be a TreeNode directly without BinEntry::Tree"
+            ),
+            BinEntry::Reservation(_) => {
+                t.wait_for_reservation(bini, bin, guard);
+                return LockedBin::Changed;
+            }
+        };
+        // need to check that this is _still_ the head
+        if t.bin(bini, guard) != bin {
+            return LockedBin::Changed;
         }
+        LockedBin::Locked(lock)
     }
 
-    fn put_all<I: Iterator<Item = (K, V)>>(&self, iter: I, guard: &Guard<'_>) {
-        for (key, value) in iter {
-            self.put(key, value, false, guard);
+    /// Returns the node for `key` in bin `bini` of `t`, which must be locked by `lock_bin`.
+    fn find_in_locked_bin<'g, Q>(
+        t: &'g Table<K, V>,
+        bini: usize,
+        hash: u64,
+        key: &Q,
+        guard: &'g Guard<'_>,
+    ) -> Option<&'g Node<K, V>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        let bin = t.bin(bini, guard);
+        // safety: the bin is locked, so it is still the head of the bin that we read under our
+        // guard, and so are all the nodes reachable from it.
+        match **unsafe { bin.deref() } {
+            BinEntry::Node(_) => {
+                let mut e = bin;
+                while !e.is_null() {
+                    // safety: as above
+                    let n = unsafe { e.deref() }.as_node().unwrap();
+                    if n.hash == hash && n.key.borrow() == key {
+                        return Some(n);
+                    }
+                    e = n.next.load(Ordering::SeqCst, guard);
+                }
+                None
+            }
+            BinEntry::Tree(ref tree_bin) => {
+                let root = tree_bin.root.load(Ordering::SeqCst, guard);
+                if root.is_null() {
+                    return None;
+                }
+                let p = TreeNode::find_tree_node(root, hash, key, guard);
+                if p.is_null() {
+                    return None;
+                }
+                // safety: as above. structurally, TreeNodes always point to TreeNodes, so this is
+                // sound.
+                Some(&unsafe { TreeNode::get_tree_node(p) }.node)
+            }
+            BinEntry::Moved | BinEntry::TreeNode(_) | BinEntry::Reservation(_) => {
+                unreachable!("a locked bin is a linked bin or a tree bin")
+            }
         }
     }
 
-    /// If the value for the specified `key` is present, attempts to
-    /// compute a new mapping given the key and its current mapped value.
-    ///
-    /// The new mapping is computed by the `remapping_function`, which may
-    /// return `None` to signalize that the mapping should be removed.
-    /// The entire method invocation is performed atomically.
-    /// The supplied function is invoked exactly once per invocation of
-    /// this method if the key is present, else not at all.  Some
-    /// attempted update operations on this map by other threads may be
-    /// blocked while computation is in progress, so the computation
-    /// should be short and simple.
+    /// If the value for the specified `key` is present, clones it, lets `f` modify the clone while
+    /// the key's bin is locked, and replaces the stored value with the modified clone. Returns
+    /// what `f` returns.
     ///
-    /// Returns the new value associated with the specified `key`, or `None`
-    /// if no value for the specified `key` is present.
+    /// Readers do not take bin locks, and may still hold references to the current value, so it
+    /// cannot be modified in place. Every call therefore pays for a full [`Clone`] of the value,
+    /// which can be expensive for large values; if `f` only needs to build the new value from the
+    /// old one, [`update`](HashMap::update) avoids the intermediate clone. Other writers to
+    /// the same bin wait until `f` returns, so updates made through this method are never lost,
+    /// and readers see either the old or the new value, never a partially modified one. Like
+    /// [`update`](HashMap::update), this is [`compute_if_present`](HashMap::compute_if_present)
+    /// under the hood.
     ///
     /// The key may be any borrowed form of the map's key type, but
     /// [`Hash`] and [`Ord`] on the borrowed form *must* match those for
@@ -2009,24 +5587,108 @@ where
     ///
     /// [`Ord`]: std::cmp::Ord
     /// [`Hash`]: std::hash::Hash
-    pub fn compute_if_present<'g, Q, F>(
+    ///
+    /// # Panics
+    ///
+    /// The bin that `key` falls into stays locked while `f` runs. If `f` modifies the map in that
+    /// same bin, for example by inserting or removing `key`, this method panics rather than
+    /// deadlocking. `f` must not wait for another thread that modifies the map either, since that
+    /// thread may be waiting for the lock, and _that_ would deadlock.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// let guard = map.guard();
+    /// map.insert("sessions", vec![1, 2], &guard);
+    ///
+    /// let len = map.with_value_mut(
+    ///     "sessions",
+    ///     |sessions| {
+    ///         sessions.push(3);
+    ///         sessions.len()
+    ///     },
+    ///     &guard,
+    /// );
+    /// assert_eq!(len, Some(3));
+    /// assert_eq!(map.get("sessions", &guard), Some(&vec![1, 2, 3]));
+    /// assert_eq!(map.with_value_mut("users", |users| users.len(), &guard), None);
+    /// ```
+    pub fn with_value_mut<Q, F, R>(&self, key: &Q, f: F, guard: &Guard<'_>) -> Option<R>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Ord,
+        V: Clone,
+        F: FnOnce(&mut V) -> R,
+    {
+        let mut result = None;
+        self.compute_if_present(
+            key,
+            |_, value| {
+                let mut value = value.clone();
+                result = Some(f(&mut value));
+                Some(value)
+            },
+            guard,
+        );
+        result
+    }
+
+    /// Attempts to compute a mapping for the specified `key` and its current mapped value (or
+    /// `None` if there is no current mapping).
+    ///
+    /// The new mapping is computed by the `remapping_function`, which may return `None` to
+    /// signalize that the mapping should be removed (or not be inserted, if the key was absent).
+    /// The entire method invocation is performed atomically. The supplied function is invoked
+    /// exactly once per invocation of this method. Some attempted update operations on this map
+    /// by other threads may be blocked while computation is in progress, so the computation
+    /// should be short and simple, and must not attempt to update any other mappings of this map.
+    ///
+    /// Returns the new value associated with the specified `key`, or `None` if there is none.
+    ///
+    /// # Panics
+    ///
+    /// The bin that `key` falls into stays locked while the function runs. If the function
+    /// modifies the map in that same bin, for example by inserting or removing `key`, this method
+    /// panics rather than deadlocking.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// let mref = map.pin();
+    ///
+    /// // count the occurrences of each word
+    /// for word in ["a", "b", "a"] {
+    ///     mref.compute(word, |_, count| Some(count.map_or(1, |c| c + 1)));
+    /// }
+    /// assert_eq!(mref.get("a"), Some(&2));
+    /// assert_eq!(mref.get("b"), Some(&1));
+    ///
+    /// // returning `None` removes the mapping
+    /// assert_eq!(mref.compute("a", |_, _| None), None);
+    /// assert!(!mref.contains_key("a"));
+    /// ```
+    pub fn compute<'g, F>(
         &'g self,
-        key: &Q,
+        key: K,
         remapping_function: F,
         guard: &'g Guard<'_>,
     ) -> Option<&'g V>
     where
-        K: Borrow<Q>,
-        Q: ?Sized + Hash + Ord,
-        F: FnOnce(&K, &V) -> Option<V>,
+        F: FnOnce(&K, Option<&V>) -> Option<V>,
     {
         self.check_guard(guard);
         let hash = self.hash(&key);
 
         let mut table = self.table.load(Ordering::SeqCst, guard);
-        let mut new_val = None;
-        let mut removed_node = false;
-        let mut bin_count;
+        let new_val;
+        let mut delta = 0;
+        let bin_count;
         loop {
             // safety: see argument below for !is_null case
             if table.is_null() || unsafe { table.deref() }.is_empty() {
@@ -2055,8 +5717,15 @@ where
             let bini = t.bini(hash);
             let bin = t.bin(bini, guard);
             if bin.is_null() {
-                // fast path -- bin is empty so key is not present
-                return None;
+                // the bin is empty, so we reserve it while we compute the value
+                let reserved = match self.reserve_bin(t, bini, guard) {
+                    Some(reserved) => reserved,
+                    // someone else got to the bin first -- try again
+                    None => continue,
+                };
+                // if no value is computed, dropping the reservation empties the bin again
+                let value = remapping_function(&key, None)?;
+                return Some(self.fill_reserved_bin(reserved, hash, key, value));
             }
 
             // slow path -- bin is non-empty
@@ -2096,8 +5765,7 @@ where
                     // yes, it is still the head, so we can now "own" the bin
                     // note that there can still be readers in the bin!
 
-                    // TODO: ReservationNode
-                    bin_count = 1;
+                    let mut count = 1;
                     let mut p = bin;
                     let mut pred: Shared<'_, BinEntry<K, V>> = Shared::null();
 
@@ -2108,7 +5776,7 @@ where
                         let n = unsafe { p.deref() }.as_node().unwrap();
                         // TODO: This Ordering can probably be relaxed due to the Mutex
                         let next = n.next.load(Ordering::SeqCst, guard);
-                        if n.hash == hash && n.key.borrow() == key {
+                        if n.hash == hash && n.key == key {
                             // the key already exists in the map!
                             let current_value = n.value.load(Ordering::SeqCst, guard);
 
@@ -2116,11 +5784,13 @@ where
                             // the beginning of the search, the value cannot be dropped until after
                             // we drop our guard.
                             let new_value =
-                                remapping_function(&n.key, unsafe { current_value.deref() });
+                                remapping_function(&n.key, Some(unsafe { current_value.deref() }));
 
                             if let Some(value) = new_value {
                                 let value = Shared::boxed(value, &self.collector);
+                                self.begin_update(n);
                                 let now_garbage = n.value.swap(value, Ordering::SeqCst, guard);
+                                self.finish_update(n);
                                 // NOTE: now_garbage == current_value
 
                                 // safety: need to guarantee that now_garbage is no longer
@@ -2143,13 +5813,9 @@ where
                                 //    `value` field (which is what we swapped), so freeing
                                 //    now_garbage is fine.
                                 unsafe { guard.retire_shared(now_garbage) };
-
-                                // safety: since the value is present now, and we've held a guard from
-                                // the beginning of the search, the value cannot be dropped until after
-                                // we drop our guard.
-                                break Some(unsafe { value.deref() });
+                                break Some(value);
                             } else {
-                                removed_node = true;
+                                delta = -1;
                                 // remove the BinEntry containing the removed key value pair from the bucket
                                 if !pred.is_null() {
                                     // either by changing the pointer of the previous BinEntry, if present
@@ -2164,26 +5830,12 @@ where
                                     t.store_bin(bini, next);
                                 }
 
-                                // in either case, mark the BinEntry as garbage, since it was just removed
-                                // safety: need to guarantee that the old value is no longer
-                                // reachable. more specifically, no thread that executes _after_
-                                // this line can ever get a reference to val.
-                                //
-                                // here are the possible cases:
-                                //
-                                //  - another thread already has a reference to now_garbage.
-                                //    they must have read it before the call to swap while
-                                //    marked as active (holding a guard), and are included in
-                                //    the reference count. therefore t won't be freed until _after_
-                                //    it decrements the reference count, which can only happen
-                                //    when that thread drops its guard, and with it, any reference
-                                //    to the value.
-                                //  - another thread is about to get a reference to this value.
-                                //    they execute _after_ the swap, and therefore do _not_ get a
-                                //    reference to now_garbage (they get `value` instead). there are
-                                //    no other ways to get to a value except through its Node's
-                                //    `value` field (which is what we swapped), so freeing
-                                //    now_garbage is fine.
+                                // in either case, mark the BinEntry as garbage, since it was just
+                                // removed.
+                                // safety: as for now_garbage above, no thread that executes
+                                // _after_ the store above can get a reference to the removed node
+                                // or its value, and threads that got one before are protected by
+                                // their guards.
                                 unsafe { guard.retire_shared(p) };
                                 unsafe { guard.retire_shared(current_value) };
                                 break None;
@@ -2192,14 +5844,27 @@ where
 
                         pred = p;
                         if next.is_null() {
-                            // we're at the end of the bin
-                            break None;
+                            // we're at the end of the bin and the key is not present -- stick
+                            // the new node here, if there is one!
+                            let value = remapping_function(&key, None).map(|value| {
+                                let value = Shared::boxed(value, &self.collector);
+                                let node = Shared::boxed(
+                                    BinEntry::Node(Node::new(hash, key, value)),
+                                    &self.collector,
+                                );
+                                n.next.store(node, Ordering::SeqCst);
+                                self.finish_update(unsafe { node.deref() }.as_node().unwrap());
+                                delta = 1;
+                                value
+                            });
+                            break value;
                         }
                         p = next;
 
-                        bin_count += 1;
+                        count += 1;
                     };
                     drop(head_lock);
+                    bin_count = count;
                 }
                 BinEntry::Tree(ref tree_bin) => {
                     // bin is non-empty, need to link into it, so we must take the lock
@@ -2209,108 +5874,103 @@ where
                     let current_head = t.bin(bini, guard);
                     if current_head != bin {
                         // nope -- try again from the start
-                        continue;
-                    }
-
-                    // yes, it is still the head, so we can now "own" the bin
-                    // note that there can still be readers in the bin!
-                    bin_count = 2;
-                    let root = tree_bin.root.load(Ordering::SeqCst, guard);
-                    if root.is_null() {
-                        // TODO: Is this even reachable?
-                        // The Java code has `NULL` checks for this, but in theory it should not be possible to
-                        // encounter a tree that has no root when we have its lock. It should always have at
-                        // least `UNTREEIFY_THRESHOLD` nodes. If it is indeed impossible we should replace
-                        // this with an assertion instead.
-                        break;
-                    }
-                    new_val = {
-                        let p = TreeNode::find_tree_node(root, hash, key, guard);
-                        if p.is_null() {
-                            // the given key is not present in the map
-                            None
-                        } else {
-                            // a node for the given key exists, so we try to update it
-                            // safety: the TreeBin was read under our guard, at which point the tree
-                            // structure was valid. Since our guard marks the current thread as active,
-                            // the TreeNodes remain valid for at least as long as we hold onto the
-                            // guard.
-                            // Structurally, TreeNodes always point to TreeNodes, so this is sound.
-                            let n = &unsafe { TreeNode::get_tree_node(p) }.node;
-                            let current_value = n.value.load(Ordering::SeqCst, guard);
+                        continue;
+                    }
 
-                            // safety: since the value is present now, and we've held a guard from
-                            // the beginning of the search, the value cannot be dropped until after
-                            // we drop our guard.
-                            let new_value =
-                                remapping_function(&n.key, unsafe { current_value.deref() });
+                    // yes, it is still the head, so we can now "own" the bin
+                    // note that there can still be readers in the bin!
 
-                            if let Some(value) = new_value {
+                    // we don't actually count bins, just set this low enough
+                    // that we don't try to treeify the bin later
+                    bin_count = 2;
+                    let root = tree_bin.root.load(Ordering::SeqCst, guard);
+                    let p = if root.is_null() {
+                        Shared::null()
+                    } else {
+                        TreeNode::find_tree_node(root, hash, &key, guard)
+                    };
+                    new_val = if p.is_null() {
+                        // the given key is not present in the map, so we put the new value (if any)
+                        match remapping_function(&key, None) {
+                            Some(value) => {
                                 let value = Shared::boxed(value, &self.collector);
-                                let now_garbage = n.value.swap(value, Ordering::SeqCst, guard);
-                                // NOTE: now_garbage == current_value
+                                let p = tree_bin.find_or_put_tree_val(
+                                    hash,
+                                    key,
+                                    value,
+                                    guard,
+                                    &self.collector,
+                                );
+                                // we hold the bin lock, so no other thread can have inserted the
+                                // key since we searched for it above
+                                assert!(p.is_null());
+                                self.finish_tree_insert(tree_bin, guard);
+                                delta = 1;
+                                Some(value)
+                            }
+                            None => None,
+                        }
+                    } else {
+                        // a node for the given key exists, so we try to update it
+                        // safety: the TreeBin was read under our guard, at which point the tree
+                        // structure was valid. Since our guard marks the current thread as active,
+                        // the TreeNodes remain valid for at least as long as we hold onto the
+                        // guard.
+                        // Structurally, TreeNodes always point to TreeNodes, so this is sound.
+                        let n = &unsafe { TreeNode::get_tree_node(p) }.node;
+                        let current_value = n.value.load(Ordering::SeqCst, guard);
 
-                                // safety: need to guarantee that now_garbage is no longer
-                                // reachable. more specifically, no thread that executes _after_
-                                // this line can ever get a reference to now_garbage.
-                                //
-                                // here are the possible cases:
+                        // safety: since the value is present now, and we've held a guard from
+                        // the beginning of the search, the value cannot be dropped until after
+                        // we drop our guard.
+                        let new_value =
+                            remapping_function(&n.key, Some(unsafe { current_value.deref() }));
+
+                        if let Some(value) = new_value {
+                            let value = Shared::boxed(value, &self.collector);
+                            self.begin_update(n);
+                            let now_garbage = n.value.swap(value, Ordering::SeqCst, guard);
+                            self.finish_update(n);
+                            // NOTE: now_garbage == current_value
+
+                            // safety: need to guarantee that now_garbage is no longer reachable.
+                            // see the equivalent case for linear bins above.
+                            unsafe { guard.retire_shared(now_garbage) };
+                            Some(value)
+                        } else {
+                            delta = -1;
+                            // remove the BinEntry::TreeNode containing the removed key value pair from the bucket
+                            // also drop the old value stored in the tree node, as it was removed from the map
+                            // safety: `p` and its value are either marked for garbage collection in `remove_tree_node`
+                            // directly, or we will `need_to_untreeify`. In the latter case, we `defer_destroy`
+                            // both `p` and its value below, after storing the linear bin. Thus, everything is
+                            // always marked for garbage collection _after_ it becomes unaccessible by other threads.
+                            let need_to_untreeify = unsafe {
+                                tree_bin.remove_tree_node(p, true, guard, &self.collector)
+                            };
+                            if need_to_untreeify {
+                                let linear_bin = self
+                                    .untreeify(tree_bin.first.load(Ordering::SeqCst, guard), guard);
+                                t.store_bin(bini, linear_bin);
+                                // the old bin is now garbage, but its values are not,
+                                // since they are re-used in the linear bin.
+                                // safety: in the same way as for `now_garbage` above, any existing
+                                // references to `bin` must have been obtained before storing the
+                                // linear bin. These references were obtained while holding a
+                                // guard, and are protected until they drop it and decrement
+                                // the reference count. After the store, threads will
+                                // always see the linear bin, so the cannot obtain new references either.
                                 //
-                                //  - another thread already has a reference to now_garbage.
-                                //    they must have read it before the call to swap while
-                                //    marked as active (holding a guard), and are included in
-                                //    the reference count. therefore t won't be freed until _after_
-                                //    it decrements the reference count, which can only happen
-                                //    when that thread drops its guard, and with it, any reference
-                                //    to the value.
-                                //  - another thread is about to get a reference to this value.
-                                //    they execute _after_ the swap, and therefore do _not_ get a
-                                //    reference to now_garbage (they get `value` instead). there are
-                                //    no other ways to get to a value except through its Node's
-                                //    `value` field (which is what we swapped), so freeing
-                                //    now_garbage is fine.
-                                unsafe { guard.retire_shared(now_garbage) };
-                                // safety: since the value is present now, and we've held a guard from
-                                // the beginning of the search, the value cannot be dropped until after
-                                // we drop our guard.
-                                Some(unsafe { value.deref() })
-                            } else {
-                                removed_node = true;
-                                // remove the BinEntry::TreeNode containing the removed key value pair from the bucket
-                                // also drop the old value stored in the tree node, as it was removed from the map
-                                // safety: `p` and its value are either marked for garbage collection in `remove_tree_node`
-                                // directly, or we will `need_to_untreeify`. In the latter case, we `defer_destroy`
-                                // both `p` and its value below, after storing the linear bin. Thus, everything is
-                                // always marked for garbage collection _after_ it becomes unaccessible by other threads.
-                                let need_to_untreeify = unsafe {
-                                    tree_bin.remove_tree_node(p, true, guard, &self.collector)
-                                };
-                                if need_to_untreeify {
-                                    let linear_bin = self.untreeify(
-                                        tree_bin.first.load(Ordering::SeqCst, guard),
-                                        guard,
-                                    );
-                                    t.store_bin(bini, linear_bin);
-                                    // the old bin is now garbage, but its values are not,
-                                    // since they are re-used in the linear bin.
-                                    // safety: in the same way as for `now_garbage` above, any existing
-                                    // references to `bin` must have been obtained before storing the
-                                    // linear bin. These references were obtained while holding a
-                                    // guard, and are protected until they drop it and decrement
-                                    // the reference count. After the store, threads will
-                                    // always see the linear bin, so the cannot obtain new references either.
-                                    //
-                                    // The same holds for `p` and its value, which does not get dropped together
-                                    // with `bin` here since `remove_tree_node` indicated that the bin needs to
-                                    // be untreeified.
-                                    unsafe {
-                                        TreeBin::defer_drop_without_values(bin, guard);
-                                        guard.retire_shared(p);
-                                        guard.retire_shared(current_value);
-                                    }
+                                // The same holds for `p` and its value, which does not get dropped together
+                                // with `bin` here since `remove_tree_node` indicated that the bin needs to
+                                // be untreeified.
+                                unsafe {
+                                    TreeBin::defer_drop_without_values(bin, guard);
+                                    guard.retire_shared(p);
+                                    guard.retire_shared(current_value);
                                 }
-                                None
                             }
+                            None
                         }
                     };
                     drop(bin_lock);
@@ -2318,23 +5978,248 @@ where
                 BinEntry::TreeNode(_) => unreachable!(
                     "The head of a bin cannot be a TreeNode directly without BinEntry::Tree"
                 ),
+                BinEntry::Reservation(_) => {
+                    t.wait_for_reservation(bini, bin, guard);
+                    continue;
+                }
+            }
+
+            if delta > 0 && bin_count >= TREEIFY_THRESHOLD {
+                self.treeify_bin(t, bini, guard);
             }
-            // NOTE: the Java code checks `bin_count` here because they also
-            // reach this point if the bin changed while obtaining the lock.
-            // However, our code doesn't (it uses continue) and making this
-            // break conditional confuses the compiler about how many times we
-            // use the `remapping_function`.
-            debug_assert_ne!(bin_count, 0);
             break;
         }
-        if removed_node {
-            // decrement count
-            self.add_count(-1, Some(bin_count), guard);
+
+        if delta != 0 {
+            self.add_count(delta, Some(bin_count), guard);
         }
-        new_val.map(|linked| &**linked)
+        // safety: the new value was allocated under our guard, and is only retired once it has
+        // been removed from the map, which can only happen after it was stored. since we still
+        // hold the guard, the returned reference will remain valid for the guard's lifetime.
+        new_val.map(|v| unsafe { &**v.deref() })
+    }
+
+    /// If the specified `key` is not already associated with a value, associates it with the
+    /// given `value`. Otherwise, replaces the value with the result of the given
+    /// `remapping_function`, or removes the mapping if the function returns `None`.
+    ///
+    /// The `remapping_function` is given the current value and the given `value`, in that
+    /// order. The entire method invocation is performed atomically. Some attempted update
+    /// operations on this map by other threads may be blocked while computation is in progress,
+    /// so the computation should be short and simple, and must not attempt to update any other
+    /// mappings of this map.
+    ///
+    /// Returns the new value associated with the specified `key`, or `None` if there is none.
+    ///
+    /// # Panics
+    ///
+    /// The bin that `key` falls into stays locked while the function runs. If the function
+    /// modifies the map in that same bin, for example by inserting or removing `key`, this method
+    /// panics rather than deadlocking.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// let mref = map.pin();
+    ///
+    /// assert_eq!(mref.merge("a", 1, |old, new| Some(old + new)), Some(&1));
+    /// assert_eq!(mref.merge("a", 2, |old, new| Some(old + new)), Some(&3));
+    /// assert_eq!(mref.merge("a", 0, |_, _| None), None);
+    /// assert!(mref.is_empty());
+    /// ```
+    pub fn merge<'g, F>(
+        &'g self,
+        key: K,
+        value: V,
+        remapping_function: F,
+        guard: &'g Guard<'_>,
+    ) -> Option<&'g V>
+    where
+        F: FnOnce(&V, &V) -> Option<V>,
+    {
+        self.compute(
+            key,
+            |_, old| match old {
+                None => Some(value),
+                Some(old) => remapping_function(old, &value),
+            },
+            guard,
+        )
+    }
+
+    /// Removes a key-value pair from the map, and returns the removed value (if any).
+    ///
+    /// The key may be any borrowed form of the map's key type, but
+    /// [`Hash`] and [`Ord`] on the borrowed form *must* match those for
+    /// the key type.
+    ///
+    /// [`Ord`]: std::cmp::Ord
+    /// [`Hash`]: std::hash::Hash
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// map.pin().insert(1, "a");
+    /// assert_eq!(map.pin().remove(&1), Some(&"a"));
+    /// assert_eq!(map.pin().remove(&1), None);
+    /// ```
+    pub fn remove<'g, Q>(&'g self, key: &Q, guard: &'g Guard<'_>) -> Option<&'g V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Ord,
+    {
+        // NOTE: _technically_, this method shouldn't require the thread-safety bounds, but a) that
+        // would require special-casing replace_node for when new_value.is_none(), and b) it's sort
+        // of useless to call remove on a collection that you know you can never insert into.
+        self.check_guard(guard);
+        self.replace_node(key, None, None, guard).map(|(_, v)| v)
+    }
+
+    /// Removes a key-value pair from the map, and returns a clone of the removed value (if any).
+    ///
+    /// Other threads may still be reading the removed value through references they obtained
+    /// before it was removed, so the value cannot be moved out of the map. Instead, it is cloned,
+    /// and the original is dropped once no guard can observe it any more. If you only need the
+    /// value while `guard` is held, [`HashMap::remove`] avoids the clone.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// map.pin().insert(1, String::from("a"));
+    ///
+    /// let evicted = map.remove_owned(&1, &map.guard());
+    /// assert_eq!(evicted, Some(String::from("a")));
+    /// assert!(map.pin().is_empty());
+    /// ```
+    pub fn remove_owned<Q>(&self, key: &Q, guard: &Guard<'_>) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Ord,
+        V: Clone,
+    {
+        self.check_guard(guard);
+        self.replace_node(key, None, None, guard)
+            .map(|(_, v)| v.clone())
+    }
+
+    /// Removes each of the given keys from the map, and returns the number of entries that were
+    /// actually removed.
+    ///
+    /// This is equivalent to calling [`remove`](HashMap::remove) for each key, but checks the
+    /// guard only once. Keys that are not in the map, or that appear more than once in `keys`,
+    /// are not counted more than once.
+    ///
+    /// The key may be any borrowed form of the map's key type, but
+    /// [`Hash`] and [`Ord`] on the borrowed form *must* match those for
+    /// the key type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map: HashMap<_, _> = (0..10).map(|i| (i, i)).collect();
+    /// let guard = map.guard();
+    ///
+    /// assert_eq!(map.remove_many(&[1, 2, 2, 42], &guard), 2);
+    /// assert_eq!(map.len(), 8);
+    /// ```
+    ///
+    /// [`Ord`]: std::cmp::Ord
+    /// [`Hash`]: std::hash::Hash
+    pub fn remove_many<'q, Q, I>(&self, keys: I, guard: &Guard<'_>) -> usize
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Ord + 'q,
+        I: IntoIterator<Item = &'q Q>,
+    {
+        self.check_guard(guard);
+        keys.into_iter()
+            .filter(|key| self.replace_node(*key, None, None, guard).is_some())
+            .count()
+    }
+
+    /// Removes a key from the map, returning the stored key and value if the
+    /// key was previously in the map.
+    ///
+    /// The key may be any borrowed form of the map's key type, but
+    /// [`Hash`] and [`Ord`] on the borrowed form *must* match those for
+    /// the key type.
+    ///
+    /// [`Ord`]: std::cmp::Ord
+    /// [`Hash`]: std::hash::Hash
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// let guard = map.guard();
+    /// map.insert(1, "a", &guard);
+    /// assert_eq!(map.remove_entry(&1, &guard), Some((&1, &"a")));
+    /// assert_eq!(map.remove(&1, &guard), None);
+    /// ```
+    pub fn remove_entry<'g, Q>(&'g self, key: &Q, guard: &'g Guard<'_>) -> Option<(&'g K, &'g V)>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Ord,
+    {
+        self.check_guard(guard);
+        self.replace_node(key, None, None, guard)
+    }
+
+    /// Replaces the value for `key` with `value`, but only if the key is already present, and
+    /// returns the previous value.
+    ///
+    /// Unlike [`HashMap::insert`], this never adds a new key to the map; if the key is absent,
+    /// `value` is dropped and `None` is returned.
+    ///
+    /// The key may be any borrowed form of the map's key type, but
+    /// [`Hash`] and [`Ord`] on the borrowed form *must* match those for
+    /// the key type.
+    ///
+    /// [`Ord`]: std::cmp::Ord
+    /// [`Hash`]: std::hash::Hash
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// let guard = map.guard();
+    /// assert_eq!(map.replace(&1, "a", &guard), None);
+    /// assert!(!map.contains_key(&1, &guard));
+    ///
+    /// map.insert(1, "a", &guard);
+    /// assert_eq!(map.replace(&1, "b", &guard), Some(&"a"));
+    /// assert_eq!(map.get(&1, &guard), Some(&"b"));
+    /// ```
+    pub fn replace<'g, Q>(&'g self, key: &Q, value: V, guard: &'g Guard<'_>) -> Option<&'g V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Ord,
+    {
+        self.check_guard(guard);
+        self.replace_node(key, Some(value), None, guard)
+            .map(|(_, v)| v)
     }
 
-    /// Removes a key-value pair from the map, and returns the removed value (if any).
+    /// Replaces the value for `key` with `new`, but only if the current value equals `expected`.
+    ///
+    /// The comparison and the swap happen while holding the lock of the bin that contains the key,
+    /// so the value cannot change in between. On success, the previous value is returned in `Ok`.
+    /// If the key is absent or its value differs from `expected`, `new` is handed back in `Err`.
     ///
     /// The key may be any borrowed form of the map's key type, but
     /// [`Hash`] and [`Ord`] on the borrowed form *must* match those for
@@ -2349,24 +6234,48 @@ where
     /// use flurry::HashMap;
     ///
     /// let map = HashMap::new();
-    /// map.pin().insert(1, "a");
-    /// assert_eq!(map.pin().remove(&1), Some(&"a"));
-    /// assert_eq!(map.pin().remove(&1), None);
+    /// let guard = map.guard();
+    /// map.insert(1, 10, &guard);
+    /// assert_eq!(map.replace_if_eq(&1, &0, 11, &guard), Err(11));
+    /// assert_eq!(map.replace_if_eq(&1, &10, 11, &guard), Ok(Some(&10)));
+    /// assert_eq!(map.get(&1, &guard), Some(&11));
+    /// assert_eq!(map.replace_if_eq(&2, &0, 1, &guard), Err(1));
     /// ```
-    pub fn remove<'g, Q>(&'g self, key: &Q, guard: &'g Guard<'_>) -> Option<&'g V>
+    pub fn replace_if_eq<'g, Q>(
+        &'g self,
+        key: &Q,
+        expected: &V,
+        new: V,
+        guard: &'g Guard<'_>,
+    ) -> Result<Option<&'g V>, V>
     where
         K: Borrow<Q>,
         Q: ?Sized + Hash + Ord,
+        V: PartialEq,
     {
-        // NOTE: _technically_, this method shouldn't require the thread-safety bounds, but a) that
-        // would require special-casing replace_node for when new_value.is_none(), and b) it's sort
-        // of useless to call remove on a collection that you know you can never insert into.
         self.check_guard(guard);
-        self.replace_node(key, None, None, guard).map(|(_, v)| v)
+        let mut new_value = Some(new);
+        // safety: the value was read under our guard while present in the map, so it cannot
+        // be dropped until after we drop our guard.
+        let old = self.replace_node_if(
+            key,
+            &mut new_value,
+            |_, current| &**unsafe { current.deref() } == expected,
+            guard,
+        );
+        match new_value {
+            // the new value was not taken, so no replacement happened
+            Some(new) => Err(new),
+            None => Ok(old.map(|(_, v)| v)),
+        }
     }
 
-    /// Removes a key from the map, returning the stored key and value if the
-    /// key was previously in the map.
+    /// Removes a key from the map if `predicate` returns `true` for its current value, and returns
+    /// the removed value (if any).
+    ///
+    /// The predicate is evaluated while holding the lock of the bin that contains the key, so no
+    /// other thread can change the value between the check and the removal. It is called at most
+    /// once, and not at all if the key is absent.
     ///
     /// The key may be any borrowed form of the map's key type, but
     /// [`Hash`] and [`Ord`] on the borrowed form *must* match those for
@@ -2383,16 +6292,72 @@ where
     /// let map = HashMap::new();
     /// let guard = map.guard();
     /// map.insert(1, "a", &guard);
-    /// assert_eq!(map.remove_entry(&1, &guard), Some((&1, &"a")));
-    /// assert_eq!(map.remove(&1, &guard), None);
+    /// assert_eq!(map.remove_if(&1, |_, v| *v == "b", &guard), None);
+    /// assert_eq!(map.remove_if(&1, |_, v| *v == "a", &guard), Some(&"a"));
+    /// assert!(!map.contains_key(&1, &guard));
     /// ```
-    pub fn remove_entry<'g, Q>(&'g self, key: &Q, guard: &'g Guard<'_>) -> Option<(&'g K, &'g V)>
+    pub fn remove_if<'g, Q, F>(
+        &'g self,
+        key: &Q,
+        predicate: F,
+        guard: &'g Guard<'_>,
+    ) -> Option<&'g V>
     where
         K: Borrow<Q>,
         Q: ?Sized + Hash + Ord,
+        F: FnOnce(&K, &V) -> bool,
     {
         self.check_guard(guard);
-        self.replace_node(key, None, None, guard)
+        self.replace_node_if(
+            key,
+            &mut None,
+            // safety: the value was read under our guard while present in the map, so it cannot
+            // be dropped until after we drop our guard.
+            |k, v| predicate(k, &**unsafe { v.deref() }),
+            guard,
+        )
+        .map(|(_, v)| v)
+    }
+
+    /// Removes a key from the map if `predicate` returns `true` for its current value, and returns
+    /// a clone of the removed value (if any).
+    ///
+    /// This combines [`remove_if`](HashMap::remove_if) with [`remove_owned`](HashMap::remove_owned):
+    /// the predicate is evaluated while holding the lock of the bin that contains the key, so of
+    /// several threads that race to take the same entry, at most one succeeds. See
+    /// [`remove_owned`](HashMap::remove_owned) for why the value is cloned rather than moved out.
+    ///
+    /// The key may be any borrowed form of the map's key type, but
+    /// [`Hash`] and [`Ord`] on the borrowed form *must* match those for
+    /// the key type.
+    ///
+    /// [`Ord`]: std::cmp::Ord
+    /// [`Hash`]: std::hash::Hash
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let jobs = HashMap::new();
+    /// let guard = jobs.guard();
+    /// jobs.insert(1, (String::from("resize images"), true), &guard);
+    /// jobs.insert(2, (String::from("send emails"), false), &guard);
+    ///
+    /// // only claim jobs that are ready
+    /// let claimed = jobs.take_if(&1, |_, (_, ready)| *ready, &guard);
+    /// assert_eq!(claimed, Some((String::from("resize images"), true)));
+    /// assert_eq!(jobs.take_if(&2, |_, (_, ready)| *ready, &guard), None);
+    /// assert_eq!(jobs.len(), 1);
+    /// ```
+    pub fn take_if<Q, F>(&self, key: &Q, predicate: F, guard: &Guard<'_>) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Ord,
+        F: FnOnce(&K, &V) -> bool,
+        V: Clone,
+    {
+        self.remove_if(key, predicate, guard).cloned()
     }
 
     /// Replaces node value with `new_value`.
@@ -2422,6 +6387,37 @@ where
     where
         K: Borrow<Q>,
         Q: ?Sized + Hash + Ord,
+    {
+        let mut new_value = new_value;
+        self.replace_node_if(
+            key,
+            &mut new_value,
+            |_, current| observed_value.map(|ov| ov == current).unwrap_or(true),
+            guard,
+        )
+    }
+
+    /// Replaces node value with `new_value` if `condition` holds for the current key and value.
+    ///
+    /// `condition` is evaluated at most once, while holding the lock of the bin that contains the
+    /// key, so the value cannot change between the check and the replacement.
+    ///
+    /// If `new_value` is `None`, it removes the key (and its corresponding value) from this map.
+    /// Otherwise, `new_value` is only taken if the replacement happens, so the caller gets it back
+    /// if the key is absent or `condition` does not hold.
+    ///
+    /// Returns the previous key and value associated with the given key.
+    fn replace_node_if<'g, Q, F>(
+        &'g self,
+        key: &Q,
+        new_value: &mut Option<V>,
+        condition: F,
+        guard: &'g Guard<'_>,
+    ) -> Option<(&'g K, &'g V)>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Ord,
+        F: FnOnce(&'g K, Shared<'g, V>) -> bool,
     {
         let hash = self.hash(key);
 
@@ -2501,17 +6497,19 @@ where
                         if n.hash == hash && n.key.borrow() == key {
                             let ev = n.value.load(Ordering::SeqCst, guard);
 
-                            // only replace the node if the value is the one we expected at method call
-                            if observed_value.map(|ov| ov == ev).unwrap_or(true) {
+                            // only replace the node if the caller's condition holds
+                            if condition(&n.key, ev) {
                                 // we remember the old value so that we can return it and mark it for deletion below
                                 old_val = Some((&n.key, ev));
 
                                 // found the node but we have a new value to replace the old one
-                                if let Some(nv) = new_value {
+                                if let Some(nv) = new_value.take() {
+                                    self.begin_update(n);
                                     n.value.store(
                                         Shared::boxed(nv, &self.collector),
                                         Ordering::SeqCst,
                                     );
+                                    self.finish_update(n);
                                     // we are just replacing entry value and we do not want to remove the node
                                     // so we stop iterating here
                                     break;
@@ -2580,15 +6578,17 @@ where
                     let n = &unsafe { TreeNode::get_tree_node(p) }.node;
                     let pv = n.value.load(Ordering::SeqCst, guard);
 
-                    // only replace the node if the value is the one we expected at method call
-                    if observed_value.map(|ov| ov == pv).unwrap_or(true) {
+                    // only replace the node if the caller's condition holds
+                    if condition(&n.key, pv) {
                         // we remember the old value so that we can return it and mark it for deletion below
                         old_val = Some((&n.key, pv));
 
-                        if let Some(nv) = new_value {
+                        if let Some(nv) = new_value.take() {
                             // found the node but we have a new value to replace the old one
+                            self.begin_update(n);
                             n.value
                                 .store(Shared::boxed(nv, &self.collector), Ordering::SeqCst);
+                            self.finish_update(n);
                         } else {
                             // drop `p` without its value, since the old value is dropped
                             // in the check on `old_val` below
@@ -2620,6 +6620,10 @@ where
                 BinEntry::TreeNode(_) => unreachable!(
                     "The head of a bin cannot be a TreeNode directly without BinEntry::Tree"
                 ),
+                BinEntry::Reservation(_) => {
+                    t.wait_for_reservation(bini, bin, guard);
+                    continue;
+                }
             }
             if let Some((key, val)) = old_val {
                 if is_remove {
@@ -2654,53 +6658,456 @@ where
             }
             break;
         }
-        None
+        None
+    }
+
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// In other words, remove all pairs `(k, v)` such that `f(&k,&v)` returns `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::new();
+    ///
+    /// for i in 0..8 {
+    ///     map.pin().insert(i, i*10);
+    /// }
+    /// map.pin().retain(|&k, _| k % 2 == 0);
+    /// assert_eq!(map.pin().len(), 4);
+    /// ```
+    ///
+    /// # Notes
+    ///
+    /// If `f` returns `false` for a given key/value pair, but the value for that pair is concurrently
+    /// modified before the removal takes place, the entry will not be removed.
+    /// If you want the removal to happen even in the case of concurrent modification, use [`HashMap::retain_force`].
+    ///
+    /// # Evicting unused `Arc`s
+    ///
+    /// A cache of [`Arc`] values can drop the entries that nobody outside the map holds on to
+    /// with `retain(|_, v| Arc::strong_count(v) > 1, guard)`. An entry is never evicted while a
+    /// clone of its `Arc` that was made before the check is still alive, and an entry that is
+    /// replaced concurrently is left alone, as described above.
+    ///
+    /// However, [`get`](HashMap::get) hands out a reference to the `Arc`, not a clone, so a reader
+    /// that has looked up an entry but not cloned it yet does not count towards the strong count.
+    /// Its entry may be evicted, and the reader then ends up with a clone of a value that is no
+    /// longer in the map. That is memory safe, since the value stays alive for as long as the
+    /// reader's guard does, but the next lookup will miss. Readers that must not lose their entry
+    /// should clone the `Arc` immediately, and re-insert it with
+    /// [`get_or_insert`](HashMap::get_or_insert) if they find it evicted.
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    /// use std::sync::Arc;
+    ///
+    /// let cache = HashMap::new();
+    /// let guard = cache.guard();
+    /// cache.insert(1, Arc::new("in use"), &guard);
+    /// cache.insert(2, Arc::new("unused"), &guard);
+    ///
+    /// let in_use = Arc::clone(cache.get(&1, &guard).unwrap());
+    /// cache.retain(|_, v| Arc::strong_count(v) > 1, &guard);
+    ///
+    /// assert!(cache.contains_key(&1, &guard));
+    /// assert!(!cache.contains_key(&2, &guard));
+    /// # drop(in_use);
+    /// ```
+    pub fn retain<F>(&self, mut f: F, guard: &Guard<'_>)
+    where
+        F: FnMut(&K, &V) -> bool,
+    {
+        self.check_guard(guard);
+        let mut iter = self.iter(guard);
+        while let Some((k, v)) = iter.next_internal() {
+            // safety: flurry does not drop or move until after guard drop
+            let value = unsafe { v.deref() };
+            if !f(k, value) {
+                self.replace_node(k, None, Some(v), guard);
+            }
+        }
+    }
+
+    /// Retains only the elements specified by the predicate, removing at most `max_removals`
+    /// entries.
+    ///
+    /// This behaves like [`HashMap::retain`], except that it stops calling `f` once
+    /// `max_removals` entries have been removed, which bounds the amount of work done when
+    /// evicting entries incrementally.
+    ///
+    /// Which entries are visited before the limit is reached is unspecified. In particular,
+    /// entries inserted or removed concurrently may or may not be visited, and the same call may
+    /// visit entries in a different order than a previous one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::new();
+    ///
+    /// for i in 0..8 {
+    ///     map.pin().insert(i, i*10);
+    /// }
+    /// map.pin().retain_bounded(2, |&k, _| k % 2 == 0);
+    /// assert_eq!(map.pin().len(), 6);
+    /// ```
+    pub fn retain_bounded<F>(&self, max_removals: usize, mut f: F, guard: &Guard<'_>)
+    where
+        F: FnMut(&K, &V) -> bool,
+    {
+        self.check_guard(guard);
+        let mut removed = 0;
+        let mut iter = self.iter(guard);
+        while removed < max_removals {
+            let (k, v) = match iter.next_internal() {
+                Some(entry) => entry,
+                None => break,
+            };
+            // safety: flurry does not drop or move until after guard drop
+            let value = unsafe { v.deref() };
+            if !f(k, value) && self.replace_node(k, None, Some(v), guard).is_some() {
+                removed += 1;
+            }
+        }
+    }
+
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// In other words, remove all pairs `(k, v)` such that `f(&k,&v)` returns `false`.
+    ///
+    /// This method always deletes any key/value pair that `f` returns `false` for, even if if the
+    /// value is updated concurrently. If you do not want that behavior, use [`HashMap::retain`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::new();
+    ///
+    /// for i in 0..8 {
+    ///     map.pin().insert(i, i*10);
+    /// }
+    /// map.pin().retain_force(|&k, _| k % 2 == 0);
+    /// assert_eq!(map.pin().len(), 4);
+    /// ```
+    pub fn retain_force<F>(&self, mut f: F, guard: &Guard<'_>)
+    where
+        F: FnMut(&K, &V) -> bool,
+    {
+        self.check_guard(guard);
+        // removed selected keys
+        for (k, v) in self.iter(guard) {
+            if !f(k, v) {
+                self.replace_node(k, None, None, guard);
+            }
+        }
+    }
+
+    /// Replaces every value in the map with the result of `f`, or removes the entry if `f`
+    /// returns `None`.
+    ///
+    /// This is like calling [`compute_if_present`](HashMap::compute_if_present) for every key,
+    /// but it walks the map one bin at a time, and calls `f` for all the entries of a bin while
+    /// holding that bin's lock once, rather than looking up and locking each key separately. Bins
+    /// that a concurrent resize has already moved to a new table, and bins whose entries are
+    /// stored as a tree, are still updated one key at a time.
+    ///
+    /// `f` is called exactly once for every entry that is in the map for the whole duration of
+    /// the call. Entries that are inserted or removed concurrently may or may not be visited.
+    ///
+    /// # Panics
+    ///
+    /// The bin the current entry is in stays locked while `f` runs. If `f` modifies the map in
+    /// that same bin, this method panics rather than deadlocking.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let tokens: HashMap<_, _> = (0..8).map(|i| (i, i * 10)).collect();
+    /// let guard = tokens.guard();
+    ///
+    /// // halve every bucket, and drop the ones that run dry
+    /// tokens.update_all(|_, &n| if n >= 20 { Some(n / 2) } else { None }, &guard);
+    /// assert_eq!(tokens.len(), 6);
+    /// assert_eq!(tokens.get(&7, &guard), Some(&35));
+    /// assert_eq!(tokens.get(&1, &guard), None);
+    /// ```
+    pub fn update_all<F>(&self, mut f: F, guard: &Guard<'_>)
+    where
+        F: FnMut(&K, &V) -> Option<V>,
+    {
+        self.check_guard(guard);
+        let table = self.table.load(Ordering::SeqCst, guard);
+        if table.is_null() {
+            return;
+        }
+        // safety: we loaded the table under our guard, and tables are only retired once they are
+        // no longer reachable, so it stays valid until we drop the guard.
+        let t = unsafe { table.deref() };
+
+        let mut removed = 0;
+        for i in 0..t.len() {
+            loop {
+                let bin = t.bin(i, guard);
+                if bin.is_null() {
+                    break;
+                }
+
+                // safety: bin is a valid pointer, see the comment on the same match in
+                // `replace_node_if`.
+                match **unsafe { bin.deref() } {
+                    BinEntry::Node(ref head) => {
+                        let head_lock = head.lock.lock();
+
+                        // need to check that this is _still_ the head
+                        if t.bin(i, guard) != bin {
+                            continue;
+                        }
+
+                        // the head node holds the bin's lock, so we only unlink it once we are
+                        // done with the rest of the bin
+                        let mut remove_head = false;
+                        let mut pred = bin;
+                        let mut e = bin;
+                        while !e.is_null() {
+                            // safety: as in `replace_node_if`, every node we reach from the head
+                            // was in the map while we were marked as active.
+                            let n = unsafe { e.deref() }.as_node().unwrap();
+                            let next = n.next.load(Ordering::SeqCst, guard);
+                            let value = n.value.load(Ordering::SeqCst, guard);
+                            // safety: flurry does not drop or move until after guard drop
+                            match f(&n.key, unsafe { value.deref() }) {
+                                Some(new_value) => {
+                                    self.begin_update(n);
+                                    n.value.store(
+                                        Shared::boxed(new_value, &self.collector),
+                                        Ordering::SeqCst,
+                                    );
+                                    self.finish_update(n);
+                                    // safety: the old value is no longer reachable through the
+                                    // node, see the safety comment in `replace_node_if`
+                                    unsafe { guard.retire_shared(value) };
+                                    pred = e;
+                                }
+                                None if e == bin => remove_head = true,
+                                None => {
+                                    // safety: pred is the head or a node that we kept, and so is
+                                    // still valid.
+                                    unsafe { pred.deref() }
+                                        .as_node()
+                                        .unwrap()
+                                        .next
+                                        .store(next, Ordering::SeqCst);
+                                    // safety: the node is no longer reachable from the bin
+                                    unsafe {
+                                        guard.retire_shared(value);
+                                        guard.retire_shared(e);
+                                    }
+                                    removed += 1;
+                                }
+                            }
+                            e = next;
+                        }
+
+                        if remove_head {
+                            t.store_bin(i, head.next.load(Ordering::SeqCst, guard));
+                            // safety: the head is no longer reachable from the bin
+                            unsafe {
+                                guard.retire_shared(head.value.load(Ordering::SeqCst, guard));
+                                guard.retire_shared(bin);
+                            }
+                            removed += 1;
+                        }
+                        drop(head_lock);
+                        break;
+                    }
+                    BinEntry::Moved | BinEntry::Tree(_) => {
+                        // update the entries that hash to this bin one by one, wherever they
+                        // are now. this does not revisit entries of bins we already updated,
+                        // even if a resize has since mixed them into the same bin.
+                        for node in NodeIter::bin(t, i, guard) {
+                            self.compute_if_present(&node.key, |k, v| f(k, v), guard);
+                        }
+                        break;
+                    }
+                    BinEntry::Reservation(_) => {
+                        t.wait_for_reservation(i, bin, guard);
+                        continue;
+                    }
+                    BinEntry::TreeNode(_) => unreachable!(
+                        "The head of a bin cannot be a TreeNode directly without BinEntry::Tree"
+                    ),
+                }
+            }
+        }
+
+        if removed > 0 {
+            self.add_count(-removed, None, guard);
+        }
+    }
+
+    /// Removes all entries from the map, and returns an iterator over the removed entries. Unlike
+    /// [`std::collections::HashMap::drain`], each entry is removed only when the iterator reaches
+    /// it, so dropping the iterator early leaves the remaining entries in the map.
+    ///
+    /// Entries inserted while the map is being drained may or may not be removed and yielded.
+    ///
+    /// The references the iterator yields remain valid for as long as the guard is held, even
+    /// though the entries are no longer in the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// let guard = map.guard();
+    /// map.insert(1, "a", &guard);
+    /// map.insert(2, "b", &guard);
+    ///
+    /// let mut drained: Vec<_> = map.drain(&guard).collect();
+    /// drained.sort();
+    /// assert_eq!(drained, [(&1, &"a"), (&2, &"b")]);
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn drain<'g>(&'g self, guard: &'g Guard<'_>) -> Drain<'g, K, V, S> {
+        self.check_guard(guard);
+        let table = self.table.load(Ordering::SeqCst, guard);
+        Drain {
+            node_iter: NodeIter::new(table, guard),
+            map: self,
+            guard,
+        }
     }
 
-    /// Retains only the elements specified by the predicate.
+    /// Shrinks the capacity of the map as much as possible.
     ///
-    /// In other words, remove all pairs `(k, v)` such that `f(&k,&v)` returns `false`.
+    /// The table is resized down to the smallest capacity that can hold the current number of
+    /// elements without growing again. If the table is being resized by another thread, this
+    /// method helps with (or waits for) that resize first.
+    ///
+    /// To avoid alternating between growing and shrinking, the table is only shrunk if fewer
+    /// elements than its [shrink factor](crate::HashMapBuilder::shrink_factor) of its capacity are
+    /// in use, which is a quarter by default.
+    ///
+    /// Note that elements inserted concurrently with the shrink may cause the map to grow again
+    /// right afterwards.
     ///
     /// # Examples
     ///
     /// ```
     /// use flurry::HashMap;
     ///
-    /// let map = HashMap::new();
+    /// let map: HashMap<i32, i32> = HashMap::with_capacity(100);
+    /// let guard = map.guard();
+    /// map.insert(1, 2, &guard);
+    /// map.insert(3, 4, &guard);
     ///
-    /// for i in 0..8 {
-    ///     map.pin().insert(i, i*10);
-    /// }
-    /// map.pin().retain(|&k, _| k % 2 == 0);
-    /// assert_eq!(map.pin().len(), 4);
+    /// map.shrink_to_fit(&guard);
+    /// assert_eq!(map.get(&1, &guard), Some(&2));
+    /// assert_eq!(map.get(&3, &guard), Some(&4));
     /// ```
+    pub fn shrink_to_fit(&self, guard: &Guard<'_>) {
+        self.shrink_to(0, guard);
+    }
+
+    /// Shrinks the capacity of the map with a lower limit.
     ///
-    /// # Notes
+    /// The capacity will remain large enough to hold both the current number of elements and
+    /// `min_capacity` elements without growing. If the current capacity is already smaller than
+    /// that, or if the larger of the two would still use at least the
+    /// [shrink factor](crate::HashMapBuilder::shrink_factor) of the current capacity, this method
+    /// does nothing.
     ///
-    /// If `f` returns `false` for a given key/value pair, but the value for that pair is concurrently
-    /// modified before the removal takes place, the entry will not be removed.
-    /// If you want the removal to happen even in the case of concurrent modification, use [`HashMap::retain_force`].
-    pub fn retain<F>(&self, mut f: F, guard: &Guard<'_>)
-    where
-        F: FnMut(&K, &V) -> bool,
-    {
+    /// See also [`shrink_to_fit`](HashMap::shrink_to_fit).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map: HashMap<i32, i32> = HashMap::with_capacity(100);
+    /// let guard = map.guard();
+    /// map.insert(1, 2, &guard);
+    ///
+    /// map.shrink_to(10, &guard);
+    /// assert_eq!(map.get(&1, &guard), Some(&2));
+    /// ```
+    pub fn shrink_to(&self, min_capacity: usize, guard: &Guard<'_>) {
         self.check_guard(guard);
-        let mut iter = self.iter(guard);
-        while let Some((k, v)) = iter.next_internal() {
-            // safety: flurry does not drop or move until after guard drop
-            let value = unsafe { v.deref() };
-            if !f(k, value) {
-                self.replace_node(k, None, Some(v), guard);
+        loop {
+            let table = self.table.load(Ordering::SeqCst, guard);
+            if table.is_null() {
+                // the table has not been allocated yet, so there is nothing to shrink
+                return;
+            }
+
+            // safety: table was read while `guard` was held. the code that drops table only drops
+            // it after it is no longer reachable, and any outstanding references are no longer
+            // active. this reference is still active (marked by the guard), so the target of the
+            // reference won't be dropped while the guard remains active.
+            let n = unsafe { table.deref() }.len();
+
+            // use the same capacity for `size` elements that `reserve` would
+            let size = std::cmp::max(self.len(), min_capacity);
+            let requested_capacity = self.capacity_for(size);
+            if requested_capacity >= n || size >= self.shrink_threshold(n) {
+                return;
+            }
+
+            let size_ctl = self.size_ctl.load(Ordering::SeqCst);
+            if size_ctl < 0 {
+                // the table is currently being resized. help out if we can, otherwise wait for the
+                // resize to finish, and then check whether we still need to shrink.
+                if self.help_transfer(table, guard) == table {
+                    std::thread::yield_now();
+                }
+                continue;
+            }
+
+            // claim the resize. no other thread can join a resize while `size_ctl` is `rs + 1`,
+            // so we will be the only thread moving bins.
+            let rs = Self::resize_stamp(n) << RESIZE_STAMP_SHIFT;
+            if self
+                .size_ctl
+                .compare_exchange(size_ctl, rs + 1, Ordering::SeqCst, Ordering::Relaxed)
+                .is_err()
+            {
+                continue;
             }
+            if self.table.load(Ordering::SeqCst, guard) != table {
+                // the table was resized after we read it; release the resize and try again
+                self.size_ctl.store(size_ctl, Ordering::SeqCst);
+                continue;
+            }
+
+            self.rehash_table(table, requested_capacity, guard);
+            return;
         }
     }
 
-    /// Retains only the elements specified by the predicate.
+    /// Resizes the map to hold `capacity` elements, moving all entries to the new table before
+    /// this method returns.
     ///
-    /// In other words, remove all pairs `(k, v)` such that `f(&k,&v)` returns `false`.
+    /// Normally, the map grows incrementally: the thread that starts a resize and any writers
+    /// that run into it share the work of moving bins, and operations keep going to the old table
+    /// until all bins have been moved. For a batch phase without latency requirements, this
+    /// method instead has the calling thread move every bin by itself, so that subsequent
+    /// operations see a single, stable table. Other threads block on the bins being moved, but do
+    /// not help.
     ///
-    /// This method always deletes any key/value pair that `f` returns `false` for, even if if the
-    /// value is updated concurrently. If you do not want that behavior, use [`HashMap::retain`].
+    /// Unlike [`reserve`](HashMap::reserve) and [`shrink_to`](HashMap::shrink_to), the map is
+    /// resized to exactly the table size that [`with_capacity`](HashMap::with_capacity) would
+    /// pick for `capacity`, whether that grows or shrinks it. The table never becomes too small
+    /// for the elements that are in the map when the resize starts. If the table is being
+    /// resized by another thread, this method helps with (or waits for) that resize first.
     ///
     /// # Examples
     ///
@@ -2708,22 +7115,386 @@ where
     /// use flurry::HashMap;
     ///
     /// let map = HashMap::new();
+    /// let guard = map.guard();
+    /// map.insert(1, "a", &guard);
     ///
-    /// for i in 0..8 {
-    ///     map.pin().insert(i, i*10);
-    /// }
-    /// map.pin().retain_force(|&k, _| k % 2 == 0);
-    /// assert_eq!(map.pin().len(), 4);
+    /// map.resize_eager(1000, &guard);
+    /// assert!(map.capacity(&guard) >= 1000);
+    /// assert_eq!(map.get(&1, &guard), Some(&"a"));
     /// ```
-    pub fn retain_force<F>(&self, mut f: F, guard: &Guard<'_>)
-    where
-        F: FnMut(&K, &V) -> bool,
-    {
+    pub fn resize_eager(&self, capacity: usize, guard: &Guard<'_>) {
         self.check_guard(guard);
-        // removed selected keys
-        for (k, v) in self.iter(guard) {
-            if !f(k, v) {
-                self.replace_node(k, None, None, guard);
+        loop {
+            let table = self.table.load(Ordering::SeqCst, guard);
+            if table.is_null() {
+                // allocating the first table does not move anything. it may not end up with
+                // exactly the requested size though, so check again once it is there.
+                self.try_presize(capacity, guard);
+                continue;
+            }
+
+            // safety: table was read while `guard` was held. see `shrink_to`.
+            let n = unsafe { table.deref() }.len();
+
+            let size = std::cmp::max(self.len(), capacity);
+            let requested_capacity = self.capacity_for(size);
+            if requested_capacity == n {
+                return;
+            }
+
+            let size_ctl = self.size_ctl.load(Ordering::SeqCst);
+            if size_ctl < 0 {
+                // the table is currently being resized. help out if we can, otherwise wait for the
+                // resize to finish, and then check whether we still need to resize.
+                if self.help_transfer(table, guard) == table {
+                    std::thread::yield_now();
+                }
+                continue;
+            }
+
+            // claim the resize so that no other thread can join it, just like `shrink_to` does
+            let rs = Self::resize_stamp(n) << RESIZE_STAMP_SHIFT;
+            if self
+                .size_ctl
+                .compare_exchange(size_ctl, rs + 1, Ordering::SeqCst, Ordering::Relaxed)
+                .is_err()
+            {
+                continue;
+            }
+            if self.table.load(Ordering::SeqCst, guard) != table {
+                self.size_ctl.store(size_ctl, Ordering::SeqCst);
+                continue;
+            }
+
+            self.rehash_table(table, requested_capacity, guard);
+            return;
+        }
+    }
+
+    /// Moves all bins of `table` into a new table with `new_capacity` bins.
+    ///
+    /// Unlike `transfer`, this is only ever done by a single thread, which must have claimed the
+    /// resize through `size_ctl`, and the new table may have any number of bins. When shrinking,
+    /// several bins of the old table map to the same bin of the new table, so the entries of each
+    /// old bin are added to the new bins under their locks, just like other writers would.
+    fn rehash_table<'g>(
+        &'g self,
+        table: Shared<'g, Table<K, V>>,
+        new_capacity: usize,
+        guard: &'g Guard<'_>,
+    ) {
+        let next_table_ptr =
+            Shared::boxed(Table::new(new_capacity, &self.collector), &self.collector);
+        let now_garbage = self
+            .next_table
+            .swap(next_table_ptr, Ordering::SeqCst, guard);
+        assert!(now_garbage.is_null());
+        // no other thread may help with this resize
+        self.transfer_index.store(0, Ordering::SeqCst);
+
+        // safety: table was read while `guard` was held, and we have not dropped the guard since.
+        // we allocated next_table ourselves, and it is only retired after it has been replaced as
+        // the map's table, which cannot happen while we hold the resize.
+        let t = unsafe { table.deref() };
+        let next_table = unsafe { next_table_ptr.deref() };
+        let n = t.len();
+
+        let mut i = n;
+        while i > 0 {
+            let bini = i - 1;
+            let bin = t.bin(bini, guard);
+            if bin.is_null() {
+                if t.cas_bin(
+                    bini,
+                    Shared::null(),
+                    t.get_moved(next_table_ptr, guard),
+                    guard,
+                )
+                .is_ok()
+                {
+                    i -= 1;
+                }
+                continue;
+            }
+
+            // safety: bin is a valid pointer. see the equivalent comment in `transfer`.
+            match **unsafe { bin.deref() } {
+                BinEntry::Moved => {
+                    unreachable!("only the rehashing thread moves bins, and it moves each bin once")
+                }
+                BinEntry::Node(ref head) => {
+                    let head_lock = head.lock.lock();
+
+                    // need to check that this is _still_ the head
+                    if t.bin(bini, guard) != bin {
+                        // nope -- try again
+                        continue;
+                    }
+
+                    // copy the nodes of the bin. the values are shared with the new nodes.
+                    let mut copies = Vec::new();
+                    let mut p = bin;
+                    while !p.is_null() {
+                        // safety: p is only retired after its bin is replaced with a move node
+                        // below, and we hold the guard, so it will not be dropped under us.
+                        let node = unsafe { p.deref() }.as_node().unwrap();
+                        let list = Self::copies_for(&mut copies, next_table.bini(node.hash));
+                        let copy = Node::with_next(
+                            node.hash,
+                            node.key.clone(),
+                            node.value.clone(),
+                            Atomic::from(*list),
+                        );
+                        self.copy_version(node, &copy);
+                        *list = Shared::boxed(BinEntry::Node(copy), &self.collector);
+                        p = node.next.load(Ordering::SeqCst, guard);
+                    }
+                    for (target, nodes) in copies {
+                        self.move_into_rehashed_bin(next_table, target, nodes, guard);
+                    }
+                    t.store_bin(bini, t.get_moved(next_table_ptr, guard));
+
+                    // all nodes of the old bin are now garbage
+                    p = bin;
+                    while !p.is_null() {
+                        // safety: the only way to get to p is through t[bini], which has been
+                        // replaced by a BinEntry::Moved, so p is no longer accessible. any
+                        // existing reference to p must have been taken before the store, by a
+                        // thread that was active at the time, so it is protected by the
+                        // reference count.
+                        let next = unsafe { p.deref() }
+                            .as_node()
+                            .unwrap()
+                            .next
+                            .load(Ordering::SeqCst, guard);
+                        unsafe { guard.retire_shared(p) };
+                        p = next;
+                    }
+
+                    drop(head_lock);
+                    i -= 1;
+                }
+                BinEntry::Tree(ref tree_bin) => {
+                    let bin_lock = tree_bin.lock.lock();
+
+                    // need to check that this is _still_ the correct bin
+                    if t.bin(bini, guard) != bin {
+                        // nope -- try again
+                        continue;
+                    }
+
+                    // copy the nodes of the bin into a linear list. the values are shared with
+                    // the new nodes.
+                    let mut copies = Vec::new();
+                    let mut e = tree_bin.first.load(Ordering::SeqCst, guard);
+                    while !e.is_null() {
+                        // safety: we read under our guard, at which point the tree
+                        // structure was valid. Since our guard marks the current thread
+                        // as active, the TreeNodes remain valid for at least as long as
+                        // we hold onto the guard.
+                        // Structurally, TreeNodes always point to TreeNodes, so this is sound.
+                        let tree_node = unsafe { TreeNode::get_tree_node(e) };
+                        let list =
+                            Self::copies_for(&mut copies, next_table.bini(tree_node.node.hash));
+                        let copy = Node::with_next(
+                            tree_node.node.hash,
+                            tree_node.node.key.clone(),
+                            tree_node.node.value.clone(),
+                            Atomic::from(*list),
+                        );
+                        self.copy_version(&tree_node.node, &copy);
+                        *list = Shared::boxed(BinEntry::Node(copy), &self.collector);
+                        e = tree_node.node.next.load(Ordering::SeqCst, guard);
+                    }
+                    for (target, nodes) in copies {
+                        self.move_into_rehashed_bin(next_table, target, nodes, guard);
+                    }
+                    t.store_bin(bini, t.get_moved(next_table_ptr, guard));
+
+                    // safety: the entry for this bin in the old table was swapped for a Moved
+                    // entry, so no thread can obtain a new reference to `bin` from there. the
+                    // values are still in use by the copied nodes, so we must not drop those.
+                    unsafe { TreeBin::defer_drop_without_values(bin, guard) };
+
+                    drop(bin_lock);
+                    i -= 1;
+                }
+                BinEntry::TreeNode(_) => unreachable!(
+                    "The head of a bin cannot be a TreeNode directly without BinEntry::Tree"
+                ),
+                BinEntry::Reservation(_) => {
+                    t.wait_for_reservation(bini, bin, guard);
+                    continue;
+                }
+            }
+        }
+
+        self.next_table.store(Shared::null(), Ordering::SeqCst);
+        let now_garbage = self.table.swap(next_table_ptr, Ordering::SeqCst, guard);
+        self.resize_count.fetch_add(1, Ordering::Relaxed);
+        // safety: need to guarantee that now_garbage is no longer reachable. the argument is the
+        // same as for the old table at the end of `transfer`: it is no longer accessible through
+        // self.table or self.next_table, and any thread that still has a reference to it must
+        // have obtained it while marked as active.
+        unsafe { guard.retire_shared(now_garbage) };
+        self.size_ctl
+            .store(self.resize_threshold(new_capacity), Ordering::SeqCst);
+        self.notify_resize(n, new_capacity);
+    }
+
+    /// Returns the list of copied nodes that go into bin `bini` of the new table.
+    ///
+    /// When shrinking, all nodes of an old bin go into the same new bin, and when growing they are
+    /// split across a few, so a linear search is all that is needed.
+    fn copies_for<'v, 'g>(
+        copies: &'v mut Vec<(usize, Shared<'g, BinEntry<K, V>>)>,
+        bini: usize,
+    ) -> &'v mut Shared<'g, BinEntry<K, V>> {
+        let i = match copies.iter().position(|&(target, _)| target == bini) {
+            Some(i) => i,
+            None => {
+                copies.push((bini, Shared::null()));
+                copies.len() - 1
+            }
+        };
+        &mut copies[i].1
+    }
+
+    /// Adds the given list of (unshared) nodes to bin `bini` of the rehashed table `table`.
+    fn move_into_rehashed_bin<'g>(
+        &'g self,
+        table: &'g Table<K, V>,
+        bini: usize,
+        nodes: Shared<'g, BinEntry<K, V>>,
+        guard: &'g Guard<'_>,
+    ) {
+        if nodes.is_null() {
+            return;
+        }
+
+        loop {
+            let bin = table.bin(bini, guard);
+            if bin.is_null() {
+                if table.cas_bin(bini, Shared::null(), nodes, guard).is_err() {
+                    continue;
+                }
+
+                // the nodes may have come from a tree bin
+                let mut bin_count = 0;
+                let mut p = nodes;
+                while !p.is_null() {
+                    bin_count += 1;
+                    // safety: the nodes can only be retired by a thread that removes them from
+                    // the table after we have added them, which our guard protects against.
+                    p = unsafe { p.deref() }
+                        .as_node()
+                        .unwrap()
+                        .next
+                        .load(Ordering::SeqCst, guard);
+                }
+                if bin_count >= TREEIFY_THRESHOLD {
+                    self.treeify_bin(table, bini, guard);
+                }
+                return;
+            }
+
+            // safety: bin is a valid pointer. see the equivalent comment in `transfer`.
+            match **unsafe { bin.deref() } {
+                BinEntry::Node(ref head) => {
+                    let head_lock = head.lock.lock();
+
+                    // need to check that this is _still_ the head
+                    if table.bin(bini, guard) != bin {
+                        continue;
+                    }
+
+                    // find the end of the bin, and link the nodes in there
+                    let mut bin_count = 1;
+                    let mut p = bin;
+                    let tail = loop {
+                        // safety: we loaded the bin while holding a guard, so any retirements
+                        // must have seen us as active. the bin and its nodes cannot be dropped
+                        // until at least after we drop our guard.
+                        let n = unsafe { p.deref() }.as_node().unwrap();
+                        let next = n.next.load(Ordering::SeqCst, guard);
+                        if next.is_null() {
+                            break n;
+                        }
+                        p = next;
+                        bin_count += 1;
+                    };
+                    let mut p = nodes;
+                    while !p.is_null() {
+                        bin_count += 1;
+                        // safety: we created the nodes, and have not shared them yet
+                        p = unsafe { p.deref() }
+                            .as_node()
+                            .unwrap()
+                            .next
+                            .load(Ordering::SeqCst, guard);
+                    }
+                    tail.next.store(nodes, Ordering::SeqCst);
+                    drop(head_lock);
+
+                    if bin_count >= TREEIFY_THRESHOLD {
+                        self.treeify_bin(table, bini, guard);
+                    }
+                    return;
+                }
+                BinEntry::Tree(ref tree_bin) => {
+                    let bin_lock = tree_bin.lock.lock();
+
+                    // need to check that this is _still_ the correct bin
+                    if table.bin(bini, guard) != bin {
+                        continue;
+                    }
+
+                    let mut p = nodes;
+                    while !p.is_null() {
+                        // safety: we created the nodes, and have not shared them yet
+                        let node = match Linked::into_inner(*unsafe { p.into_box() }) {
+                            BinEntry::Node(node) => node,
+                            _ => unreachable!("we only create BinEntry::Node"),
+                        };
+                        p = node.next.load(Ordering::SeqCst, guard);
+                        let value = node.value.load(Ordering::SeqCst, guard);
+                        // the copies were given their versions when they were made
+                        #[cfg(feature = "versioned")]
+                        let version = node.version.load(Ordering::SeqCst);
+                        let existing = tree_bin.find_or_put_tree_val(
+                            node.hash,
+                            node.key,
+                            value,
+                            guard,
+                            &self.collector,
+                        );
+                        // keys from different bins of the old table are necessarily different
+                        assert!(existing.is_null());
+                        #[cfg(feature = "versioned")]
+                        {
+                            // new tree nodes are always inserted at the front of the bin's list
+                            let first = tree_bin.first.load(Ordering::SeqCst, guard);
+                            // safety: we just inserted the node, and hold the bin lock.
+                            // structurally, TreeNodes always point to TreeNodes, so this is sound.
+                            unsafe { TreeNode::get_tree_node(first) }
+                                .node
+                                .version
+                                .store(version, Ordering::SeqCst);
+                        }
+                    }
+                    drop(bin_lock);
+                    return;
+                }
+                BinEntry::Reservation(_) => {
+                    table.wait_for_reservation(bini, bin, guard);
+                    continue;
+                }
+                BinEntry::Moved => {
+                    unreachable!("the new table cannot be resized while the rehash is ongoing")
+                }
+                BinEntry::TreeNode(_) => unreachable!(
+                    "The head of a bin cannot be a TreeNode directly without BinEntry::Tree"
+                ),
             }
         }
     }
@@ -2776,6 +7547,7 @@ where
                             Atomic::null(),
                             Atomic::null(),
                         );
+                        self.copy_version(e_deref, &new_tree_node.node);
                         new_tree_node.prev.store(tail, Ordering::Relaxed);
                         let new_tree_node =
                             Shared::boxed(BinEntry::TreeNode(new_tree_node), &self.collector);
@@ -2871,6 +7643,10 @@ where
                     // need to perform any action on the bin anymore, since either it has already
                     // been treeified or it was moved to a new table.
                 }
+                BinEntry::Reservation(_) => {
+                    // The bin was emptied and a value is now being computed for it, so there is
+                    // nothing to treeify.
+                }
                 BinEntry::TreeNode(_) => unreachable!("TreeNode cannot be the head of a bin"),
             }
         }
@@ -2897,14 +7673,13 @@ where
             let q_deref = unsafe { q.deref() }.as_tree_node().unwrap();
             // NOTE: cloning the value uses a load with Ordering::Relaxed, but
             // write access is synchronized through the bin lock
-            let new_node = Shared::boxed(
-                BinEntry::Node(Node::new(
-                    q_deref.node.hash,
-                    q_deref.node.key.clone(),
-                    q_deref.node.value.clone(),
-                )),
-                &self.collector,
+            let copy = Node::new(
+                q_deref.node.hash,
+                q_deref.node.key.clone(),
+                q_deref.node.value.clone(),
             );
+            self.copy_version(&q_deref.node, &copy);
+            let new_node = Shared::boxed(BinEntry::Node(copy), &self.collector);
             if tail.is_null() {
                 head = new_node;
             } else {
@@ -2937,6 +7712,30 @@ where
     }
 }
 
+impl<K, V, S, S2> PartialEq<std::collections::HashMap<K, V, S2>> for HashMap<K, V, S>
+where
+    K: Ord + Hash,
+    V: PartialEq,
+    S: BuildHasher,
+    S2: BuildHasher,
+{
+    fn eq(&self, other: &std::collections::HashMap<K, V, S2>) -> bool {
+        self.std_eq(other, &self.guard())
+    }
+}
+
+impl<K, V, S, S2> PartialEq<HashMap<K, V, S>> for std::collections::HashMap<K, V, S2>
+where
+    K: Ord + Hash,
+    V: PartialEq,
+    S: BuildHasher,
+    S2: BuildHasher,
+{
+    fn eq(&self, other: &HashMap<K, V, S>) -> bool {
+        other.std_eq(self, &other.guard())
+    }
+}
+
 impl<K, V, S> Eq for HashMap<K, V, S>
 where
     K: Ord + Hash,
@@ -2945,6 +7744,33 @@ where
 {
 }
 
+/// The hash does not depend on the order of the entries or on the map's hasher, so it is
+/// consistent with [`PartialEq`]. Note that, since the map can be modified through a shared
+/// reference, a map used as a key elsewhere must not be modified while it is in use as a key.
+impl<K, V, S> Hash for HashMap<K, V, S>
+where
+    K: Ord + Hash,
+    V: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // equal maps may use different hashers, and may yield their entries in different orders.
+        // so, we hash each entry with a fixed hasher and combine the results in an
+        // order-independent way.
+        let guard = self.collector.enter();
+        let mut len = 0usize;
+        let mut combined = 0u64;
+        for (key, value) in self.iter(&guard) {
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            key.hash(&mut hasher);
+            value.hash(&mut hasher);
+            combined ^= hasher.finish();
+            len += 1;
+        }
+        state.write_usize(len);
+        state.write_u64(combined);
+    }
+}
+
 impl<K, V, S> fmt::Debug for HashMap<K, V, S>
 where
     K: Debug,
@@ -2980,6 +7806,152 @@ impl<K, V, S> Drop for HashMap<K, V, S> {
     }
 }
 
+impl<K, V, S> IntoIterator for HashMap<K, V, S> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    /// Creates a consuming iterator, that is, one that moves each key-value pair out of the map
+    /// in arbitrary order. The map cannot be used after calling this.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// map.pin().insert("a", String::from("x"));
+    /// map.pin().insert("b", String::from("y"));
+    ///
+    /// let mut entries: Vec<(&str, String)> = map.into_iter().collect();
+    /// entries.sort();
+    /// assert_eq!(entries, [("a", String::from("x")), ("b", String::from("y"))]);
+    /// ```
+    fn into_iter(self) -> Self::IntoIter {
+        // safety: we own the map, so there cannot be any outstanding references to anything in
+        // it (see the comment in `drop`).
+        let guard = unsafe { Guard::unprotected() };
+
+        assert!(self.next_table.load(Ordering::SeqCst, &guard).is_null());
+        let table = self.table.swap(Shared::null(), Ordering::SeqCst, &guard);
+        let entries = if table.is_null() {
+            // table was never allocated!
+            Vec::new()
+        } else {
+            // safety: same as above + we own the table
+            let mut table = unsafe { table.into_box() };
+            table.take_entries()
+        };
+
+        // the map itself is dropped here, but since the table was swapped out, it no longer
+        // owns any entries.
+        IntoIter {
+            entries: entries.into_iter(),
+        }
+    }
+}
+
+impl<K, V, S> HashMap<K, V, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher + Clone,
+{
+    /// Converts the map into a [`std::collections::HashMap`] with the same hasher.
+    ///
+    /// The keys and values are moved out of the map, so neither needs to implement `Clone`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// map.pin().insert(1, String::from("a"));
+    ///
+    /// let map: std::collections::HashMap<_, _, _> = map.into_std();
+    /// assert_eq!(map[&1], "a");
+    /// ```
+    pub fn into_std(self) -> std::collections::HashMap<K, V, S> {
+        let mut map = std::collections::HashMap::with_capacity_and_hasher(
+            self.len(),
+            self.build_hasher.clone(),
+        );
+        map.extend(self);
+        map
+    }
+
+    /// Copies the entries of the map into a [`std::collections::HashMap`] with the same hasher.
+    ///
+    /// Like iteration, this does not take a consistent snapshot of the map if it is modified
+    /// concurrently.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// let guard = map.guard();
+    /// map.insert(1, String::from("a"), &guard);
+    ///
+    /// let copy = map.to_std(&guard);
+    /// assert_eq!(copy[&1], "a");
+    /// assert_eq!(map.get(&1, &guard).map(String::as_str), Some("a"));
+    /// ```
+    pub fn to_std(&self, guard: &Guard<'_>) -> std::collections::HashMap<K, V, S>
+    where
+        K: Clone,
+        V: Clone,
+    {
+        let mut map = std::collections::HashMap::with_capacity_and_hasher(
+            self.len(),
+            self.build_hasher.clone(),
+        );
+        map.extend(self.iter(guard).map(|(k, v)| (k.clone(), v.clone())));
+        map
+    }
+
+    /// Copies the entries of the map into an immutable [`FrozenHashMap`] with the same hasher.
+    ///
+    /// The frozen map stores its entries in a single contiguous table without any bin locks, so
+    /// lookups on it are plain `&self` method calls that do not need a [`Guard`]. This makes it a
+    /// good fit for read-heavy phases in which the map is no longer modified.
+    ///
+    /// The frozen map is a copy: it does not see any changes made to this map after the call.
+    /// Like iteration, this does not take a consistent snapshot of the map if it is modified
+    /// concurrently.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// let guard = map.guard();
+    /// map.insert(1, "a", &guard);
+    /// map.insert(2, "b", &guard);
+    ///
+    /// let frozen = map.freeze(&guard);
+    /// map.remove(&1, &guard);
+    /// drop(guard);
+    ///
+    /// assert_eq!(frozen.len(), 2);
+    /// assert_eq!(frozen[&1], "a");
+    /// assert_eq!(frozen.get(&2), Some(&"b"));
+    /// ```
+    pub fn freeze(&self, guard: &Guard<'_>) -> FrozenHashMap<K, V, S>
+    where
+        K: Clone,
+        V: Clone,
+    {
+        self.check_guard(guard);
+        let entries = self
+            .iter(guard)
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+        FrozenHashMap::from_entries(entries, self.build_hasher.clone())
+    }
+}
+
 impl<K, V, S> Extend<(K, V)> for &HashMap<K, V, S>
 where
     K: Sync + Send + Clone + Hash + Ord,
@@ -3064,15 +8036,97 @@ where
     }
 }
 
+impl<K, V, S, const N: usize> From<[(K, V); N]> for HashMap<K, V, S>
+where
+    K: Sync + Send + Clone + Hash + Ord,
+    V: Sync + Send,
+    S: BuildHasher + Default,
+{
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map1 = HashMap::from([(1, 2), (3, 4)]);
+    /// let map2: HashMap<_, _> = [(1, 2), (3, 4)].into();
+    /// assert_eq!(map1, map2);
+    /// ```
+    fn from(arr: [(K, V); N]) -> Self {
+        // `FromIterator` reserves space for the whole array, since its size hint is exact
+        IntoIterator::into_iter(arr).collect()
+    }
+}
+
+impl<K, V, S> From<std::collections::HashMap<K, V, S>> for HashMap<K, V, S>
+where
+    K: Sync + Send + Clone + Hash + Ord,
+    V: Sync + Send,
+    S: BuildHasher + Clone,
+{
+    /// Converts a [`std::collections::HashMap`] into a map with the same hasher.
+    ///
+    /// This is the inverse of [`HashMap::into_std`]. The keys and values are moved out of the
+    /// standard library map rather than cloned, and the map is sized for all of them up front.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let mut std_map = std::collections::HashMap::new();
+    /// std_map.insert(1, String::from("a"));
+    ///
+    /// let map = HashMap::from(std_map);
+    /// assert_eq!(map.pin().get(&1).map(String::as_str), Some("a"));
+    /// ```
+    fn from(map: std::collections::HashMap<K, V, S>) -> Self {
+        let converted = HashMap::with_capacity_and_hasher(map.len(), map.hasher().clone());
+
+        // safety: we own `converted`, so it's not concurrently accessed by
+        // anyone else at this point.
+        let guard = unsafe { Guard::unprotected() };
+        converted.put_all(map.into_iter(), &guard);
+        converted
+    }
+}
+
 impl<K, V, S> Clone for HashMap<K, V, S>
 where
     K: Sync + Send + Clone + Hash + Ord,
     V: Sync + Send + Clone,
     S: BuildHasher + Clone,
 {
+    /// Returns a point-in-time snapshot of the map.
+    ///
+    /// The entries are copied with the same weak consistency as [`iter`](HashMap::iter): if the
+    /// map is modified concurrently while it is being cloned, some of those modifications may be
+    /// reflected in the clone while others are not. Modifications made after `clone` returns are
+    /// never reflected, and vice versa.
+    ///
+    /// The clone is created with enough capacity for the number of entries in the map when `clone`
+    /// was called, so copying the entries does not resize it. It uses the same hasher and load
+    /// factor as the original map, but a collector of its own.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// map.pin().insert("timeout", 30);
+    ///
+    /// let snapshot = map.clone();
+    /// map.pin().insert("timeout", 60);
+    /// assert_eq!(snapshot.pin().get("timeout"), Some(&30));
+    /// ```
     fn clone(&self) -> HashMap<K, V, S> {
-        let cloned_map = Self::with_capacity_and_hasher(self.len(), self.build_hasher.clone())
-            .with_collector(self.collector.clone());
+        let cloned_map = HashMap::builder()
+            .capacity(self.len())
+            .load_factor(self.load_factor)
+            .shrink_factor(self.shrink_factor)
+            .hasher(self.build_hasher.clone())
+            .build()
+            .with_collector(Collector::clone(&self.collector));
 
         {
             let guard = self.collector.enter();
@@ -3081,71 +8135,318 @@ where
                 cloned_map.insert(k.clone(), v.clone(), &cloned_guard);
             }
         }
-        cloned_map
+        cloned_map
+    }
+}
+
+#[cfg(not(miri))]
+#[inline]
+/// Returns the number of physical CPUs in the machine (_O(1)_).
+fn num_cpus() -> usize {
+    NCPU_INITIALIZER.call_once(|| NCPU.store(num_cpus::get_physical(), Ordering::Relaxed));
+    NCPU.load(Ordering::Relaxed)
+}
+
+#[cfg(miri)]
+#[inline]
+const fn num_cpus() -> usize {
+    1
+}
+
+#[test]
+fn capacity() {
+    let map = HashMap::<usize, usize>::new();
+    let guard = map.guard();
+
+    assert_eq!(map.capacity(&guard), 0);
+    // The table has not yet been allocated
+
+    map.insert(42, 0, &guard);
+
+    assert_eq!(map.capacity(&guard), 16);
+    // The table has been allocated and has default capacity
+
+    for i in 0..16 {
+        map.insert(i, 42, &guard);
+    }
+
+    assert_eq!(map.capacity(&guard), 32);
+    // The table has been resized once (and it's capacity doubled),
+    // since we inserted more elements than it can hold
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_resizing() {
+        let map = HashMap::<usize, usize>::new();
+        let guard = map.guard();
+        map.insert(1, 1, &guard);
+        assert!(!map.is_resizing(&guard));
+
+        // pretend that a resize is in progress
+        let next_table = Shared::boxed(Table::new(32, &map.collector), &map.collector);
+        map.next_table.store(next_table, Ordering::SeqCst);
+        assert!(map.is_resizing(&guard));
+
+        map.next_table.store(Shared::null(), Ordering::SeqCst);
+        assert!(!map.is_resizing(&guard));
+        // safety: the table was never reachable from anywhere but `next_table`
+        unsafe { next_table.into_box() }.drop_bins();
+    }
+
+    #[test]
+    fn reserve() {
+        let map = HashMap::<usize, usize>::new();
+        let guard = map.guard();
+
+        map.insert(42, 0, &guard);
+
+        map.reserve(32, &guard);
+
+        let capacity = map.capacity(&guard);
+        assert!(capacity >= 16 + 32);
+    }
+
+    #[test]
+    fn reserve_uninit() {
+        let map = HashMap::<usize, usize>::new();
+        let guard = map.guard();
+
+        map.reserve(32, &guard);
+
+        let capacity = map.capacity(&guard);
+        assert!(capacity >= 32);
+    }
+
+    #[test]
+    fn try_reserve() {
+        let map = HashMap::<usize, usize>::new();
+        let guard = map.guard();
+
+        map.insert(42, 0, &guard);
+        assert_eq!(map.try_reserve(32, &guard), Ok(()));
+        assert!(map.capacity(&guard) >= 16 + 32);
+
+        assert_eq!(
+            map.try_reserve(usize::MAX, &guard),
+            Err(TryReserveError::CapacityOverflow)
+        );
+        assert_eq!(
+            map.try_reserve(MAXIMUM_CAPACITY, &guard),
+            Err(TryReserveError::CapacityOverflow)
+        );
+        assert_eq!(map.get(&42, &guard), Some(&0));
+    }
+
+    #[test]
+    fn load_factor() {
+        // a 16-bin table resizes at 12 elements by default
+        for (load_factor, threshold) in [(0.75, 12), (0.5, 8), (0.9, 15)] {
+            let map = HashMap::builder().load_factor(load_factor).build();
+            let guard = map.guard();
+            for i in 0..threshold - 1 {
+                map.insert(i, i, &guard);
+            }
+            assert_eq!(map.capacity(&guard), 16);
+            map.insert(threshold, threshold, &guard);
+            assert_eq!(map.capacity(&guard), 32);
+        }
     }
-}
 
-#[cfg(not(miri))]
-#[inline]
-/// Returns the number of physical CPUs in the machine (_O(1)_).
-fn num_cpus() -> usize {
-    NCPU_INITIALIZER.call_once(|| NCPU.store(num_cpus::get_physical(), Ordering::Relaxed));
-    NCPU.load(Ordering::Relaxed)
-}
+    #[test]
+    fn load_factor_presize() {
+        let map = HashMap::<usize, usize>::with_capacity(100);
+        let default = map.capacity(&map.guard());
+        let sparse = HashMap::<usize, usize>::builder()
+            .capacity(100)
+            .load_factor(0.25)
+            .build();
+        let guard = sparse.guard();
+        assert!(sparse.capacity(&guard) > default);
+        for i in 0..100 {
+            sparse.insert(i, i, &guard);
+        }
+        // clones keep the load factor
+        let clone = sparse.clone();
+        assert_eq!(clone.capacity(&clone.guard()), sparse.capacity(&guard));
+    }
 
-#[cfg(miri)]
-#[inline]
-const fn num_cpus() -> usize {
-    1
-}
+    #[test]
+    fn compute_in_other_bin() {
+        let map = HashMap::<usize, usize>::with_capacity(64);
+        let guard = map.guard();
+        map.insert(0, 0, &guard);
+
+        let t = map.table.load(Ordering::Relaxed, &guard);
+        let t = unsafe { t.deref() };
+        let other = (1..)
+            .find(|k| t.bini(map.hash(k)) != t.bini(map.hash(&0)))
+            .unwrap();
+
+        // reading the map from within the closure is fine, and so is writing to other bins
+        let new = map.compute_if_present(
+            &0,
+            |_, v| {
+                assert_eq!(map.get(&0, &guard), Some(v));
+                map.insert(other, 1, &guard);
+                Some(v + 1)
+            },
+            &guard,
+        );
+        assert_eq!(new, Some(&1));
+        assert_eq!(map.get(&other, &guard), Some(&1));
+    }
 
-#[test]
-fn capacity() {
-    let map = HashMap::<usize, usize>::new();
-    let guard = map.guard();
+    #[test]
+    #[should_panic]
+    fn load_factor_out_of_range() {
+        let _ = HashMap::<usize, usize>::builder().load_factor(1.0);
+    }
 
-    assert_eq!(map.capacity(&guard), 0);
-    // The table has not yet been allocated
+    #[test]
+    fn shrink_to_fit() {
+        let map = HashMap::<usize, usize>::with_capacity(1024);
+        let guard = map.guard();
 
-    map.insert(42, 0, &guard);
+        for i in 0..10 {
+            map.insert(i, i, &guard);
+        }
+        assert!(map.capacity(&guard) >= 1024);
 
-    assert_eq!(map.capacity(&guard), 16);
-    // The table has been allocated and has default capacity
+        map.shrink_to_fit(&guard);
+        assert_eq!(map.capacity(&guard), 16);
+        assert_eq!(map.len(), 10);
+        for i in 0..10 {
+            assert_eq!(map.get(&i, &guard), Some(&i));
+        }
+    }
 
-    for i in 0..16 {
-        map.insert(i, 42, &guard);
+    #[test]
+    fn shrink_to() {
+        let map = HashMap::<usize, usize>::with_capacity(1024);
+        let guard = map.guard();
+
+        map.insert(42, 0, &guard);
+        map.shrink_to(100, &guard);
+        let capacity = map.capacity(&guard);
+        assert!(capacity >= 100);
+        assert!(capacity < 1024);
+
+        // shrinking never grows the table
+        map.shrink_to(4096, &guard);
+        assert_eq!(map.capacity(&guard), capacity);
     }
 
-    assert_eq!(map.capacity(&guard), 32);
-    // The table has been resized once (and it's capacity doubled),
-    // since we inserted more elements than it can hold
-}
+    #[test]
+    fn resize_eager() {
+        let map = HashMap::<usize, usize>::new();
+        let guard = map.guard();
+        for i in 0..10 {
+            map.insert(i, i, &guard);
+        }
+        assert_eq!(map.capacity(&guard), 16);
+
+        // the table goes straight to its final size, rather than doubling several times
+        map.resize_eager(3000, &guard);
+        assert_eq!(map.capacity(&guard), 8192);
+        assert_eq!(map.stats().resize_count, 1);
+        for i in 0..10 {
+            assert_eq!(map.get(&i, &guard), Some(&i));
+        }
+
+        // resizing to the current size does nothing
+        map.resize_eager(3000, &guard);
+        assert_eq!(map.stats().resize_count, 1);
+
+        // the table can be shrunk, but never below what the elements need
+        map.resize_eager(0, &guard);
+        assert_eq!(map.capacity(&guard), 16);
+        assert_eq!(map.stats().resize_count, 2);
+        assert_eq!(map.len(), 10);
+        for i in 0..10 {
+            assert_eq!(map.get(&i, &guard), Some(&i));
+        }
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
     #[test]
-    fn reserve() {
+    fn resize_eager_uninit() {
         let map = HashMap::<usize, usize>::new();
         let guard = map.guard();
+        map.resize_eager(100, &guard);
+        let expected = HashMap::<usize, usize>::with_capacity(100);
+        assert_eq!(map.capacity(&guard), expected.capacity(&expected.guard()));
+    }
 
-        map.insert(42, 0, &guard);
+    #[test]
+    fn shrink_hysteresis() {
+        let map = HashMap::<usize, usize>::new();
+        let guard = map.guard();
 
-        map.reserve(32, &guard);
+        // grow the table just past its resize threshold
+        for i in 0..13 {
+            map.insert(i, i, &guard);
+        }
+        assert_eq!(map.capacity(&guard), 32);
 
-        let capacity = map.capacity(&guard);
-        assert!(capacity >= 16 + 32);
+        // a few elements fewer would fit in the old table, but are above the shrink factor
+        for i in 0..4 {
+            map.remove(&i, &guard);
+        }
+        map.shrink_to_fit(&guard);
+        assert_eq!(map.capacity(&guard), 32);
+        map.shrink_to(0, &guard);
+        assert_eq!(map.capacity(&guard), 32);
+
+        // below a quarter of the capacity, the table is shrunk
+        for i in 4..6 {
+            map.remove(&i, &guard);
+        }
+        map.shrink_to_fit(&guard);
+        assert_eq!(map.capacity(&guard), 16);
+        assert_eq!(map.len(), 7);
     }
 
     #[test]
-    fn reserve_uninit() {
+    fn shrink_factor() {
         let map = HashMap::<usize, usize>::new();
-        let guard = map.guard();
+        assert_eq!(map.shrink_factor, 0.25);
 
-        map.reserve(32, &guard);
+        // the default shrink factor follows the load factor
+        let map = HashMap::<usize, usize>::builder().load_factor(0.3).build();
+        assert!((map.shrink_factor - 0.1).abs() < 1e-9);
 
-        let capacity = map.capacity(&guard);
-        assert!(capacity >= 32);
+        let map = HashMap::<usize, usize>::builder()
+            .shrink_factor(0.5)
+            .build();
+        assert_eq!(map.shrink_factor, 0.5);
+        assert_eq!(map.clone().shrink_factor, 0.5);
+    }
+
+    #[test]
+    #[should_panic(expected = "shrink factor must be in (0.0, 1.0)")]
+    fn shrink_factor_out_of_range() {
+        let _ = HashMap::<usize, usize>::builder().shrink_factor(0.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "must be smaller than the load factor")]
+    fn shrink_factor_above_load_factor() {
+        let _ = HashMap::<usize, usize>::builder()
+            .load_factor(0.5)
+            .shrink_factor(0.5)
+            .build();
+    }
+
+    #[test]
+    fn shrink_uninit() {
+        let map = HashMap::<usize, usize>::new();
+        let guard = map.guard();
+
+        map.shrink_to_fit(&guard);
+        assert_eq!(map.capacity(&guard), 0);
     }
 
     #[test]
@@ -3256,6 +8557,65 @@ mod tests {
 /// drop(r);
 /// ```
 ///
+/// # The map is `Send` and `Sync` exactly when its keys, values and hasher are
+///
+/// ```
+/// fn send_sync<T: Send + Sync>() {}
+/// send_sync::<flurry::HashMap<i32, i32>>();
+/// send_sync::<flurry::HashMap<String, Vec<u8>>>();
+/// send_sync::<flurry::HashSet<i32>>();
+/// ```
+/// ```compile_fail
+/// fn send<T: Send>() {}
+/// send::<flurry::HashMap<std::rc::Rc<i32>, ()>>();
+/// ```
+/// ```compile_fail
+/// fn sync<T: Sync>() {}
+/// sync::<flurry::HashMap<std::rc::Rc<i32>, ()>>();
+/// ```
+/// ```compile_fail
+/// fn send<T: Send>() {}
+/// send::<flurry::HashMap<(), std::rc::Rc<i32>>>();
+/// ```
+/// ```compile_fail
+/// fn sync<T: Sync>() {}
+/// sync::<flurry::HashMap<(), std::cell::Cell<i32>>>();
+/// ```
+/// ```compile_fail
+/// fn send<T: Send>() {}
+/// send::<flurry::HashSet<std::rc::Rc<i32>>>();
+/// ```
+/// ```compile_fail
+/// fn send<T: Send>() {}
+/// send::<flurry::HashMap<i32, i32, std::rc::Rc<std::collections::hash_map::RandomState>>>();
+/// ```
+///
+/// # Entries must be `Send` and `Sync` to be inserted
+///
+/// ```compile_fail
+/// let map = flurry::HashMap::new();
+/// map.insert(std::rc::Rc::new(1), (), &map.guard());
+/// ```
+/// ```compile_fail
+/// let map = flurry::HashMap::new();
+/// map.insert((), std::cell::Cell::new(1), &map.guard());
+/// ```
+///
+/// # Guards and references to a pinned map stay on their thread
+///
+/// ```compile_fail
+/// fn send<T: Send>() {}
+/// send::<flurry::HashMapRef<'static, i32, i32>>();
+/// ```
+/// ```compile_fail
+/// fn sync<T: Sync>() {}
+/// sync::<flurry::HashMapRef<'static, i32, i32>>();
+/// ```
+/// ```compile_fail
+/// fn send<T: Send>() {}
+/// send::<flurry::iter::Iter<'static, i32, i32>>();
+/// ```
+///
 /// # Keys and values do not have be static
 ///
 /// ```
@@ -3382,6 +8742,7 @@ mod tree_bins {
     #[derive(Default)]
     struct ZeroHasher;
 
+    #[derive(Clone)]
     struct ZeroHashBuilder;
 
     impl Hasher for ZeroHasher {
@@ -3398,6 +8759,268 @@ mod tree_bins {
         }
     }
 
+    #[test]
+    #[should_panic(
+        expected = "cannot access the map for the same bin from within a compute closure"
+    )]
+    fn compute_reentrant_tree_bin() {
+        let map = HashMap::<usize, usize, _>::with_hasher(ZeroHashBuilder);
+        let guard = &map.guard();
+        for i in 0..100 {
+            map.insert(i, i, guard);
+        }
+
+        // every key collides, so inserting any key locks the bin that is being computed on
+        map.compute_if_present(
+            &0,
+            |_, v| {
+                map.insert(100, 100, guard);
+                Some(*v)
+            },
+            guard,
+        );
+    }
+
+    #[test]
+    fn clear_fast_tree_bin() {
+        let map = HashMap::<usize, usize, _>::with_hasher(ZeroHashBuilder);
+        let guard = &map.guard();
+        for i in 0..100 {
+            map.insert(i, i, guard);
+        }
+        let t = map.table.load(Ordering::Relaxed, guard);
+        let t = unsafe { t.deref() };
+        let bin = t.bin(t.bini(0), guard);
+        assert!(matches!(unsafe { &**bin.deref() }, BinEntry::Tree(_)));
+
+        map.clear_fast(guard);
+        assert!(map.is_empty());
+        assert_eq!(map.get(&0, guard), None);
+        map.insert(0, 1, guard);
+        assert_eq!(map.get(&0, guard), Some(&1));
+        assert_eq!(map.len(), 1);
+    }
+
+    #[test]
+    fn freeze_tree_bin() {
+        let map = HashMap::<usize, usize, _>::with_hasher(ZeroHashBuilder);
+        let guard = &map.guard();
+        for i in 0..100 {
+            map.insert(i, i, guard);
+        }
+
+        // every key collides, so the frozen map has to scan a single bin
+        let frozen = map.freeze(guard);
+        assert_eq!(frozen.len(), 100);
+        for i in 0..100 {
+            assert_eq!(frozen.get(&i), Some(&i));
+        }
+        assert_eq!(frozen.get(&100), None);
+    }
+
+    #[test]
+    fn bins_tree_bin() {
+        let map = HashMap::<usize, usize, _>::with_hasher(ZeroHashBuilder);
+        let guard = &map.guard();
+        for i in 0..100 {
+            map.insert(i, i, guard);
+        }
+
+        // every key collides, so there is exactly one bin
+        let mut bins = map.bins(guard);
+        let mut keys: Vec<_> = bins.next().unwrap().map(|(k, _)| *k).collect();
+        assert!(bins.next().is_none());
+        keys.sort_unstable();
+        assert_eq!(keys, (0..100).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn update_all_tree_bin() {
+        let map = HashMap::<usize, usize, _>::with_hasher(ZeroHashBuilder);
+        let guard = &map.guard();
+        for i in 0..100 {
+            map.insert(i, i, guard);
+        }
+
+        // removing most entries turns the tree back into a linked bin halfway through
+        map.update_all(|&k, &v| if k < 90 { None } else { Some(v + 1) }, guard);
+        assert_eq!(map.len(), 10);
+        for i in 0..100 {
+            let expected = if i < 90 { None } else { Some(i + 1) };
+            assert_eq!(map.get(&i, guard).copied(), expected);
+        }
+
+        // and the linked bin is updated in place
+        map.update_all(|_, &v| Some(v + 1), guard);
+        assert!((90..100).all(|i| map.get(&i, guard) == Some(&(i + 2))));
+    }
+
+    #[test]
+    fn shrink_tree_bin() {
+        let map = HashMap::<usize, usize, _>::with_capacity_and_hasher(1024, ZeroHashBuilder);
+        let guard = &map.guard();
+        for i in 0..100 {
+            map.insert(i, i, guard);
+        }
+
+        map.shrink_to_fit(guard);
+        assert_eq!(map.capacity(guard), 256);
+        let t = map.table.load(Ordering::Relaxed, guard);
+        let t = unsafe { t.deref() };
+        let bin = t.bin(t.bini(0), guard);
+        assert!(matches!(unsafe { &**bin.deref() }, BinEntry::Tree(_)));
+        for i in 0..100 {
+            assert_eq!(map.get(&i, guard), Some(&i));
+        }
+        assert_eq!(map.iter(guard).count(), 100);
+    }
+
+    #[test]
+    fn resize_eager_tree_bin() {
+        let map = HashMap::<usize, usize, _>::with_hasher(ZeroHashBuilder);
+        let guard = &map.guard();
+        for i in 0..100 {
+            map.insert(i, i, guard);
+        }
+
+        map.resize_eager(1000, guard);
+        assert_eq!(map.capacity(guard), 2048);
+        let t = map.table.load(Ordering::Relaxed, guard);
+        let t = unsafe { t.deref() };
+        let bin = t.bin(t.bini(0), guard);
+        assert!(matches!(unsafe { &**bin.deref() }, BinEntry::Tree(_)));
+        for i in 0..100 {
+            assert_eq!(map.get(&i, guard), Some(&i));
+        }
+        assert_eq!(map.iter(guard).count(), 100);
+    }
+
+    #[test]
+    #[cfg(feature = "versioned")]
+    fn iter_since_tree_bin() {
+        let map = HashMap::<usize, usize, _>::with_hasher(ZeroHashBuilder);
+        let guard = &map.guard();
+        for i in 0..10 {
+            map.insert(i, i, guard);
+        }
+        let seen = map.current_version();
+        map.insert(3, 30, guard);
+        map.compute_if_present(&4, |_, v| Some(v * 10), guard);
+        map.replace_key(5, 50, guard);
+        map.insert(10, 100, guard);
+
+        let t = map.table.load(Ordering::Relaxed, guard);
+        let t = unsafe { t.deref() };
+        let bin = t.bin(t.bini(0), guard);
+        assert!(matches!(unsafe { &**bin.deref() }, BinEntry::Tree(_)));
+        let mut changed: Vec<_> = map.iter_since(seen, guard).collect();
+        changed.sort();
+        assert_eq!(changed, [(&3, &30), (&4, &40), (&5, &50), (&10, &100)]);
+
+        // moving the bin into a larger table keeps the versions
+        let seen = map.current_version();
+        map.reserve(1024, guard);
+        assert_eq!(map.iter_since(seen, guard).count(), 0);
+        assert_eq!(map.iter_since(0, guard).count(), 11);
+
+        // and so does turning it back into a linked bin
+        for i in 0..8 {
+            map.remove(&i, guard);
+        }
+        let bin = map.table.load(Ordering::Relaxed, guard);
+        let bin = unsafe { bin.deref() }.bin(0, guard);
+        assert!(matches!(unsafe { &**bin.deref() }, BinEntry::Node(_)));
+        assert_eq!(map.iter_since(seen, guard).count(), 0);
+    }
+
+    #[test]
+    fn swap_tree_bin() {
+        let map = HashMap::<usize, usize, _>::with_hasher(ZeroHashBuilder);
+        let guard = &map.guard();
+        for i in 0..10 {
+            map.insert(i, i, guard);
+        }
+
+        assert!(map.swap(&2, &7, guard));
+        assert!(!map.swap(&2, &10, guard));
+        let t = map.table.load(Ordering::Relaxed, guard);
+        let t = unsafe { t.deref() };
+        let bin = t.bin(t.bini(0), guard);
+        assert!(matches!(unsafe { &**bin.deref() }, BinEntry::Tree(_)));
+        for i in 0..10 {
+            let expected = match i {
+                2 => 7,
+                7 => 2,
+                i => i,
+            };
+            assert_eq!(map.get(&i, guard), Some(&expected));
+        }
+    }
+
+    #[test]
+    fn raw_entry_tree_bin() {
+        let map = HashMap::<usize, usize, _>::with_hasher(ZeroHashBuilder);
+        let guard = &map.guard();
+        for i in 0..10 {
+            map.insert(i, i, guard);
+        }
+
+        assert_eq!(map.raw_entry(0, guard).from_key(&7), Some((&7, &7)));
+        assert_eq!(
+            map.raw_entry(0, guard).from_match(|&k| k > 8),
+            Some((&9, &9))
+        );
+        assert_eq!(map.raw_entry(0, guard).from_key(&10), None);
+        assert_eq!(
+            map.raw_entry_mut(0, guard).from_key(&10).or_insert(10, 10),
+            &10
+        );
+        let t = map.table.load(Ordering::Relaxed, guard);
+        let t = unsafe { t.deref() };
+        let bin = t.bin(t.bini(0), guard);
+        assert!(matches!(unsafe { &**bin.deref() }, BinEntry::Tree(_)));
+        assert_eq!(map.get(&10, guard), Some(&10));
+    }
+
+    #[test]
+    fn replace_key_tree_bin() {
+        let map = HashMap::<usize, usize, _>::with_hasher(ZeroHashBuilder);
+        let guard = &map.guard();
+        for i in 0..10 {
+            map.insert(i, i, guard);
+        }
+
+        assert_eq!(map.replace_key(5, 50, guard), Some(&5));
+        assert_eq!(map.replace_key(10, 100, guard), None);
+        let t = map.table.load(Ordering::Relaxed, guard);
+        let t = unsafe { t.deref() };
+        let bin = t.bin(t.bini(0), guard);
+        assert!(matches!(unsafe { &**bin.deref() }, BinEntry::Tree(_)));
+        assert_eq!(map.len(), 11);
+        for i in 0..10 {
+            let expected = if i == 5 { 50 } else { i };
+            assert_eq!(map.get(&i, guard), Some(&expected));
+        }
+        assert_eq!(map.get(&10, guard), Some(&100));
+    }
+
+    #[test]
+    fn remove_if_tree_bin() {
+        let map = HashMap::<usize, usize, _>::with_hasher(ZeroHashBuilder);
+        let guard = &map.guard();
+        for i in 0..10 {
+            map.insert(i, i, guard);
+        }
+
+        assert_eq!(map.remove_if(&5, |_, v| *v == 0, guard), None);
+        assert_eq!(map.remove_if(&10, |_, _| unreachable!(), guard), None);
+        assert_eq!(map.remove_if(&5, |k, v| k == v, guard), Some(&5));
+        assert_eq!(map.len(), 9);
+        for i in 0..10 {
+            assert_eq!(map.get(&i, guard).is_some(), i != 5);
+        }
+    }
+
     #[test]
     #[cfg_attr(miri, ignore)]
     fn concurrent_tree_bin() {
@@ -3419,6 +9042,9 @@ mod tree_bins {
                 BinEntry::Moved => panic!("bin was not correctly treeified -- is Moved"),
                 BinEntry::Node(_) => panic!("bin was not correctly treeified -- is Node"),
                 BinEntry::TreeNode(_) => panic!("bin was not correctly treeified -- is TreeNode"),
+                BinEntry::Reservation(_) => {
+                    panic!("bin was not correctly treeified -- is Reservation")
+                }
             }
 
             let _ = guard;
@@ -3495,6 +9121,87 @@ mod tree_bins {
         });
     }
 
+    #[test]
+    fn compute_if_absent_tree_bin() {
+        let map = HashMap::<usize, usize, _>::with_hasher(ZeroHashBuilder);
+        let guard = &map.guard();
+        // Force creation of a tree bin by computing enough values that hash to 0
+        for i in 0..10 {
+            assert_eq!(map.compute_if_absent(i, |&k| k + 1, guard), &(i + 1));
+        }
+        // Ensure the bin was correctly treeified
+        let t = map.table.load(Ordering::Relaxed, guard);
+        let t = unsafe { t.deref() };
+        let bin = t.bin(t.bini(0), guard);
+        assert!(matches!(unsafe { &**bin.deref() }, BinEntry::Tree(_)));
+
+        // keys in the tree must not be computed again
+        for i in 0..10 {
+            assert_eq!(
+                map.compute_if_absent(i, |_| unreachable!(), guard),
+                &(i + 1)
+            );
+        }
+        assert_eq!(map.compute_if_absent(10, |&k| k + 1, guard), &11);
+        assert_eq!(map.len(), 11);
+    }
+
+    #[test]
+    fn untreeify_shared_values_compute() {
+        test_tree_bin_remove(|i, map, guard| {
+            assert_eq!(map.compute(i, |_, _| None, guard), None);
+        });
+    }
+
+    #[test]
+    fn compute_tree_bin() {
+        let map = HashMap::<usize, usize, _>::with_hasher(ZeroHashBuilder);
+        let guard = &map.guard();
+        // Force creation of a tree bin by computing enough values that hash to 0
+        for i in 0..10 {
+            assert_eq!(
+                map.compute(i, |_, v| Some(v.map_or(i, |v| v + 1)), guard),
+                Some(&i)
+            );
+        }
+        let t = map.table.load(Ordering::Relaxed, guard);
+        let t = unsafe { t.deref() };
+        let bin = t.bin(t.bini(0), guard);
+        assert!(matches!(unsafe { &**bin.deref() }, BinEntry::Tree(_)));
+
+        for i in 0..10 {
+            assert_eq!(
+                map.compute(i, |_, v| Some(v.unwrap() + 1), guard),
+                Some(&(i + 1))
+            );
+        }
+        assert_eq!(map.compute(10, |_, _| None, guard), None);
+        assert_eq!(map.len(), 10);
+    }
+
+    #[test]
+    fn into_iter_tree_bin() {
+        let map = HashMap::<usize, String, _>::with_hasher(ZeroHashBuilder);
+        {
+            let guard = &map.guard();
+            // Force creation of a tree bin by inserting enough values that hash to 0
+            for i in 0..10 {
+                map.insert(i, i.to_string(), guard);
+            }
+            let t = map.table.load(Ordering::Relaxed, guard);
+            let t = unsafe { t.deref() };
+            let bin = t.bin(t.bini(0), guard);
+            assert!(matches!(unsafe { &**bin.deref() }, BinEntry::Tree(_)));
+        }
+
+        let mut entries: Vec<_> = map.into_iter().collect();
+        entries.sort_unstable();
+        assert_eq!(
+            entries,
+            (0..10).map(|i| (i, i.to_string())).collect::<Vec<_>>()
+        );
+    }
+
     fn test_tree_bin_remove<F>(f: F)
     where
         F: Fn(usize, &HashMap<usize, usize, ZeroHashBuilder>, &Guard<'_>),
@@ -3516,6 +9223,9 @@ mod tree_bins {
                 BinEntry::Moved => panic!("bin was not correctly treeified -- is Moved"),
                 BinEntry::Node(_) => panic!("bin was not correctly treeified -- is Node"),
                 BinEntry::TreeNode(_) => panic!("bin was not correctly treeified -- is TreeNode"),
+                BinEntry::Reservation(_) => {
+                    panic!("bin was not correctly treeified -- is Reservation")
+                }
             }
 
             // Delete keys to force untreeifying the bin
@@ -3538,6 +9248,9 @@ mod tree_bins {
                 BinEntry::Moved => panic!("bin was not correctly untreeified -- is Moved"),
                 BinEntry::Node(_) => {} // pass
                 BinEntry::TreeNode(_) => panic!("bin was not correctly untreeified -- is TreeNode"),
+                BinEntry::Reservation(_) => {
+                    panic!("bin was not correctly untreeified -- is Reservation")
+                }
             }
         }
 
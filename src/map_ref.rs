@@ -1,10 +1,14 @@
 use crate::iter::*;
 use crate::reclaim::{Guard, GuardRef};
-use crate::{HashMap, TryInsertError};
+use crate::{
+    Entry, FrozenHashMap, HashMap, MapStats, RawEntryBuilder, RawEntryBuilderMut, TryInsertError,
+    TryReserveError,
+};
 use std::borrow::Borrow;
 use std::fmt::{self, Debug, Formatter};
 use std::hash::{BuildHasher, Hash};
-use std::ops::Index;
+use std::ops::{Index, RangeBounds};
+use std::sync::Arc;
 
 /// A reference to a [`HashMap`], constructed with [`HashMap::pin`] or [`HashMap::with_guard`].
 ///
@@ -12,7 +16,7 @@ use std::ops::Index;
 /// Keep in mind that this prevents the collection of garbage generated by the map.
 pub struct HashMapRef<'map, K, V, S = crate::DefaultHashBuilder> {
     pub(crate) map: &'map HashMap<K, V, S>,
-    guard: GuardRef<'map>,
+    pub(crate) guard: GuardRef<'map>,
 }
 
 impl<K, V, S> HashMap<K, V, S> {
@@ -34,9 +38,70 @@ impl<K, V, S> HashMap<K, V, S> {
             map: self,
         }
     }
+
+    /// Get an owned handle to this map with the current thread pinned.
+    ///
+    /// Unlike [`HashMap::pin`], the returned handle holds its own clone of the [`Arc`], so it
+    /// does not borrow from anything and can be stored alongside the map, for example as a field
+    /// of a long-lived struct. Like [`HashMapRef`], it cannot be sent to another thread, and it
+    /// prevents the collection of garbage generated by the map for as long as it is held.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::{HashMap, OwnedHashMapRef};
+    /// use std::sync::Arc;
+    ///
+    /// struct Actor {
+    ///     map: OwnedHashMapRef<i32, &'static str>,
+    /// }
+    ///
+    /// let map = Arc::new(HashMap::new());
+    /// let actor = Actor { map: map.pin_arc() };
+    /// actor.map.map_ref().insert(1, "a");
+    /// assert_eq!(actor.map.map_ref().get(&1), Some(&"a"));
+    /// ```
+    pub fn pin_arc(self: &Arc<Self>) -> OwnedHashMapRef<K, V, S> {
+        let map = Arc::clone(self);
+        // safety: the guard only borrows the map's collector, which lives in its own heap
+        // allocation that the map keeps alive, so its address does not change while `map` is
+        // alive. `OwnedHashMapRef` declares `guard` before `map`, so the guard is dropped before
+        // the `Arc` is.
+        let guard = unsafe { std::mem::transmute::<Guard<'_>, Guard<'static>>(map.guard()) };
+        OwnedHashMapRef { guard, map }
+    }
 }
 
 impl<K, V, S> HashMapRef<'_, K, V, S> {
+    /// Returns the guard this reference holds, for use with the methods on [`HashMap`].
+    ///
+    /// This is the guard the reference was created with by [`HashMap::with_guard`], or the one
+    /// that [`HashMap::pin`] created for it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map = HashMap::new();
+    /// let mref = map.pin();
+    /// mref.insert(1, "a");
+    ///
+    /// // mix methods on the reference with those on the map, without pinning again
+    /// let value = map.get(&1, mref.guard());
+    /// assert_eq!(value, Some(&"a"));
+    /// ```
+    pub fn guard(&self) -> &Guard<'_> {
+        &self.guard
+    }
+
+    /// Returns a reference to the map's [`BuildHasher`].
+    ///
+    /// See also [`HashMap::hasher`].
+    pub fn hasher(&self) -> &S {
+        self.map.hasher()
+    }
+
     /// Returns the number of entries in the map.
     ///
     /// See also [`HashMap::len`].
@@ -51,6 +116,54 @@ impl<K, V, S> HashMapRef<'_, K, V, S> {
         self.map.is_empty()
     }
 
+    /// Returns the number of bins in the map's current table.
+    ///
+    /// See also [`HashMap::capacity`].
+    pub fn capacity(&self) -> usize {
+        self.map.capacity(&self.guard)
+    }
+
+    /// Returns an estimate of the number of bytes the map has allocated for its table and its
+    /// entries.
+    ///
+    /// See also [`HashMap::memory_usage`].
+    pub fn memory_usage(&self) -> usize {
+        self.map.memory_usage(&self.guard)
+    }
+
+    /// Returns `true` if the map is currently moving its entries to a new table.
+    ///
+    /// See also [`HashMap::is_resizing`].
+    pub fn is_resizing(&self) -> bool {
+        self.map.is_resizing(&self.guard)
+    }
+
+    /// Returns the number of elements in the map together with the number of bins in the table
+    /// that those elements were counted in.
+    ///
+    /// See also [`HashMap::len_and_capacity`].
+    pub fn len_and_capacity(&self) -> (usize, usize) {
+        self.map.len_and_capacity(&self.guard)
+    }
+
+    /// Returns runtime statistics about the map.
+    ///
+    /// See also [`HashMap::stats`].
+    pub fn stats(&self) -> MapStats {
+        self.map.stats()
+    }
+
+    /// Registers a callback that is invoked with the old and new capacity whenever the map
+    /// finishes resizing.
+    ///
+    /// See also [`HashMap::on_resize`].
+    pub fn on_resize<F>(&self, f: F)
+    where
+        F: Fn(usize, usize) + Send + Sync + 'static,
+    {
+        self.map.on_resize(f)
+    }
+
     /// An iterator visiting all key-value pairs in arbitrary order.
     ///
     /// The iterator element type is `(&'g K, &'g V)`.
@@ -60,6 +173,32 @@ impl<K, V, S> HashMapRef<'_, K, V, S> {
         self.map.iter(&self.guard)
     }
 
+    /// Returns some entry of the map, or `None` if the map is empty.
+    ///
+    /// See also [`HashMap::any_entry`].
+    pub fn any_entry(&self) -> Option<(&'_ K, &'_ V)> {
+        self.map.any_entry(&self.guard)
+    }
+
+    /// Returns up to `k` entries of the map, chosen uniformly at random using `rng`.
+    ///
+    /// See also [`HashMap::sample`].
+    #[cfg(feature = "rand")]
+    pub fn sample<R>(&self, k: usize, rng: &mut R) -> Vec<(&'_ K, &'_ V)>
+    where
+        R: rand::Rng + ?Sized,
+    {
+        self.map.sample(k, rng, &self.guard)
+    }
+
+    /// An iterator visiting all key-value pairs in arbitrary order, along with the length of the
+    /// map when the iterator was created.
+    ///
+    /// See also [`HashMap::iter_with_len`].
+    pub fn iter_with_len(&self) -> (usize, Iter<'_, K, V>) {
+        self.map.iter_with_len(&self.guard)
+    }
+
     /// An iterator visiting all keys in arbitrary order.
     ///
     /// The iterator element type is `&'g K`.
@@ -77,6 +216,142 @@ impl<K, V, S> HashMapRef<'_, K, V, S> {
     pub fn values(&self) -> Values<'_, K, V> {
         self.map.values(&self.guard)
     }
+
+    /// An iterator visiting all key-value pairs in arbitrary order, along with the hash of each
+    /// key.
+    ///
+    /// See also [`HashMap::iter_hashed`].
+    pub fn iter_hashed(&self) -> IterHashed<'_, K, V> {
+        self.map.iter_hashed(&self.guard)
+    }
+
+    /// Returns the latest version handed out by the map.
+    ///
+    /// See also [`HashMap::current_version`].
+    #[cfg(feature = "versioned")]
+    pub fn current_version(&self) -> u64 {
+        self.map.current_version()
+    }
+
+    /// An iterator visiting the key-value pairs that were inserted or updated after `version`.
+    ///
+    /// See also [`HashMap::iter_since`].
+    #[cfg(feature = "versioned")]
+    pub fn iter_since(&self, version: u64) -> IterSince<'_, K, V> {
+        self.map.iter_since(version, &self.guard)
+    }
+
+    /// An iterator visiting clones of all key-value pairs in arbitrary order.
+    ///
+    /// See also [`HashMap::iter_cloned`].
+    pub fn iter_cloned(&self) -> IterCloned<'_, K, V>
+    where
+        K: Clone,
+        V: Clone,
+    {
+        self.map.iter_cloned(&self.guard)
+    }
+
+    /// An iterator visiting clones of all keys in arbitrary order.
+    ///
+    /// See also [`HashMap::keys_cloned`].
+    pub fn keys_cloned(&self) -> KeysCloned<'_, K, V>
+    where
+        K: Clone,
+    {
+        self.map.keys_cloned(&self.guard)
+    }
+
+    /// An iterator visiting clones of all values in arbitrary order.
+    ///
+    /// See also [`HashMap::values_cloned`].
+    pub fn values_cloned(&self) -> ValuesCloned<'_, K, V>
+    where
+        V: Clone,
+    {
+        self.map.values_cloned(&self.guard)
+    }
+
+    /// An iterator visiting the key-value pairs whose keys fall within `range`.
+    ///
+    /// See also [`HashMap::range`].
+    pub fn range<Q, R>(&self, range: R) -> Range<'_, K, V, Q, R>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+        R: RangeBounds<Q>,
+    {
+        self.map.range(range, &self.guard)
+    }
+
+    /// An iterator visiting the bins of the map, each of which yields the key-value pairs in that
+    /// bin.
+    ///
+    /// See also [`HashMap::bins`].
+    pub fn bins(&self) -> Bins<'_, K, V> {
+        self.map.bins(&self.guard)
+    }
+
+    /// Collects all key-value pairs into a `Vec`, sorted by key.
+    ///
+    /// See also [`HashMap::debug_iter_sorted`].
+    pub fn debug_iter_sorted(&self) -> Vec<(&'_ K, &'_ V)>
+    where
+        K: Ord,
+    {
+        self.map.debug_iter_sorted(&self.guard)
+    }
+
+    /// Calls `f` on each key-value pair in the map, in arbitrary order.
+    ///
+    /// See also [`HashMap::for_each`].
+    pub fn for_each<F>(&self, f: F)
+    where
+        F: FnMut(&K, &V),
+    {
+        self.map.for_each(f, &self.guard);
+    }
+
+    /// Folds every key-value pair in the map into an accumulator, in arbitrary order.
+    ///
+    /// See also [`HashMap::fold`].
+    pub fn fold<B, F>(&self, init: B, f: F) -> B
+    where
+        F: FnMut(B, &K, &V) -> B,
+    {
+        self.map.fold(init, f, &self.guard)
+    }
+
+    /// Collects the results of `f` for every key-value pair for which it returns `Some`, in
+    /// arbitrary order.
+    ///
+    /// See also [`HashMap::collect_map`].
+    pub fn collect_map<F, T>(&self, f: F) -> Vec<T>
+    where
+        F: FnMut(&K, &V) -> Option<T>,
+    {
+        self.map.collect_map(f, &self.guard)
+    }
+
+    /// Returns the number of key-value pairs for which `f` returns `true`.
+    ///
+    /// See also [`HashMap::count_if`].
+    pub fn count_if<F>(&self, f: F) -> usize
+    where
+        F: FnMut(&K, &V) -> bool,
+    {
+        self.map.count_if(f, &self.guard)
+    }
+
+    /// Returns `true` if the map maps one or more keys to the given value.
+    ///
+    /// See also [`HashMap::contains_value`].
+    pub fn contains_value(&self, value: &V) -> bool
+    where
+        V: PartialEq,
+    {
+        self.map.contains_value(value, &self.guard)
+    }
 }
 
 impl<K, V, S> HashMapRef<'_, K, V, S>
@@ -92,6 +367,22 @@ where
     pub fn reserve(&self, additional: usize) {
         self.map.reserve(additional, &self.guard)
     }
+
+    /// Tries to reserve capacity for at least `additional` more elements to be inserted in the
+    /// `HashMap`, returning an error if that is not possible.
+    ///
+    /// See also [`HashMap::try_reserve`].
+    pub fn try_reserve(&self, additional: usize) -> Result<(), TryReserveError> {
+        self.map.try_reserve(additional, &self.guard)
+    }
+
+    /// Allocates the map's table with room for at least `capacity` elements in total before this
+    /// method returns.
+    ///
+    /// See also [`HashMap::preallocate`].
+    pub fn preallocate(&self, capacity: usize) {
+        self.map.preallocate(capacity, &self.guard)
+    }
 }
 
 impl<K, V, S> HashMapRef<'_, K, V, S>
@@ -133,6 +424,49 @@ where
     {
         self.map.get_key_value(key, &self.guard)
     }
+
+    /// Returns a reference to the key stored in the map for `key`.
+    ///
+    /// See also [`HashMap::get_key`].
+    #[inline]
+    pub fn get_key<'g, Q>(&'g self, key: &Q) -> Option<&'g K>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Ord,
+    {
+        self.map.get_key(key, &self.guard)
+    }
+
+    /// Returns a clone of the value corresponding to the key.
+    ///
+    /// See also [`HashMap::get_cloned_guarded`].
+    #[inline]
+    pub fn get_cloned_guarded<Q>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Ord,
+        V: Clone,
+    {
+        self.map.get_cloned_guarded(key, &self.guard)
+    }
+
+    /// Creates a builder for looking up an entry by a precomputed `hash`.
+    ///
+    /// See also [`HashMap::raw_entry`].
+    pub fn raw_entry(&self, hash: u64) -> RawEntryBuilder<'_, K, V, S> {
+        self.map.raw_entry(hash, &self.guard)
+    }
+
+    /// Returns references to the values corresponding to each of the given `keys`.
+    ///
+    /// See also [`HashMap::get_many`].
+    pub fn get_many<'g, Q, const N: usize>(&'g self, keys: [&Q; N]) -> [Option<&'g V>; N]
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Ord,
+    {
+        self.map.get_many(keys, &self.guard)
+    }
 }
 
 impl<K, V, S> HashMapRef<'_, K, V, S>
@@ -145,6 +479,13 @@ where
     pub fn clear(&self) {
         self.map.clear(&self.guard);
     }
+
+    /// Clears the map by swapping in a fresh, empty table.
+    ///
+    /// See also [`HashMap::clear_fast`].
+    pub fn clear_fast(&self) {
+        self.map.clear_fast(&self.guard);
+    }
 }
 
 impl<K, V, S> HashMapRef<'_, K, V, S>
@@ -160,6 +501,43 @@ where
         self.map.insert(key, value, &self.guard)
     }
 
+    /// Inserts a key-value pair into the map, and returns a clone of the value it replaced (if
+    /// any).
+    ///
+    /// See also [`HashMap::insert_owned`].
+    pub fn insert_owned(&self, key: K, value: V) -> Option<V>
+    where
+        V: Clone,
+    {
+        self.map.insert_owned(key, value, &self.guard)
+    }
+
+    /// Inserts a key-value pair into the map, returning an error instead of aborting if the
+    /// table that the insert would have to allocate cannot be allocated.
+    ///
+    /// See also [`HashMap::try_insert_alloc`].
+    pub fn try_insert_alloc(&self, key: K, value: V) -> Result<Option<&'_ V>, TryReserveError> {
+        self.map.try_insert_alloc(key, value, &self.guard)
+    }
+
+    /// Inserts a key-value pair into the map, and reports whether doing so moved bins for a
+    /// resize.
+    ///
+    /// See also [`HashMap::insert_cooperative`].
+    pub fn insert_cooperative(&self, key: K, value: V) -> (Option<&'_ V>, bool) {
+        self.map.insert_cooperative(key, value, &self.guard)
+    }
+
+    /// Inserts all key-value pairs from `iter` into the map, resizing at most once at the end.
+    ///
+    /// See also [`HashMap::bulk_insert`].
+    pub fn bulk_insert<I>(&self, iter: I)
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        self.map.bulk_insert(iter, &self.guard)
+    }
+
     /// Inserts a key-value pair into the map unless the key already exists.
     ///
     /// See also [`HashMap::try_insert`].
@@ -168,6 +546,73 @@ where
         self.map.try_insert(key, value, &self.guard)
     }
 
+    /// Returns a reference to the value for `key`, inserting `value` first if the key is not
+    /// already present.
+    ///
+    /// See also [`HashMap::get_or_insert`].
+    pub fn get_or_insert(&self, key: K, value: V) -> &'_ V {
+        self.map.get_or_insert(key, value, &self.guard)
+    }
+
+    /// Returns a reference to the value for `key`, inserting the result of `f` first if the key
+    /// is not already present.
+    ///
+    /// See also [`HashMap::get_or_insert_with`].
+    pub fn get_or_insert_with<F>(&self, key: K, f: F) -> &'_ V
+    where
+        F: FnOnce() -> V,
+    {
+        self.map.get_or_insert_with(key, f, &self.guard)
+    }
+
+    /// Returns a reference to the value for `key`, inserting the result of calling `f` with the
+    /// key first if the key is not already present.
+    ///
+    /// See also [`HashMap::get_or_insert_with_key`].
+    pub fn get_or_insert_with_key<F>(&self, key: K, f: F) -> &'_ V
+    where
+        F: FnOnce(&K) -> V,
+    {
+        self.map.get_or_insert_with_key(key, f, &self.guard)
+    }
+
+    /// Returns a reference to the value for `key`, inserting the result of `f` first if the key
+    /// is not already present and `f` succeeds.
+    ///
+    /// See also [`HashMap::get_or_try_insert_with`].
+    pub fn get_or_try_insert_with<F, E>(&self, key: K, f: F) -> Result<&'_ V, E>
+    where
+        F: FnOnce() -> Result<V, E>,
+    {
+        self.map.get_or_try_insert_with(key, f, &self.guard)
+    }
+
+    /// Gets the given key's corresponding entry in the map for in-place manipulation.
+    ///
+    /// See also [`HashMap::entry`].
+    pub fn entry(&self, key: K) -> Entry<'_, K, V, S> {
+        self.map.entry(key, &self.guard)
+    }
+
+    /// Creates a builder for looking up or inserting an entry by a precomputed `hash`.
+    ///
+    /// See also [`HashMap::raw_entry_mut`].
+    pub fn raw_entry_mut(&self, hash: u64) -> RawEntryBuilderMut<'_, K, V, S> {
+        self.map.raw_entry_mut(hash, &self.guard)
+    }
+
+    /// If the specified `key` is not already associated with a value, attempts to compute its
+    /// value using the given `mapping_function` and inserts it into the map.
+    ///
+    /// See also [`HashMap::compute_if_absent`].
+    pub fn compute_if_absent<F>(&self, key: K, mapping_function: F) -> &'_ V
+    where
+        F: FnOnce(&K) -> V,
+    {
+        self.map
+            .compute_if_absent(key, mapping_function, &self.guard)
+    }
+
     /// If the value for the specified `key` is present, attempts to
     /// compute a new mapping given the key and its current mapped value.
     ///
@@ -182,6 +627,67 @@ where
             .compute_if_present(key, remapping_function, &self.guard)
     }
 
+    /// If the value for the specified `key` is present, replaces it with a new value computed from
+    /// the current one.
+    ///
+    /// See also [`HashMap::update`].
+    pub fn update<'g, Q, F>(&'g self, key: &Q, f: F) -> Option<&'g V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Ord,
+        F: FnOnce(&V) -> V,
+    {
+        self.map.update(key, f, &self.guard)
+    }
+
+    /// Swaps the values of `key_a` and `key_b`.
+    ///
+    /// See also [`HashMap::swap`].
+    pub fn swap<Q>(&self, key_a: &Q, key_b: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Ord,
+    {
+        self.map.swap(key_a, key_b, &self.guard)
+    }
+
+    /// If the value for the specified `key` is present, lets `f` modify a clone of it while the
+    /// key's bin is locked, replaces the value with that clone, and returns what `f` returns.
+    ///
+    /// See also [`HashMap::with_value_mut`].
+    pub fn with_value_mut<Q, F, R>(&self, key: &Q, f: F) -> Option<R>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Ord,
+        V: Clone,
+        F: FnOnce(&mut V) -> R,
+    {
+        self.map.with_value_mut(key, f, &self.guard)
+    }
+
+    /// Attempts to compute a mapping for the specified `key` and its current mapped value (or
+    /// `None` if there is no current mapping).
+    ///
+    /// See also [`HashMap::compute`].
+    pub fn compute<F>(&self, key: K, remapping_function: F) -> Option<&'_ V>
+    where
+        F: FnOnce(&K, Option<&V>) -> Option<V>,
+    {
+        self.map.compute(key, remapping_function, &self.guard)
+    }
+
+    /// If the specified `key` is not already associated with a value, associates it with the
+    /// given `value`. Otherwise, replaces the value with the result of the given
+    /// `remapping_function`, or removes the mapping if the function returns `None`.
+    ///
+    /// See also [`HashMap::merge`].
+    pub fn merge<F>(&self, key: K, value: V, remapping_function: F) -> Option<&'_ V>
+    where
+        F: FnOnce(&V, &V) -> Option<V>,
+    {
+        self.map.merge(key, value, remapping_function, &self.guard)
+    }
+
     /// Removes a key-value pair from the map, and returns the removed value (if any).
     ///
     /// See also [`HashMap::remove`].
@@ -193,6 +699,31 @@ where
         self.map.remove(key, &self.guard)
     }
 
+    /// Removes a key-value pair from the map, and returns a clone of the removed value (if any).
+    ///
+    /// See also [`HashMap::remove_owned`].
+    pub fn remove_owned<Q>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Ord,
+        V: Clone,
+    {
+        self.map.remove_owned(key, &self.guard)
+    }
+
+    /// Removes each of the given keys from the map, and returns the number of entries that were
+    /// actually removed.
+    ///
+    /// See also [`HashMap::remove_many`].
+    pub fn remove_many<'q, Q, I>(&self, keys: I) -> usize
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Ord + 'q,
+        I: IntoIterator<Item = &'q Q>,
+    {
+        self.map.remove_many(keys, &self.guard)
+    }
+
     /// Removes a key from the map, returning the stored key and value if the
     /// key was previously in the map.
     ///
@@ -205,6 +736,57 @@ where
         self.map.remove_entry(key, &self.guard)
     }
 
+    /// Replaces the value for `key` with `value`, but only if the key is already present, and
+    /// returns the previous value.
+    ///
+    /// See also [`HashMap::replace`].
+    pub fn replace<'g, Q>(&'g self, key: &Q, value: V) -> Option<&'g V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Ord,
+    {
+        self.map.replace(key, value, &self.guard)
+    }
+
+    /// Replaces the value for `key` with `new`, but only if the current value equals `expected`.
+    ///
+    /// See also [`HashMap::replace_if_eq`].
+    pub fn replace_if_eq<'g, Q>(&'g self, key: &Q, expected: &V, new: V) -> Result<Option<&'g V>, V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Ord,
+        V: PartialEq,
+    {
+        self.map.replace_if_eq(key, expected, new, &self.guard)
+    }
+
+    /// Removes a key from the map if `predicate` returns `true` for its current value, and returns
+    /// the removed value (if any).
+    ///
+    /// See also [`HashMap::remove_if`].
+    pub fn remove_if<'g, Q, F>(&'g self, key: &Q, predicate: F) -> Option<&'g V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Ord,
+        F: FnOnce(&K, &V) -> bool,
+    {
+        self.map.remove_if(key, predicate, &self.guard)
+    }
+
+    /// Removes a key from the map if `predicate` returns `true` for its current value, and returns
+    /// a clone of the removed value (if any).
+    ///
+    /// See also [`HashMap::take_if`].
+    pub fn take_if<Q, F>(&self, key: &Q, predicate: F) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Ord,
+        F: FnOnce(&K, &V) -> bool,
+        V: Clone,
+    {
+        self.map.take_if(key, predicate, &self.guard)
+    }
+
     /// Retains only the elements specified by the predicate.
     ///
     /// See also [`HashMap::retain`].
@@ -215,6 +797,17 @@ where
         self.map.retain(f, &self.guard);
     }
 
+    /// Retains only the elements specified by the predicate, removing at most `max_removals`
+    /// entries.
+    ///
+    /// See also [`HashMap::retain_bounded`].
+    pub fn retain_bounded<F>(&self, max_removals: usize, f: F)
+    where
+        F: FnMut(&K, &V) -> bool,
+    {
+        self.map.retain_bounded(max_removals, f, &self.guard);
+    }
+
     /// Retains only the elements specified by the predicate.
     ///
     /// See also [`HashMap::retain_force`].
@@ -224,6 +817,59 @@ where
     {
         self.map.retain_force(f, &self.guard);
     }
+
+    /// Replaces every value in the map with the result of `f`, or removes the entry if `f`
+    /// returns `None`.
+    ///
+    /// See also [`HashMap::update_all`].
+    pub fn update_all<F>(&self, f: F)
+    where
+        F: FnMut(&K, &V) -> Option<V>,
+    {
+        self.map.update_all(f, &self.guard);
+    }
+
+    /// Removes all entries from the map, and returns an iterator over the removed entries.
+    ///
+    /// See also [`HashMap::drain`].
+    pub fn drain(&self) -> Drain<'_, K, V, S> {
+        self.map.drain(&self.guard)
+    }
+
+    /// Shrinks the capacity of the map as much as possible.
+    ///
+    /// See also [`HashMap::shrink_to_fit`].
+    pub fn shrink_to_fit(&self) {
+        self.map.shrink_to_fit(&self.guard);
+    }
+
+    /// Shrinks the capacity of the map with a lower limit.
+    ///
+    /// See also [`HashMap::shrink_to`].
+    pub fn shrink_to(&self, min_capacity: usize) {
+        self.map.shrink_to(min_capacity, &self.guard);
+    }
+
+    /// Resizes the map to hold `capacity` elements, moving all entries before returning.
+    ///
+    /// See also [`HashMap::resize_eager`].
+    pub fn resize_eager(&self, capacity: usize) {
+        self.map.resize_eager(capacity, &self.guard);
+    }
+}
+
+impl<K, V, S> HashMapRef<'_, K, V, S>
+where
+    K: Clone + Hash + Eq,
+    V: Clone,
+    S: BuildHasher + Clone,
+{
+    /// Copies the entries of the map into an immutable [`FrozenHashMap`].
+    ///
+    /// See also [`HashMap::freeze`].
+    pub fn freeze(&self) -> FrozenHashMap<K, V, S> {
+        self.map.freeze(&self.guard)
+    }
 }
 
 impl<'g, K, V, S> IntoIterator for &'g HashMapRef<'_, K, V, S> {
@@ -235,6 +881,39 @@ impl<'g, K, V, S> IntoIterator for &'g HashMapRef<'_, K, V, S> {
     }
 }
 
+impl<K, V, S> Extend<(K, V)> for &HashMapRef<'_, K, V, S>
+where
+    K: Sync + Send + Clone + Hash + Ord,
+    V: Sync + Send,
+    S: BuildHasher,
+{
+    fn extend<T: IntoIterator<Item = (K, V)>>(&mut self, iter: T) {
+        // see `Extend for &HashMap` for the reservation strategy
+        let iter = iter.into_iter();
+        let reserve = if self.is_empty() {
+            iter.size_hint().0
+        } else {
+            (iter.size_hint().0 + 1) / 2
+        };
+
+        self.reserve(reserve);
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<'a, K, V, S> Extend<(&'a K, &'a V)> for &HashMapRef<'_, K, V, S>
+where
+    K: Sync + Send + Copy + Hash + Ord,
+    V: Sync + Send + Copy,
+    S: BuildHasher,
+{
+    fn extend<T: IntoIterator<Item = (&'a K, &'a V)>>(&mut self, iter: T) {
+        self.extend(iter.into_iter().map(|(&key, &value)| (key, value)));
+    }
+}
+
 impl<K, V, S> Debug for HashMapRef<'_, K, V, S>
 where
     K: Debug,
@@ -284,6 +963,30 @@ where
     }
 }
 
+impl<K, V, S, S2> PartialEq<std::collections::HashMap<K, V, S2>> for HashMapRef<'_, K, V, S>
+where
+    K: Hash + Ord,
+    V: PartialEq,
+    S: BuildHasher,
+    S2: BuildHasher,
+{
+    fn eq(&self, other: &std::collections::HashMap<K, V, S2>) -> bool {
+        self.map.std_eq(other, &self.guard)
+    }
+}
+
+impl<K, V, S, S2> PartialEq<HashMapRef<'_, K, V, S>> for std::collections::HashMap<K, V, S2>
+where
+    K: Hash + Ord,
+    V: PartialEq,
+    S: BuildHasher,
+    S2: BuildHasher,
+{
+    fn eq(&self, other: &HashMapRef<'_, K, V, S>) -> bool {
+        other.map.std_eq(self, &other.guard)
+    }
+}
+
 impl<K, V, S> Eq for HashMapRef<'_, K, V, S>
 where
     K: Hash + Ord,
@@ -300,7 +1003,53 @@ where
 {
     type Output = V;
 
+    /// Returns a reference to the value corresponding to `key`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key is not present in the map. The panic is reported at the location of the
+    /// indexing expression rather than inside this crate. The message cannot include the key
+    /// itself, since `Index` does not require `Debug` keys; use [`HashMapRef::get`] with a custom
+    /// panic if you need that.
+    #[track_caller]
     fn index(&self, key: &Q) -> &V {
         self.get(key).expect("no entry found for key")
     }
 }
+
+/// An owned handle to a [`HashMap`] behind an [`Arc`], constructed with [`HashMap::pin_arc`].
+///
+/// The current thread will be pinned for as long as the handle is held.
+/// Keep in mind that this prevents the collection of garbage generated by the map.
+pub struct OwnedHashMapRef<K, V, S = crate::DefaultHashBuilder> {
+    // NOTE: `guard` must be declared (and so dropped) before `map`, see `HashMap::pin_arc`.
+    guard: Guard<'static>,
+    map: Arc<HashMap<K, V, S>>,
+}
+
+impl<K, V, S> OwnedHashMapRef<K, V, S> {
+    /// Returns a [`HashMapRef`] to the map that uses this handle's guard.
+    pub fn map_ref(&self) -> HashMapRef<'_, K, V, S> {
+        self.map.with_guard(&self.guard)
+    }
+
+    /// Returns the guard this handle holds, for use with the methods on [`HashMap`].
+    pub fn guard(&self) -> &Guard<'_> {
+        &self.guard
+    }
+
+    /// Returns the map this handle refers to.
+    pub fn map(&self) -> &Arc<HashMap<K, V, S>> {
+        &self.map
+    }
+}
+
+impl<K, V, S> Debug for OwnedHashMapRef<K, V, S>
+where
+    K: Debug,
+    V: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.map_ref().fmt(f)
+    }
+}
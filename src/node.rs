@@ -1,9 +1,11 @@
 use crate::raw::Table;
 use crate::reclaim::{Atomic, Collector, Guard, RetireShared, Shared};
-use core::sync::atomic::{AtomicBool, AtomicI64, Ordering};
-use parking_lot::Mutex;
+use core::sync::atomic::{AtomicBool, AtomicI64, AtomicUsize, Ordering};
+use parking_lot::{Mutex, MutexGuard};
 use seize::Linked;
 use std::borrow::Borrow;
+#[cfg(feature = "versioned")]
+use std::sync::atomic::AtomicU64;
 use std::thread::{current, park, Thread};
 
 /// Entry in a bin.
@@ -15,6 +17,12 @@ pub(crate) enum BinEntry<K, V> {
     Tree(TreeBin<K, V>),
     TreeNode(TreeNode<K, V>),
     Moved,
+    /// A place-holder for an empty bin while a value is being computed for it.
+    ///
+    /// The computing thread takes the lock _before_ placing the reservation in the bin, and
+    /// replaces the reservation before releasing the lock. Any other writer that locks the
+    /// reservation must therefore find that the bin has since changed.
+    Reservation(BinLock),
 }
 
 unsafe impl<K, V> Send for BinEntry<K, V>
@@ -60,6 +68,80 @@ impl<K, V> BinEntry<K, V> {
     }
 }
 
+/// The lock that writers take on the head of a bin.
+///
+/// Bin locks are not reentrant, so a thread that tries to lock a bin it is already holding the
+/// lock for (for example, by modifying the map from within a `compute` closure) would deadlock.
+/// `BinLock` remembers which thread holds it, and turns that deadlock into a panic.
+#[derive(Debug)]
+pub(crate) struct BinLock {
+    mutex: Mutex<()>,
+    /// Identifies the thread currently holding the lock, or 0 if no thread holds it.
+    owner: AtomicUsize,
+}
+
+/// Releases the [`BinLock`] it was returned from when dropped.
+pub(crate) struct BinLockGuard<'a> {
+    lock: &'a BinLock,
+    _guard: MutexGuard<'a, ()>,
+}
+
+/// Returns a number that is unique to the current thread among all running threads.
+fn thread_id() -> usize {
+    thread_local!(static ID: u8 = const { 0 });
+    ID.with(|id| id as *const u8 as usize)
+}
+
+impl BinLock {
+    pub(crate) fn new() -> Self {
+        BinLock {
+            mutex: Mutex::new(()),
+            owner: AtomicUsize::new(0),
+        }
+    }
+
+    /// Acquires the lock, blocking the current thread until it is able to do so.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the current thread already holds the lock.
+    pub(crate) fn lock(&self) -> BinLockGuard<'_> {
+        let me = thread_id();
+        let guard = match self.mutex.try_lock() {
+            Some(guard) => guard,
+            None => {
+                // only this thread ever stores its own id, so if we read it here, we are the
+                // ones holding the lock, and waiting for it would never finish.
+                assert_ne!(
+                    self.owner.load(Ordering::Relaxed),
+                    me,
+                    "cannot access the map for the same bin from within a compute closure"
+                );
+                self.mutex.lock()
+            }
+        };
+        self.owner.store(me, Ordering::Relaxed);
+        BinLockGuard {
+            lock: self,
+            _guard: guard,
+        }
+    }
+}
+
+impl Drop for BinLockGuard<'_> {
+    fn drop(&mut self) {
+        // clear the owner _before_ the mutex is released when `_guard` is dropped, so that the
+        // next thread to take the lock never observes a stale owner
+        self.lock.owner.store(0, Ordering::Relaxed);
+    }
+}
+
+/// The version of a node whose value is being set, see [`HashMap::iter_since`].
+///
+/// [`HashMap::iter_since`]: crate::HashMap::iter_since
+#[cfg(feature = "versioned")]
+pub(crate) const PENDING_VERSION: u64 = u64::MAX;
+
 /// Key-value entry.
 #[derive(Debug)]
 pub(crate) struct Node<K, V> {
@@ -67,7 +149,10 @@ pub(crate) struct Node<K, V> {
     pub(crate) key: K,
     pub(crate) value: Atomic<V>,
     pub(crate) next: Atomic<BinEntry<K, V>>,
-    pub(crate) lock: Mutex<()>,
+    pub(crate) lock: BinLock,
+    /// The map version at which `value` was last set, or `PENDING_VERSION` while it is being set.
+    #[cfg(feature = "versioned")]
+    pub(crate) version: AtomicU64,
 }
 
 impl<K, V> Node<K, V> {
@@ -86,7 +171,9 @@ impl<K, V> Node<K, V> {
             key,
             value: value.into(),
             next,
-            lock: Mutex::new(()),
+            lock: BinLock::new(),
+            #[cfg(feature = "versioned")]
+            version: AtomicU64::new(PENDING_VERSION),
         }
     }
 }
@@ -230,7 +317,7 @@ pub(crate) struct TreeBin<K, V> {
     pub(crate) root: Atomic<BinEntry<K, V>>,
     pub(crate) first: Atomic<BinEntry<K, V>>,
     pub(crate) waiter: Atomic<Thread>,
-    pub(crate) lock: parking_lot::Mutex<()>,
+    pub(crate) lock: BinLock,
     pub(crate) lock_state: AtomicI64,
 }
 
@@ -321,7 +408,7 @@ where
             root: Atomic::from(root),
             first: Atomic::from(bin),
             waiter: Atomic::null(),
-            lock: parking_lot::Mutex::new(()),
+            lock: BinLock::new(),
             lock_state: AtomicI64::new(0),
         }
     }
@@ -1463,13 +1550,12 @@ mod tests {
     use std::sync::atomic::Ordering;
 
     fn new_node(hash: u64, key: usize, value: usize, collector: &Collector) -> Node<usize, usize> {
-        Node {
+        Node::with_next(
             hash,
             key,
-            value: Atomic::from(Shared::boxed(value, collector)),
-            next: Atomic::null(),
-            lock: Mutex::new(()),
-        }
+            Atomic::from(Shared::boxed(value, collector)),
+            Atomic::null(),
+        )
     }
 
     #[test]
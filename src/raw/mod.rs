@@ -164,6 +164,7 @@ impl<K, V> Table<K, V> {
                             table = unsafe { table.next_table(guard).deref() };
                             continue;
                         }
+                        BinEntry::Reservation(_) => break Shared::null(),
                         BinEntry::TreeNode(_) => unreachable!("`find` was called on a Moved entry pointing to a TreeNode, which cannot be the first entry in a bin"),
                     }
                 }
@@ -178,10 +179,34 @@ impl<K, V> Table<K, V> {
                 // only needs a shared reference to the bin
                 TreeBin::find(Shared::from(bin as *const _ as *mut _), hash, key, guard)
             }
+            BinEntry::Reservation(_) => {
+                // the bin is empty while a value is being computed for it
+                Shared::null()
+            }
         }
     }
 
     pub(crate) fn drop_bins(&mut self) {
+        self.drain_bins(|_, _| {});
+    }
+
+    /// Removes all entries from the table and returns them by value.
+    ///
+    /// Like `drop_bins`, this leaves the table with no bins, and must only be used once no-one
+    /// else can access the table any more.
+    pub(crate) fn take_entries(&mut self) -> Vec<(K, V)> {
+        let mut entries = Vec::new();
+        self.drain_bins(|key, value| entries.push((key, value)));
+        entries
+    }
+
+    /// Removes all entries from the table, and passes each of them to `f` by value.
+    ///
+    /// This leaves the table with no bins. Entries that `f` does not keep are dropped.
+    fn drain_bins<F>(&mut self, mut f: F)
+    where
+        F: FnMut(K, V),
+    {
         // safety: we have &mut self _and_ all references we have returned are bound to the
         // lifetime of their borrow of self, so there cannot be any outstanding references to
         // anything in the map.
@@ -205,7 +230,7 @@ impl<K, V> Table<K, V> {
                     let mut p = unsafe { bin.into_box() };
                     loop {
                         // safety below:
-                        // we're dropping the entire map, so no-one else is accessing it.
+                        // we own the entire map, so no-one else is accessing it.
                         // we replaced the bin with a NULL, so there's no future way to access it
                         // either; we own all the nodes in the list.
 
@@ -215,8 +240,9 @@ impl<K, V> Table<K, V> {
                             unreachable!();
                         };
 
-                        // first, drop the value in this node
-                        let _ = unsafe { node.value.into_box() };
+                        // first, take the key and value out of this node
+                        let value = unsafe { node.value.into_box() };
+                        f(node.key, Linked::into_inner(*value));
 
                         // then we move to the next node
                         if node.next.load(Ordering::SeqCst, &guard).is_null() {
@@ -233,12 +259,34 @@ impl<K, V> Table<K, V> {
                     } else {
                         unreachable!();
                     };
-                    // TreeBin::drop will take care of freeing the contained TreeNodes and their values
+
+                    // the tree pointers point to the same nodes as the `next` pointers of the
+                    // `first` element, so it is sufficient to follow those. we swap out the first
+                    // pointer so that the nodes do not get dropped again when `bin` is dropped.
+                    let mut p = bin.first.swap(Shared::null(), Ordering::SeqCst, &guard);
+                    while !p.is_null() {
+                        // safety: same as for BinEntry::Node
+                        let tree_node = if let BinEntry::TreeNode(tree_node) =
+                            Linked::into_inner(*unsafe { p.into_box() })
+                        {
+                            tree_node
+                        } else {
+                            unreachable!("Trees can only ever contain TreeNodes");
+                        };
+
+                        let node = tree_node.node;
+                        let value = unsafe { node.value.into_box() };
+                        f(node.key, Linked::into_inner(*value));
+                        p = node.next.load(Ordering::SeqCst, &guard);
+                    }
                     drop(bin);
                 }
                 BinEntry::TreeNode(_) => unreachable!(
                     "The head of a bin cannot be a TreeNode directly without BinEntry::Tree"
                 ),
+                BinEntry::Reservation(_) => {
+                    unreachable!("A bin is only reserved while a computation on the map is running")
+                }
             }
         }
     }
@@ -328,4 +376,24 @@ impl<K, V> Table<K, V> {
     pub(crate) fn next_table<'g>(&'g self, guard: &'g Guard<'_>) -> Shared<'g, Table<K, V>> {
         self.next_table.load(Ordering::SeqCst, guard)
     }
+
+    /// Waits for the thread that placed the reservation `bin` into bin `i` to replace it.
+    ///
+    /// A thread that computes the value for a (previously empty) bin holds the reservation's lock
+    /// until it has replaced the reservation, so once this returns, the bin has changed and the
+    /// caller can read it again.
+    pub(crate) fn wait_for_reservation<'g>(
+        &'g self,
+        i: usize,
+        bin: Shared<'g, BinEntry<K, V>>,
+        guard: &'g Guard<'_>,
+    ) {
+        // safety: `bin` was read from this table under `guard`, so it is not dropped until after
+        // the guard is.
+        match **unsafe { bin.deref() } {
+            BinEntry::Reservation(ref lock) => drop(lock.lock()),
+            _ => unreachable!("only a reservation can be waited for"),
+        }
+        debug_assert!(self.bin(i, guard) != bin);
+    }
 }
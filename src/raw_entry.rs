@@ -0,0 +1,278 @@
+use crate::reclaim::Guard;
+use crate::HashMap;
+use std::borrow::Borrow;
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{BuildHasher, Hash};
+
+/// A builder for looking up an entry in a [`HashMap`] by a precomputed hash.
+///
+/// This `struct` is constructed from the [`raw_entry`](HashMap::raw_entry) method on
+/// [`HashMap`].
+pub struct RawEntryBuilder<'g, K, V, S = crate::DefaultHashBuilder> {
+    map: &'g HashMap<K, V, S>,
+    hash: u64,
+    guard: &'g Guard<'g>,
+}
+
+/// A builder for looking up and inserting an entry in a [`HashMap`] by a precomputed hash.
+///
+/// This `struct` is constructed from the [`raw_entry_mut`](HashMap::raw_entry_mut) method on
+/// [`HashMap`].
+pub struct RawEntryBuilderMut<'g, K, V, S = crate::DefaultHashBuilder> {
+    map: &'g HashMap<K, V, S>,
+    hash: u64,
+    guard: &'g Guard<'g>,
+}
+
+/// A view into a single entry in a map, which may either be vacant or occupied, that was looked
+/// up by a precomputed hash.
+///
+/// This `enum` is constructed from a [`RawEntryBuilderMut`]. As with [`Entry`](crate::Entry),
+/// the variant only reflects the state of the map at the time the entry was looked up.
+pub enum RawEntryMut<'g, K, V, S = crate::DefaultHashBuilder> {
+    /// An occupied entry.
+    Occupied(RawOccupiedEntryMut<'g, K, V>),
+    /// A vacant entry.
+    Vacant(RawVacantEntryMut<'g, K, V, S>),
+}
+
+/// A view into an occupied entry in a [`HashMap`] that was looked up by a precomputed hash.
+/// It is part of the [`RawEntryMut`] enum.
+pub struct RawOccupiedEntryMut<'g, K, V> {
+    key: &'g K,
+    value: &'g V,
+}
+
+/// A view into a vacant entry in a [`HashMap`] that was looked up by a precomputed hash.
+/// It is part of the [`RawEntryMut`] enum.
+pub struct RawVacantEntryMut<'g, K, V, S = crate::DefaultHashBuilder> {
+    map: &'g HashMap<K, V, S>,
+    hash: u64,
+    guard: &'g Guard<'g>,
+}
+
+impl<'g, K, V, S> RawEntryBuilder<'g, K, V, S> {
+    pub(crate) fn new(map: &'g HashMap<K, V, S>, hash: u64, guard: &'g Guard<'g>) -> Self {
+        Self { map, hash, guard }
+    }
+}
+
+impl<'g, K, V, S> RawEntryBuilder<'g, K, V, S>
+where
+    K: Hash + Ord,
+    S: BuildHasher,
+{
+    /// Returns the key-value pair with the builder's hash for which `is_match` returns `true`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    /// use std::hash::{BuildHasher, Hash, Hasher};
+    ///
+    /// let map = HashMap::new();
+    /// let guard = map.guard();
+    /// map.insert(String::from("poneyland"), 3, &guard);
+    ///
+    /// let mut hasher = map.hasher().build_hasher();
+    /// "poneyland".hash(&mut hasher);
+    /// let hash = hasher.finish();
+    ///
+    /// let entry = map.raw_entry(hash, &guard).from_match(|k| k == "poneyland");
+    /// assert_eq!(entry, Some((&String::from("poneyland"), &3)));
+    /// ```
+    pub fn from_match<F>(self, is_match: F) -> Option<(&'g K, &'g V)>
+    where
+        F: FnMut(&K) -> bool,
+    {
+        self.map
+            .get_key_value_hashed(self.hash, is_match, self.guard)
+    }
+
+    /// Returns the key-value pair with the builder's hash whose key is equal to `key`.
+    pub fn from_key<Q>(self, key: &Q) -> Option<(&'g K, &'g V)>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq,
+    {
+        self.from_match(|k| k.borrow() == key)
+    }
+}
+
+impl<'g, K, V, S> RawEntryBuilderMut<'g, K, V, S> {
+    pub(crate) fn new(map: &'g HashMap<K, V, S>, hash: u64, guard: &'g Guard<'g>) -> Self {
+        Self { map, hash, guard }
+    }
+}
+
+impl<'g, K, V, S> RawEntryBuilderMut<'g, K, V, S>
+where
+    K: Hash + Ord,
+    S: BuildHasher,
+{
+    /// Looks up the entry with the builder's hash for which `is_match` returns `true`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    /// use std::hash::{BuildHasher, Hash, Hasher};
+    ///
+    /// let map: HashMap<String, u32> = HashMap::new();
+    /// let guard = map.guard();
+    ///
+    /// let mut hasher = map.hasher().build_hasher();
+    /// "poneyland".hash(&mut hasher);
+    /// let hash = hasher.finish();
+    ///
+    /// let value = map
+    ///     .raw_entry_mut(hash, &guard)
+    ///     .from_match(|k| k == "poneyland")
+    ///     .or_insert(String::from("poneyland"), 3);
+    /// assert_eq!(value, &3);
+    /// assert_eq!(map.get("poneyland", &guard), Some(&3));
+    /// ```
+    pub fn from_match<F>(self, is_match: F) -> RawEntryMut<'g, K, V, S>
+    where
+        F: FnMut(&K) -> bool,
+    {
+        match self
+            .map
+            .get_key_value_hashed(self.hash, is_match, self.guard)
+        {
+            Some((key, value)) => RawEntryMut::Occupied(RawOccupiedEntryMut { key, value }),
+            None => RawEntryMut::Vacant(RawVacantEntryMut {
+                map: self.map,
+                hash: self.hash,
+                guard: self.guard,
+            }),
+        }
+    }
+
+    /// Looks up the entry with the builder's hash whose key is equal to `key`.
+    pub fn from_key<Q>(self, key: &Q) -> RawEntryMut<'g, K, V, S>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq,
+    {
+        self.from_match(|k| k.borrow() == key)
+    }
+}
+
+impl<'g, K, V, S> RawEntryMut<'g, K, V, S>
+where
+    K: Sync + Send + Clone + Hash + Ord,
+    V: Sync + Send,
+    S: BuildHasher,
+{
+    /// Ensures a value is in the entry by inserting `key` and `value` if empty, and returns a
+    /// reference to the value in the entry.
+    ///
+    /// `key` must have the hash that the entry was looked up with.
+    pub fn or_insert(self, key: K, value: V) -> &'g V {
+        match self {
+            RawEntryMut::Occupied(entry) => entry.get(),
+            RawEntryMut::Vacant(entry) => entry.insert(key, value),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting the key-value pair returned by `default` if
+    /// empty, and returns a reference to the value in the entry.
+    ///
+    /// The key returned by `default` must have the hash that the entry was looked up with.
+    pub fn or_insert_with<F>(self, default: F) -> &'g V
+    where
+        F: FnOnce() -> (K, V),
+    {
+        match self {
+            RawEntryMut::Occupied(entry) => entry.get(),
+            RawEntryMut::Vacant(entry) => {
+                let (key, value) = default();
+                entry.insert(key, value)
+            }
+        }
+    }
+}
+
+impl<'g, K, V> RawOccupiedEntryMut<'g, K, V> {
+    /// Gets a reference to the key in the entry.
+    pub fn key(&self) -> &'g K {
+        self.key
+    }
+
+    /// Gets a reference to the value in the entry.
+    ///
+    /// This is the value that was observed when the entry was looked up. It may since have been
+    /// replaced or removed by another thread.
+    pub fn get(&self) -> &'g V {
+        self.value
+    }
+}
+
+impl<'g, K, V, S> RawVacantEntryMut<'g, K, V, S>
+where
+    K: Sync + Send + Clone + Hash + Ord,
+    V: Sync + Send,
+    S: BuildHasher,
+{
+    /// Sets the key and value of the entry, and returns a reference to the value.
+    ///
+    /// `key` must have the hash that the entry was looked up with, or later lookups through the
+    /// map's own hasher will not find it.
+    ///
+    /// If another thread inserted a value for an equal key after the entry was looked up, the map
+    /// is left unchanged and a reference to that value is returned instead.
+    pub fn insert(self, key: K, value: V) -> &'g V {
+        self.map.insert_hashed(self.hash, key, value, self.guard)
+    }
+}
+
+impl<K, V, S> Debug for RawEntryBuilder<'_, K, V, S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RawEntryBuilder")
+            .field("hash", &self.hash)
+            .finish()
+    }
+}
+
+impl<K, V, S> Debug for RawEntryBuilderMut<'_, K, V, S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RawEntryBuilderMut")
+            .field("hash", &self.hash)
+            .finish()
+    }
+}
+
+impl<K, V, S> Debug for RawEntryMut<'_, K, V, S>
+where
+    K: Debug,
+    V: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match *self {
+            RawEntryMut::Occupied(ref o) => f.debug_tuple("RawEntryMut").field(o).finish(),
+            RawEntryMut::Vacant(ref v) => f.debug_tuple("RawEntryMut").field(v).finish(),
+        }
+    }
+}
+
+impl<K, V> Debug for RawOccupiedEntryMut<'_, K, V>
+where
+    K: Debug,
+    V: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RawOccupiedEntryMut")
+            .field("key", self.key)
+            .field("value", self.value)
+            .finish()
+    }
+}
+
+impl<K, V, S> Debug for RawVacantEntryMut<'_, K, V, S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RawVacantEntryMut")
+            .field("hash", &self.hash)
+            .finish()
+    }
+}
@@ -1,6 +1,261 @@
+use crate::iter::NodeIter;
+use crate::raw::Table;
+use crate::reclaim::{Guard, Linked};
 use crate::{HashMap, HashMapRef, HashSet, HashSetRef};
+use rayon::iter::plumbing::UnindexedConsumer;
 use rayon::iter::{FromParallelIterator, IntoParallelIterator, ParallelExtend, ParallelIterator};
 use std::hash::{BuildHasher, Hash};
+use std::sync::atomic::Ordering;
+
+/// A parallel iterator over a map's entries.
+///
+/// See [`HashMap::par_iter`] for details.
+#[derive(Debug)]
+pub struct ParIter<'g, K, V> {
+    entries: Vec<(&'g K, &'g V)>,
+}
+
+/// A parallel iterator over a map's keys.
+///
+/// See [`HashMap::par_keys`] for details.
+#[derive(Debug)]
+pub struct ParKeys<'g, K> {
+    keys: Vec<&'g K>,
+}
+
+/// A parallel iterator over a map's values.
+///
+/// See [`HashMap::par_values`] for details.
+#[derive(Debug)]
+pub struct ParValues<'g, V> {
+    values: Vec<&'g V>,
+}
+
+impl<'g, K, V> ParallelIterator for ParIter<'g, K, V>
+where
+    K: Sync,
+    V: Sync,
+{
+    type Item = (&'g K, &'g V);
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        self.entries.into_par_iter().drive_unindexed(consumer)
+    }
+
+    fn opt_len(&self) -> Option<usize> {
+        Some(self.entries.len())
+    }
+}
+
+impl<'g, K> ParallelIterator for ParKeys<'g, K>
+where
+    K: Sync,
+{
+    type Item = &'g K;
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        self.keys.into_par_iter().drive_unindexed(consumer)
+    }
+
+    fn opt_len(&self) -> Option<usize> {
+        Some(self.keys.len())
+    }
+}
+
+impl<'g, V> ParallelIterator for ParValues<'g, V>
+where
+    V: Sync,
+{
+    type Item = &'g V;
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        self.values.into_par_iter().drive_unindexed(consumer)
+    }
+
+    fn opt_len(&self) -> Option<usize> {
+        Some(self.values.len())
+    }
+}
+
+/// A table whose bins are walked by several of rayon's workers at once.
+struct BinsOf<'a, K, V>(&'a Linked<Table<K, V>>);
+
+// safety: `Linked` is not `Sync` because of the state seize keeps for retiring it, which the
+// workers never touch. they only read the table's bins, and the keys and values in them.
+unsafe impl<K: Sync, V: Sync> Sync for BinsOf<'_, K, V> {}
+
+// NOTE: `par_iter`, `par_keys`, and `par_values` gather their items by walking the table on the
+// calling thread, rather than by handing ranges of bins to rayon's workers. a `Guard` only
+// protects the loads of the thread that entered it, and cannot be shared with the workers. a
+// worker has to enter a guard of its own to read the bins, and that guard is dropped when the
+// worker is done, while the references it found are handed out for all of `'g`. the sequential
+// walk is the same one `Iter` does, so bins that are being moved by a resize are followed to the
+// next table and every entry is visited exactly once. `par_fold` can split the bins between
+// the workers, since the references it passes to `fold` cannot escape it.
+impl<K, V, S> HashMap<K, V, S>
+where
+    K: Sync,
+    V: Sync,
+{
+    /// A parallel iterator visiting all key-value pairs in arbitrary order.
+    ///
+    /// The iterator element type is `(&'g K, &'g V)`. The map's entries are first collected into
+    /// a `Vec` on the current thread, with the same consistency guarantees as
+    /// [`iter`](HashMap::iter), and are then processed in parallel. Only the work done with the
+    /// entries runs in parallel, and the `Vec` holds a reference pair for every entry in the map.
+    /// [`par_fold`](HashMap::par_fold) walks the map itself in parallel.
+    ///
+    /// This method requires the `rayon` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    /// use rayon::iter::ParallelIterator;
+    ///
+    /// let map: HashMap<_, _> = (0..100).map(|i| (i, i * 2)).collect();
+    /// let guard = map.guard();
+    ///
+    /// let sum: i32 = map.par_iter(&guard).map(|(_, v)| v).sum();
+    /// assert_eq!(sum, 9900);
+    /// ```
+    pub fn par_iter<'g>(&'g self, guard: &'g Guard<'_>) -> ParIter<'g, K, V> {
+        ParIter {
+            entries: self.iter(guard).collect(),
+        }
+    }
+
+    /// A parallel iterator visiting all keys in arbitrary order.
+    ///
+    /// The iterator element type is `&'g K`. See [`par_iter`](HashMap::par_iter) for details.
+    ///
+    /// This method requires the `rayon` feature.
+    pub fn par_keys<'g>(&'g self, guard: &'g Guard<'_>) -> ParKeys<'g, K> {
+        ParKeys {
+            keys: self.keys(guard).collect(),
+        }
+    }
+
+    /// A parallel iterator visiting all values in arbitrary order.
+    ///
+    /// The iterator element type is `&'g V`. See [`par_iter`](HashMap::par_iter) for details.
+    ///
+    /// This method requires the `rayon` feature.
+    pub fn par_values<'g>(&'g self, guard: &'g Guard<'_>) -> ParValues<'g, V> {
+        ParValues {
+            values: self.values(guard).collect(),
+        }
+    }
+
+    /// Folds every key-value pair in the map in parallel, and combines the partial results.
+    ///
+    /// The table's bins are split into ranges that rayon's workers walk on their own, each under
+    /// a guard of its own, and fold into an accumulator that starts out as `identity()`. The
+    /// accumulators are then merged with `combine`. Since the order in which entries are visited
+    /// and accumulators are combined is unspecified, `combine` should be associative, and
+    /// `identity()` should be its identity. Unlike [`par_iter`](HashMap::par_iter), this does not
+    /// gather the entries on the current thread first.
+    ///
+    /// Every entry that is in the map for the whole call is visited exactly once, even if the map
+    /// is resized concurrently.
+    ///
+    /// This method requires the `rayon` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashMap;
+    ///
+    /// let map: HashMap<_, _> = (0..100).map(|i| (i, i * 2)).collect();
+    /// let guard = map.guard();
+    ///
+    /// let sum = map.par_fold(|| 0, |sum, _, v| sum + v, |a, b| a + b, &guard);
+    /// assert_eq!(sum, 9900);
+    /// ```
+    pub fn par_fold<B, ID, F, C>(&self, identity: ID, fold: F, combine: C, guard: &Guard<'_>) -> B
+    where
+        B: Send,
+        ID: Fn() -> B + Sync + Send,
+        F: Fn(B, &K, &V) -> B + Sync + Send,
+        C: Fn(B, B) -> B + Sync + Send,
+    {
+        self.check_guard(guard);
+        let table = self.table.load(Ordering::SeqCst, guard);
+        if table.is_null() {
+            return identity();
+        }
+        // safety: the table was read under the caller's guard, which is held for the whole call,
+        // so the table is not dropped until after the workers are done with it. the bins and
+        // nodes the workers read are protected by the guards they enter themselves.
+        let table = BinsOf(unsafe { table.deref() });
+        let collector = &*self.collector;
+        rayon::iter::split((0, table.0.len()), |(lo, hi)| {
+            if hi - lo < 2 {
+                return ((lo, hi), None);
+            }
+            let mid = lo + (hi - lo) / 2;
+            ((lo, mid), Some((mid, hi)))
+        })
+        .map(|(lo, hi)| {
+            let guard = collector.enter();
+            NodeIter::range(table.0, lo, hi, &guard).fold(identity(), |acc, node| {
+                let value = node.value.load(Ordering::SeqCst, &guard);
+                // safety: flurry does not drop or move until after guard drop
+                fold(acc, &node.key, unsafe { value.deref() })
+            })
+        })
+        .reduce(&identity, combine)
+    }
+}
+
+impl<K, V, S> HashMapRef<'_, K, V, S>
+where
+    K: Sync,
+    V: Sync,
+{
+    /// A parallel iterator visiting all key-value pairs in arbitrary order.
+    ///
+    /// See also [`HashMap::par_iter`].
+    pub fn par_iter(&self) -> ParIter<'_, K, V> {
+        self.map.par_iter(&self.guard)
+    }
+
+    /// A parallel iterator visiting all keys in arbitrary order.
+    ///
+    /// See also [`HashMap::par_keys`].
+    pub fn par_keys(&self) -> ParKeys<'_, K> {
+        self.map.par_keys(&self.guard)
+    }
+
+    /// A parallel iterator visiting all values in arbitrary order.
+    ///
+    /// See also [`HashMap::par_values`].
+    pub fn par_values(&self) -> ParValues<'_, V> {
+        self.map.par_values(&self.guard)
+    }
+
+    /// Folds every key-value pair in the map in parallel, and combines the partial results.
+    ///
+    /// See also [`HashMap::par_fold`].
+    pub fn par_fold<B, ID, F, C>(&self, identity: ID, fold: F, combine: C) -> B
+    where
+        B: Send,
+        ID: Fn() -> B + Sync + Send,
+        F: Fn(B, &K, &V) -> B + Sync + Send,
+        C: Fn(B, B) -> B + Sync + Send,
+    {
+        self.map.par_fold(identity, fold, combine, &self.guard)
+    }
+}
 
 impl<K, V, S> FromParallelIterator<(K, V)> for HashMap<K, V, S>
 where
@@ -125,6 +380,121 @@ mod test {
     use crate::{HashMap, HashSet};
     use rayon::iter::{FromParallelIterator, IntoParallelIterator, ParallelExtend};
 
+    #[test]
+    fn hm_par_iter() {
+        use rayon::iter::ParallelIterator;
+
+        let map: HashMap<u64, u64> = (0..1000).map(|i| (i, i * 2)).collect();
+        let guard = map.guard();
+
+        let mut entries: Vec<_> = map.par_iter(&guard).map(|(&k, &v)| (k, v)).collect();
+        entries.sort_unstable();
+        assert_eq!(entries, (0..1000).map(|i| (i, i * 2)).collect::<Vec<_>>());
+
+        assert_eq!(map.par_keys(&guard).sum::<u64>(), 499_500);
+        assert_eq!(map.pin().par_values().sum::<u64>(), 999_000);
+        assert_eq!(map.pin().par_iter().count(), 1000);
+    }
+
+    #[test]
+    fn hm_par_fold() {
+        let map: HashMap<u64, u64> = (0..1000).map(|i| (i, i * 2)).collect();
+        let guard = map.guard();
+
+        let (keys, values) = map.par_fold(
+            || (0, 0),
+            |(ks, vs), k, v| (ks + k, vs + v),
+            |(ks1, vs1), (ks2, vs2)| (ks1 + ks2, vs1 + vs2),
+            &guard,
+        );
+        assert_eq!((keys, values), (499_500, 999_000));
+
+        let empty = HashMap::<u64, u64>::new();
+        assert_eq!(empty.pin().par_fold(|| 1, |_, _, _| 0, |a, b| a * b), 1);
+    }
+
+    #[test]
+    fn hm_par_iter_during_resize() {
+        use rayon::iter::ParallelIterator;
+        use std::sync::Arc;
+
+        let map = Arc::new(HashMap::<u64, u64>::new());
+        {
+            let guard = map.guard();
+            for i in 0..1000 {
+                map.insert(i, i, &guard);
+            }
+        }
+
+        // insert more keys concurrently, which forces the table to be resized while we iterate
+        let writer = {
+            let map = map.clone();
+            std::thread::spawn(move || {
+                let guard = map.guard();
+                for i in 1000..20000 {
+                    map.insert(i, i, &guard);
+                }
+            })
+        };
+        for _ in 0..10 {
+            let guard = map.guard();
+            let mut keys: Vec<_> = map.par_keys(&guard).copied().collect();
+            keys.sort_unstable();
+            let len = keys.len();
+            keys.dedup();
+            // no key is visited twice, and none of the initial keys is skipped
+            assert_eq!(keys.len(), len);
+            assert!((0..1000).all(|i| keys.binary_search(&i).is_ok()));
+        }
+        writer.join().unwrap();
+    }
+
+    #[test]
+    fn hm_par_fold_during_resize() {
+        use std::sync::Arc;
+
+        let map = Arc::new(HashMap::<u64, u64>::new());
+        {
+            let guard = map.guard();
+            for i in 0..1000 {
+                map.insert(i, i, &guard);
+            }
+        }
+
+        // insert more keys concurrently, which forces the table to be resized while we fold
+        let writer = {
+            let map = map.clone();
+            std::thread::spawn(move || {
+                let guard = map.guard();
+                for i in 1000..20000 {
+                    map.insert(i, i, &guard);
+                }
+            })
+        };
+        for _ in 0..10 {
+            let guard = map.guard();
+            let mut keys = map.par_fold(
+                Vec::new,
+                |mut keys, &k, _| {
+                    keys.push(k);
+                    keys
+                },
+                |mut a, b| {
+                    a.extend(b);
+                    a
+                },
+                &guard,
+            );
+            keys.sort_unstable();
+            let len = keys.len();
+            keys.dedup();
+            // no key is visited twice, and none of the initial keys is skipped
+            assert_eq!(keys.len(), len);
+            assert!((0..1000).all(|i| keys.binary_search(&i).is_ok()));
+        }
+        writer.join().unwrap();
+    }
+
     #[test]
     fn hm_from_empty_parallel_iter() {
         let to_create_from: Vec<(i32, i32)> = Vec::new();
@@ -42,7 +42,7 @@ where
 impl<'de, K, V, S> Deserialize<'de> for HashMap<K, V, S>
 where
     K: Deserialize<'de> + Send + Sync + Hash + Clone + Ord,
-    V: Deserialize<'de> + Send + Sync + Ord,
+    V: Deserialize<'de> + Send + Sync,
     S: Default + BuildHasher,
 {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
@@ -66,7 +66,7 @@ impl<K, V, S> HashMapVisitor<K, V, S> {
 impl<'de, K, V, S> Visitor<'de> for HashMapVisitor<K, V, S>
 where
     K: Deserialize<'de> + Send + Sync + Hash + Clone + Ord,
-    V: Deserialize<'de> + Send + Sync + Ord,
+    V: Deserialize<'de> + Send + Sync,
     S: Default + BuildHasher,
 {
     type Value = HashMap<K, V, S>;
@@ -164,7 +164,10 @@ where
     where
         A: SeqAccess<'de>,
     {
-        let set = HashSet::default();
+        let set = match access.size_hint() {
+            Some(n) => HashSet::with_capacity_and_hasher(n, S::default()),
+            None => HashSet::with_hasher(S::default()),
+        };
 
         {
             let guard = set.guard();
@@ -201,6 +204,27 @@ mod test {
         assert_eq!(map, deserialized);
     }
 
+    #[test]
+    fn test_map_ref() {
+        let map: HashMap<u8, u8> = HashMap::new();
+        let mref = map.pin();
+        let _ = mref.insert(0, 4);
+
+        let serialized = serde_json::to_string(&mref).expect("Couldn't serialize map");
+        assert_eq!(serialized, r#"{"0":4}"#);
+        assert_eq!(serialized, serde_json::to_string(&map).unwrap());
+    }
+
+    #[test]
+    fn test_map_unordered_values() {
+        let map: HashMap<u8, Vec<f64>> =
+            serde_json::from_str(r#"{"0":[0.5],"1":[]}"#).expect("Couldn't deserialize map");
+
+        let guard = map.guard();
+        assert_eq!(map.get(&0, &guard), Some(&vec![0.5]));
+        assert_eq!(map.get(&1, &guard), Some(&vec![]));
+    }
+
     #[test]
     fn test_set() {
         let set: HashSet<u8> = HashSet::with_capacity(5);
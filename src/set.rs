@@ -2,9 +2,9 @@
 //!
 //! See `HashSet` for details.
 
-use crate::iter::Keys;
+use crate::iter::{Difference, Intersection, IntoKeys, Keys, SetDrain, SymmetricDifference, Union};
 use crate::reclaim::Guard;
-use crate::HashMap;
+use crate::{HashMap, HashMapBuilder, TryReserveError};
 use std::borrow::Borrow;
 use std::fmt::{self, Debug, Formatter};
 use std::hash::{BuildHasher, Hash};
@@ -44,7 +44,103 @@ pub struct HashSet<T, S = crate::DefaultHashBuilder> {
     pub(crate) map: HashMap<T, (), S>,
 }
 
+/// A builder for a [`HashSet`] with a non-default configuration.
+///
+/// This `struct` is created by [`HashSet::builder`]. See [`HashMapBuilder`] for details on the
+/// options. There is no [shrink factor](HashMapBuilder::shrink_factor), since a `HashSet` is
+/// never shrunk.
+///
+/// # Examples
+///
+/// ```
+/// use flurry::HashSet;
+///
+/// let set: HashSet<i32> = HashSet::builder().capacity(100).load_factor(0.5).build();
+/// set.pin().insert(1);
+/// ```
+pub struct HashSetBuilder<T, S = crate::DefaultHashBuilder> {
+    map: HashMapBuilder<T, (), S>,
+}
+
+impl<T, S> Clone for HashSetBuilder<T, S>
+where
+    S: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            map: self.map.clone(),
+        }
+    }
+}
+
+impl<T, S> Debug for HashSetBuilder<T, S>
+where
+    S: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HashSetBuilder")
+            .field("map", &self.map)
+            .finish()
+    }
+}
+
+impl<T, S> HashSetBuilder<T, S> {
+    /// Sets the number of elements the set should be able to hold without resizing.
+    ///
+    /// See also [`HashMapBuilder::capacity`].
+    #[must_use]
+    pub fn capacity(self, capacity: usize) -> Self {
+        Self {
+            map: self.map.capacity(capacity),
+        }
+    }
+
+    /// Sets the fraction of the set's capacity that may be filled before the set is resized.
+    ///
+    /// See also [`HashMapBuilder::load_factor`].
+    #[must_use]
+    pub fn load_factor(self, load_factor: f64) -> Self {
+        Self {
+            map: self.map.load_factor(load_factor),
+        }
+    }
+
+    /// Sets the hasher the set will use to hash values.
+    ///
+    /// See also [`HashMapBuilder::hasher`].
+    pub fn hasher<S2>(self, hash_builder: S2) -> HashSetBuilder<T, S2> {
+        HashSetBuilder {
+            map: self.map.hasher(hash_builder),
+        }
+    }
+
+    /// Creates the configured set.
+    pub fn build(self) -> HashSet<T, S> {
+        HashSet {
+            map: self.map.build(),
+        }
+    }
+}
+
 impl<T> HashSet<T, crate::DefaultHashBuilder> {
+    /// Creates a builder for a set with a non-default capacity, hasher or load factor.
+    ///
+    /// See also [`HashMap::builder`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashSet;
+    ///
+    /// let set: HashSet<i32> = HashSet::builder().capacity(10).build();
+    /// set.pin().insert(1);
+    /// ```
+    pub fn builder() -> HashSetBuilder<T> {
+        HashSetBuilder {
+            map: HashMap::builder(),
+        }
+    }
+
     /// Creates an empty `HashSet`.
     ///
     /// The hash set is initially created with a capacity of 0, so it will not allocate until it
@@ -89,6 +185,27 @@ impl<T, S> Default for HashSet<T, S>
 where
     S: Default,
 {
+    /// Creates an empty set, with the `Default` value for the hasher.
+    ///
+    /// As with `std`, the default type of the hasher only applies when the type is spelled out,
+    /// so a bare `HashSet::default()` needs its type to be known from context.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::{DefaultHashBuilder, HashSet};
+    /// use std::hash::BuildHasher;
+    ///
+    /// let set: HashSet<i32> = HashSet::default();
+    /// assert!(set.pin().is_empty());
+    ///
+    /// // the same works for any hasher in generic code
+    /// fn empty<S: BuildHasher + Default>() -> HashSet<i32, S> {
+    ///     HashSet::default()
+    /// }
+    /// let set: HashSet<i32, DefaultHashBuilder> = empty();
+    /// assert!(set.pin().insert(1));
+    /// ```
     fn default() -> Self {
         Self::with_hasher(S::default())
     }
@@ -155,6 +272,13 @@ impl<T, S> HashSet<T, S> {
         self.map.guard()
     }
 
+    /// Returns a reference to the set's [`BuildHasher`].
+    ///
+    /// See also [`HashMap::hasher`].
+    pub fn hasher(&self) -> &S {
+        self.map.hasher()
+    }
+
     /// Returns the number of elements in the set.
     ///
     /// # Examples
@@ -212,6 +336,25 @@ impl<T, S> HashSet<T, S> {
     pub fn iter<'g>(&'g self, guard: &'g Guard<'_>) -> Keys<'g, T, ()> {
         self.map.keys(guard)
     }
+
+    /// Returns the number of elements for which `f` returns `true`.
+    ///
+    /// See [`HashMap::count_if`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashSet;
+    ///
+    /// let set: HashSet<_> = (0..10).collect();
+    /// assert_eq!(set.count_if(|&x| x % 3 == 0, &set.guard()), 4);
+    /// ```
+    pub fn count_if<F>(&self, mut f: F, guard: &Guard<'_>) -> usize
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.map.count_if(|value, ()| f(value), guard)
+    }
 }
 
 impl<T, S> HashSet<T, S>
@@ -278,7 +421,8 @@ where
 
     /// Returns `true` if `self` has no elements in common with `other`.
     ///
-    /// This is equivalent to checking for an empty intersection.
+    /// This is equivalent to checking for an empty
+    /// [`intersection`](HashSet::intersection), and stops at the first common element.
     ///
     /// # Examples
     ///
@@ -302,17 +446,16 @@ where
         our_guard: &Guard<'_>,
         their_guard: &Guard<'_>,
     ) -> bool {
-        for value in self.iter(our_guard) {
-            if other.contains(value, their_guard) {
-                return false;
-            }
-        }
-
-        true
+        // walks the smaller of the two sets
+        self.intersection(other, our_guard, their_guard)
+            .next()
+            .is_none()
     }
 
     /// Returns `true` if the set is a subset of another, i.e., `other` contains at least all the values in `self`.
     ///
+    /// Each value of `self` is looked up in `other`, stopping at the first one that is missing.
+    ///
     /// # Examples
     ///
     /// ```
@@ -372,12 +515,162 @@ where
         other.is_subset(self, their_guard, our_guard)
     }
 
-    pub(crate) fn guarded_eq(
-        &self,
-        other: &Self,
-        our_guard: &Guard<'_>,
-        their_guard: &Guard<'_>,
-    ) -> bool {
+    /// Visits the values that are in `self` but not in `other`.
+    ///
+    /// The iterator is lazy: each value of `self` is checked against `other` as the iterator
+    /// reaches it. Like all iteration over a flurry set, the result reflects a loosely-consistent
+    /// view of both sets if they are modified concurrently, but every check made through the same
+    /// pair of guards observes the sets as they were at some point during the check.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashSet;
+    ///
+    /// let a: HashSet<_> = [1, 2, 3].iter().cloned().collect();
+    /// let b: HashSet<_> = [4, 2, 3, 4].iter().cloned().collect();
+    /// let (a_guard, b_guard) = (a.guard(), b.guard());
+    ///
+    /// let diff: Vec<_> = a.difference(&b, &a_guard, &b_guard).collect();
+    /// assert_eq!(diff, [&1]);
+    ///
+    /// let diff: Vec<_> = b.difference(&a, &b_guard, &a_guard).collect();
+    /// assert_eq!(diff, [&4]);
+    /// ```
+    pub fn difference<'g>(
+        &'g self,
+        other: &'g HashSet<T, S>,
+        our_guard: &'g Guard<'_>,
+        their_guard: &'g Guard<'_>,
+    ) -> Difference<'g, T, S> {
+        Difference {
+            iter: self.iter(our_guard),
+            other,
+            other_guard: their_guard,
+        }
+    }
+
+    /// Visits the values that are in `self` or in `other`, but not in both.
+    ///
+    /// See [`difference`](HashSet::difference) for the consistency guarantees of the iterator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashSet;
+    ///
+    /// let a: HashSet<_> = [1, 2, 3].iter().cloned().collect();
+    /// let b: HashSet<_> = [4, 2, 3, 4].iter().cloned().collect();
+    /// let (a_guard, b_guard) = (a.guard(), b.guard());
+    ///
+    /// let mut diff: Vec<_> = a.symmetric_difference(&b, &a_guard, &b_guard).collect();
+    /// diff.sort();
+    /// assert_eq!(diff, [&1, &4]);
+    /// ```
+    pub fn symmetric_difference<'g>(
+        &'g self,
+        other: &'g HashSet<T, S>,
+        our_guard: &'g Guard<'_>,
+        their_guard: &'g Guard<'_>,
+    ) -> SymmetricDifference<'g, T, S> {
+        SymmetricDifference {
+            iter: self
+                .difference(other, our_guard, their_guard)
+                .chain(other.difference(self, their_guard, our_guard)),
+        }
+    }
+
+    /// Visits the values that are in both `self` and `other`.
+    ///
+    /// The iterator walks the smaller of the two sets, and checks each of its values against the
+    /// larger one. See [`difference`](HashSet::difference) for the consistency guarantees of the
+    /// iterator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashSet;
+    ///
+    /// let a: HashSet<_> = [1, 2, 3].iter().cloned().collect();
+    /// let b: HashSet<_> = [4, 2, 3, 4].iter().cloned().collect();
+    /// let (a_guard, b_guard) = (a.guard(), b.guard());
+    ///
+    /// let mut common: Vec<_> = a.intersection(&b, &a_guard, &b_guard).collect();
+    /// common.sort();
+    /// assert_eq!(common, [&2, &3]);
+    /// ```
+    pub fn intersection<'g>(
+        &'g self,
+        other: &'g HashSet<T, S>,
+        our_guard: &'g Guard<'_>,
+        their_guard: &'g Guard<'_>,
+    ) -> Intersection<'g, T, S> {
+        if self.len() <= other.len() {
+            Intersection {
+                iter: self.iter(our_guard),
+                other,
+                other_guard: their_guard,
+            }
+        } else {
+            Intersection {
+                iter: other.iter(their_guard),
+                other: self,
+                other_guard: our_guard,
+            }
+        }
+    }
+
+    /// Visits the values that are in `self` or in `other`, without duplicates.
+    ///
+    /// All values of `self` are visited first, followed by the values of `other` that are not in
+    /// `self`. See [`difference`](HashSet::difference) for the consistency guarantees of the
+    /// iterator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashSet;
+    ///
+    /// let a: HashSet<_> = [1, 2, 3].iter().cloned().collect();
+    /// let b: HashSet<_> = [4, 2, 3, 4].iter().cloned().collect();
+    /// let (a_guard, b_guard) = (a.guard(), b.guard());
+    ///
+    /// let mut all: Vec<_> = a.union(&b, &a_guard, &b_guard).collect();
+    /// all.sort();
+    /// assert_eq!(all, [&1, &2, &3, &4]);
+    /// ```
+    pub fn union<'g>(
+        &'g self,
+        other: &'g HashSet<T, S>,
+        our_guard: &'g Guard<'_>,
+        their_guard: &'g Guard<'_>,
+    ) -> Union<'g, T, S> {
+        Union {
+            iter: self
+                .iter(our_guard)
+                .chain(other.difference(self, their_guard, our_guard)),
+        }
+    }
+
+    /// Returns `true` if this set and `other` contain the same elements, using guards that the
+    /// caller already holds for each of the two sets.
+    ///
+    /// See also [`HashMap::guarded_eq`](crate::HashMap::guarded_eq).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashSet;
+    ///
+    /// let a = HashSet::new();
+    /// let b = HashSet::new();
+    /// let (guard_a, guard_b) = (a.guard(), b.guard());
+    ///
+    /// a.insert(1, &guard_a);
+    /// b.insert(1, &guard_b);
+    /// assert!(a.guarded_eq(&b, &guard_a, &guard_b));
+    /// ```
+    pub fn guarded_eq(&self, other: &Self, our_guard: &Guard<'_>, their_guard: &Guard<'_>) -> bool {
         self.map.guarded_eq(&other.map, our_guard, their_guard)
     }
 }
@@ -410,6 +703,29 @@ where
         old.is_none()
     }
 
+    /// Adds a value to the set, replacing the existing value, if any, that is equal to the given
+    /// one. Returns the replaced value.
+    ///
+    /// This is useful when equality only considers part of the value. The replacement happens
+    /// atomically with respect to other updates of the set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashSet;
+    ///
+    /// let set = HashSet::new();
+    /// let guard = set.guard();
+    /// set.insert(Vec::<i32>::new(), &guard);
+    ///
+    /// assert_eq!(set.get(&[][..], &guard).unwrap().capacity(), 0);
+    /// assert_eq!(set.replace(Vec::with_capacity(10), &guard), Some(&Vec::new()));
+    /// assert_eq!(set.get(&[][..], &guard).unwrap().capacity(), 10);
+    /// ```
+    pub fn replace<'g>(&'g self, value: T, guard: &'g Guard<'_>) -> Option<&'g T> {
+        self.map.replace_key(value, (), guard)
+    }
+
     /// Removes a value from the set.
     ///
     /// If the set did not have this value present, `false` is returned.
@@ -459,7 +775,7 @@ where
     /// ```
     /// use flurry::HashSet;
     ///
-    /// let mut set: HashSet<_> = [1, 2, 3].iter().cloned().collect();
+    /// let set: HashSet<_> = [1, 2, 3].iter().cloned().collect();
     /// let guard = set.guard();
     /// assert_eq!(set.take(&2, &guard), Some(&2));
     /// assert_eq!(set.take(&2, &guard), None);
@@ -489,12 +805,74 @@ where
     /// set.pin().retain(|&e| e % 2 == 0);
     /// assert_eq!(set.pin().len(), 4);
     /// ```
+    ///
+    /// # Notes
+    ///
+    /// If `f` returns `false` for an element, but that element is concurrently inserted again
+    /// before the removal takes place, the element will not be removed.
+    /// If you want the removal to happen regardless, use [`HashSet::retain_force`].
     pub fn retain<F>(&self, mut f: F, guard: &Guard<'_>)
     where
         F: FnMut(&T) -> bool,
     {
         self.map.retain(|value, ()| f(value), guard)
     }
+
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// In other words, remove all elements `e` such that `f(&e)` returns `false`.
+    ///
+    /// This method always removes any element that `f` returns `false` for, even if it is
+    /// concurrently inserted again. If you do not want that behavior, use [`HashSet::retain`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashSet;
+    ///
+    /// let set = HashSet::new();
+    ///
+    /// for i in 0..8 {
+    ///     set.pin().insert(i);
+    /// }
+    /// set.pin().retain_force(|&e| e % 2 == 0);
+    /// assert_eq!(set.pin().len(), 4);
+    /// ```
+    pub fn retain_force<F>(&self, mut f: F, guard: &Guard<'_>)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.map.retain_force(|value, ()| f(value), guard)
+    }
+
+    /// Removes all elements from the set, and returns an iterator over the removed elements. Unlike
+    /// [`std::collections::HashSet::drain`], each element is removed only when the iterator
+    /// reaches it, so dropping the iterator early leaves the remaining elements in the set.
+    ///
+    /// See [`HashMap::drain`] for how the removal interacts with concurrent modifications. The
+    /// yielded references remain valid for as long as the guard is held; to move the elements
+    /// out of a set that is no longer shared, use its [`IntoIterator`] implementation instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashSet;
+    ///
+    /// let set = HashSet::new();
+    /// let guard = set.guard();
+    /// set.insert(1, &guard);
+    /// set.insert(2, &guard);
+    ///
+    /// let mut drained: Vec<_> = set.drain(&guard).collect();
+    /// drained.sort();
+    /// assert_eq!(drained, [&1, &2]);
+    /// assert!(set.is_empty());
+    /// ```
+    pub fn drain<'g>(&'g self, guard: &'g Guard<'_>) -> SetDrain<'g, T, S> {
+        SetDrain {
+            inner: self.map.drain(guard),
+        }
+    }
 }
 
 impl<T, S> HashSet<T, S>
@@ -525,6 +903,15 @@ where
     pub fn reserve(&self, additional: usize, guard: &Guard<'_>) {
         self.map.reserve(additional, guard)
     }
+
+    /// Tries to reserve capacity for at least `additional` more elements to
+    /// be inserted in the `HashSet`, returning an error if the new table
+    /// cannot be allocated.
+    ///
+    /// Like [`HashMap::try_reserve`], this is best-effort. See there for details.
+    pub fn try_reserve(&self, additional: usize, guard: &Guard<'_>) -> Result<(), TryReserveError> {
+        self.map.try_reserve(additional, guard)
+    }
 }
 
 impl<T, S> PartialEq for HashSet<T, S>
@@ -598,11 +985,58 @@ where
     }
 }
 
+impl<T, S> IntoIterator for HashSet<T, S> {
+    type Item = T;
+    type IntoIter = IntoKeys<T, ()>;
+
+    /// Creates a consuming iterator, that is, one that moves each value out of the set in
+    /// arbitrary order. The set cannot be used after calling this.
+    ///
+    /// Like the [`IntoIterator`] implementation of [`HashMap`], this does not clone the values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashSet;
+    ///
+    /// let set = HashSet::new();
+    /// set.pin().insert(String::from("a"));
+    /// set.pin().insert(String::from("b"));
+    ///
+    /// let mut values: Vec<String> = set.into_iter().collect();
+    /// values.sort();
+    /// assert_eq!(values, ["a", "b"]);
+    /// ```
+    fn into_iter(self) -> Self::IntoIter {
+        self.map.into_keys()
+    }
+}
+
 impl<T, S> Clone for HashSet<T, S>
 where
     T: Sync + Send + Clone + Hash + Ord,
     S: BuildHasher + Clone,
 {
+    /// Returns a point-in-time snapshot of the set.
+    ///
+    /// Like the [`Clone`] implementation of [`HashMap`], the values are copied with the same weak
+    /// consistency as [`iter`](HashSet::iter), and the clone is created with enough capacity for
+    /// the number of values in the set, so copying them does not resize it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flurry::HashSet;
+    ///
+    /// let flags = HashSet::new();
+    /// flags.pin().insert("dark-mode");
+    ///
+    /// // branch the flags before trying out a variant
+    /// let variant = flags.clone();
+    /// variant.pin().insert("new-checkout");
+    /// assert!(!flags.pin().contains("new-checkout"));
+    /// assert!(variant.pin().contains("dark-mode"));
+    /// ```
     fn clone(&self) -> HashSet<T, S> {
         Self {
             map: self.map.clone(),
@@ -1,6 +1,6 @@
 use crate::iter::*;
 use crate::reclaim::{Guard, GuardRef};
-use crate::HashSet;
+use crate::{HashSet, TryReserveError};
 use std::borrow::Borrow;
 use std::fmt::{self, Debug, Formatter};
 use std::hash::{BuildHasher, Hash};
@@ -36,6 +36,13 @@ impl<T, S> HashSet<T, S> {
 }
 
 impl<T, S> HashSetRef<'_, T, S> {
+    /// Returns a reference to the set's [`BuildHasher`].
+    ///
+    /// See also [`HashSet::hasher`].
+    pub fn hasher(&self) -> &S {
+        self.set.hasher()
+    }
+
     /// Returns the number of elements in the set.
     ///
     /// See also [`HashSet::len`].
@@ -58,6 +65,16 @@ impl<T, S> HashSetRef<'_, T, S> {
     pub fn iter(&self) -> Keys<'_, T, ()> {
         self.set.iter(&self.guard)
     }
+
+    /// Returns the number of elements for which `f` returns `true`.
+    ///
+    /// See also [`HashSet::count_if`].
+    pub fn count_if<F>(&self, f: F) -> usize
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.set.count_if(f, &self.guard)
+    }
 }
 
 impl<T, S> HashSetRef<'_, T, S>
@@ -108,6 +125,38 @@ where
     pub fn is_superset(&self, other: &HashSetRef<'_, T, S>) -> bool {
         self.set.is_superset(other.set, &self.guard, &other.guard)
     }
+
+    /// Visits the values that are in `self` but not in `other`.
+    ///
+    /// See also [`HashSet::difference`].
+    pub fn difference<'g>(&'g self, other: &'g HashSetRef<'_, T, S>) -> Difference<'g, T, S> {
+        self.set.difference(other.set, &self.guard, &other.guard)
+    }
+
+    /// Visits the values that are in `self` or in `other`, but not in both.
+    ///
+    /// See also [`HashSet::symmetric_difference`].
+    pub fn symmetric_difference<'g>(
+        &'g self,
+        other: &'g HashSetRef<'_, T, S>,
+    ) -> SymmetricDifference<'g, T, S> {
+        self.set
+            .symmetric_difference(other.set, &self.guard, &other.guard)
+    }
+
+    /// Visits the values that are in both `self` and `other`.
+    ///
+    /// See also [`HashSet::intersection`].
+    pub fn intersection<'g>(&'g self, other: &'g HashSetRef<'_, T, S>) -> Intersection<'g, T, S> {
+        self.set.intersection(other.set, &self.guard, &other.guard)
+    }
+
+    /// Visits the values that are in `self` or in `other`, without duplicates.
+    ///
+    /// See also [`HashSet::union`].
+    pub fn union<'g>(&'g self, other: &'g HashSetRef<'_, T, S>) -> Union<'g, T, S> {
+        self.set.union(other.set, &self.guard, &other.guard)
+    }
 }
 
 impl<T, S> HashSetRef<'_, T, S>
@@ -122,6 +171,14 @@ where
         self.set.insert(value, &self.guard)
     }
 
+    /// Adds a value to the set, replacing the existing value, if any, that is equal to the given
+    /// one. Returns the replaced value.
+    ///
+    /// See also [`HashSet::replace`].
+    pub fn replace(&self, value: T) -> Option<&'_ T> {
+        self.set.replace(value, &self.guard)
+    }
+
     /// Removes a value from the set.
     ///
     /// See also [`HashSet::remove`].
@@ -153,6 +210,24 @@ where
     {
         self.set.retain(f, &self.guard);
     }
+
+    /// Retains only the elements specified by the predicate, even if they are concurrently
+    /// inserted again.
+    ///
+    /// See also [`HashSet::retain_force`].
+    pub fn retain_force<F>(&self, f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.set.retain_force(f, &self.guard);
+    }
+
+    /// Removes all elements from the set, and returns an iterator over the removed elements.
+    ///
+    /// See also [`HashSet::drain`].
+    pub fn drain(&self) -> SetDrain<'_, T, S> {
+        self.set.drain(&self.guard)
+    }
 }
 
 impl<T, S> HashSetRef<'_, T, S>
@@ -173,6 +248,14 @@ where
     pub fn reserve(&self, additional: usize) {
         self.set.reserve(additional, &self.guard)
     }
+
+    /// Tries to reserve capacity for at least `additional` more elements to
+    /// be inserted into the underlying `HashSet`, returning an error if that is not possible.
+    ///
+    /// See also [`HashSet::try_reserve`].
+    pub fn try_reserve(&self, additional: usize) -> Result<(), TryReserveError> {
+        self.set.try_reserve(additional, &self.guard)
+    }
 }
 
 impl<'g, T, S> IntoIterator for &'g HashSetRef<'_, T, S> {
@@ -184,6 +267,37 @@ impl<'g, T, S> IntoIterator for &'g HashSetRef<'_, T, S> {
     }
 }
 
+impl<T, S> Extend<T> for &HashSetRef<'_, T, S>
+where
+    T: Sync + Send + Clone + Hash + Ord,
+    S: BuildHasher,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        // see `Extend for &HashMap` for the reservation strategy
+        let iter = iter.into_iter();
+        let reserve = if self.is_empty() {
+            iter.size_hint().0
+        } else {
+            (iter.size_hint().0 + 1) / 2
+        };
+
+        self.reserve(reserve);
+        for value in iter {
+            self.insert(value);
+        }
+    }
+}
+
+impl<'a, T, S> Extend<&'a T> for &HashSetRef<'_, T, S>
+where
+    T: Sync + Send + Copy + Hash + Ord,
+    S: BuildHasher,
+{
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
 impl<T, S> Debug for HashSetRef<'_, T, S>
 where
     T: Debug,
@@ -194,6 +308,10 @@ where
 }
 
 impl<T, S> Clone for HashSetRef<'_, T, S> {
+    /// Returns another reference to the same set.
+    ///
+    /// This does not copy the set. Like the clone of a [`HashMapRef`](crate::HashMapRef), the new
+    /// reference pins a guard of its own with [`HashSet::pin`] rather than sharing this one's.
     fn clone(&self) -> Self {
         self.set.pin()
     }
@@ -6,6 +6,59 @@ fn new() {
     let _map = HashMap::<usize, usize>::new();
 }
 
+#[test]
+fn with_capacity_and_hasher() {
+    use std::collections::hash_map::RandomState;
+
+    let map = HashMap::<usize, usize, _>::with_capacity_and_hasher(1000, RandomState::new());
+    let guard = map.guard();
+    // the table is allocated up front, with room for all the elements
+    let capacity = map.capacity(&guard);
+    assert!(capacity >= 1000);
+
+    for i in 0..1000 {
+        map.insert(i, i, &guard);
+    }
+    assert_eq!(map.capacity(&guard), capacity);
+    assert_eq!(map.get(&42, &guard), Some(&42));
+}
+
+#[test]
+fn hasher() {
+    use std::collections::hash_map::RandomState;
+    use std::hash::{BuildHasher, Hash, Hasher};
+
+    let s = RandomState::new();
+    let map = HashMap::<usize, usize, _>::with_hasher(s.clone());
+
+    let hash = |s: &RandomState| {
+        let mut hasher = s.build_hasher();
+        42usize.hash(&mut hasher);
+        hasher.finish()
+    };
+    assert_eq!(hash(map.hasher()), hash(&s));
+    assert_eq!(hash(map.pin().hasher()), hash(&s));
+}
+
+#[test]
+fn builder() {
+    use std::collections::hash_map::RandomState;
+
+    let map: HashMap<usize, usize, _> = HashMap::builder()
+        .capacity(1000)
+        .hasher(RandomState::new())
+        .load_factor(0.5)
+        .build();
+    let guard = map.guard();
+    let capacity = map.capacity(&guard);
+    assert!(capacity >= 2000);
+    for i in 0..1000 {
+        map.insert(i, i, &guard);
+    }
+    assert_eq!(map.capacity(&guard), capacity);
+    assert_eq!(map.len(), 1000);
+}
+
 #[test]
 fn clear() {
     let map = HashMap::<usize, usize>::new();
@@ -21,6 +74,109 @@ fn clear() {
     assert!(map.is_empty());
 }
 
+#[test]
+fn clear_fast() {
+    let map = HashMap::<usize, usize>::new();
+    let guard = map.guard();
+    // clearing a map without a table is a no-op
+    map.clear_fast(&guard);
+    assert!(map.is_empty());
+
+    for i in 0..1000 {
+        map.insert(i, i, &guard);
+    }
+    let value = map.get(&42, &guard).unwrap();
+    let capacity = map.capacity(&guard);
+    map.clear_fast(&guard);
+    assert!(map.is_empty());
+    assert_eq!(map.capacity(&guard), capacity);
+    assert_eq!(map.get(&42, &guard), None);
+    assert_eq!(map.iter(&guard).count(), 0);
+    // references obtained before the clear remain valid while the guard is held
+    assert_eq!(value, &42);
+
+    // the cleared map is fully usable
+    for i in 0..2000 {
+        map.insert(i, i + 1, &guard);
+    }
+    assert_eq!(map.len(), 2000);
+    for i in 0..2000 {
+        assert_eq!(map.get(&i, &guard), Some(&(i + 1)));
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn concurrent_clear_fast() {
+    let map = Arc::new(HashMap::<usize, usize>::new());
+
+    let writers: Vec<_> = (0..2)
+        .map(|t| {
+            let map = map.clone();
+            std::thread::spawn(move || {
+                let guard = map.guard();
+                for i in 0..8192 {
+                    let key = t * 8192 + i;
+                    map.insert(key, key, &guard);
+                    if let Some(v) = map.get(&key, &guard) {
+                        assert_eq!(*v, key);
+                    }
+                }
+            })
+        })
+        .collect();
+    let clearer = {
+        let map = map.clone();
+        std::thread::spawn(move || {
+            for _ in 0..32 {
+                let guard = map.guard();
+                map.clear_fast(&guard);
+            }
+        })
+    };
+    for handle in writers {
+        handle.join().unwrap();
+    }
+    clearer.join().unwrap();
+
+    // the count must agree with the entries that survived
+    let guard = map.guard();
+    assert_eq!(map.len(), map.iter(&guard).count());
+    map.clear_fast(&guard);
+    assert!(map.is_empty());
+    assert_eq!(map.iter(&guard).count(), 0);
+}
+
+#[test]
+fn pinned_lookups() {
+    let map = HashMap::<usize, String>::new();
+    assert!(!map.contains_key_pinned(&1));
+    assert_eq!(map.get_cloned(&1), None);
+
+    map.pin().insert(1, String::from("a"));
+    assert!(map.contains_key_pinned(&1));
+    let value = map.get_cloned(&1).unwrap();
+    map.pin().remove(&1);
+    // the clone is independent of the map
+    assert_eq!(value, "a");
+    assert!(!map.contains_key_pinned(&1));
+}
+
+#[test]
+fn get_cloned_guarded() {
+    let map = HashMap::<usize, String>::new();
+    let guard = map.guard();
+    assert_eq!(map.get_cloned_guarded(&1, &guard), None);
+
+    map.insert(1, String::from("a"), &guard);
+    let value = map.get_cloned_guarded(&1, &guard).unwrap();
+    map.remove(&1, &guard);
+    drop(guard);
+    // the clone outlives both the entry and the guard
+    assert_eq!(value, "a");
+    assert_eq!(map.pin().get_cloned_guarded(&1), None);
+}
+
 #[test]
 fn insert() {
     let map = HashMap::<usize, usize>::new();
@@ -76,163 +232,1880 @@ fn insert_and_remove() {
 }
 
 #[test]
-fn insert_and_get() {
+fn replace() {
     let map = HashMap::<usize, usize>::new();
 
-    map.insert(42, 0, &map.guard());
     {
         let guard = map.guard();
-        let e = map.get(&42, &guard).unwrap();
-        assert_eq!(e, &0);
+        assert_eq!(map.replace(&42, 0, &guard), None);
+        assert!(map.is_empty());
+        map.insert(42, 0, &guard);
+        assert_eq!(map.replace(&42, 1, &guard), Some(&0));
+        assert_eq!(map.get(&42, &guard), Some(&1));
+        assert_eq!(map.len(), 1);
     }
 }
 
 #[test]
-fn insert_and_get_key_value() {
+fn replace_if_eq() {
+    let map = HashMap::<usize, String>::new();
+
+    {
+        let guard = map.guard();
+        let a = String::from("a");
+        assert_eq!(
+            map.replace_if_eq(&42, &a, a.clone(), &guard),
+            Err(a.clone())
+        );
+        assert!(map.is_empty());
+        map.insert(42, a.clone(), &guard);
+        let b = String::from("b");
+        assert_eq!(
+            map.replace_if_eq(&42, &b, b.clone(), &guard),
+            Err(b.clone())
+        );
+        assert_eq!(map.replace_if_eq(&42, &a, b.clone(), &guard), Ok(Some(&a)));
+        assert_eq!(map.get(&42, &guard), Some(&b));
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn retain_unused_arcs() {
+    let map = Arc::new(HashMap::<usize, Arc<usize>>::new());
+    let held: Vec<Arc<usize>> = {
+        let guard = map.guard();
+        (0..1024)
+            .map(|i| {
+                let value = Arc::new(i);
+                map.insert(i, Arc::clone(&value), &guard);
+                value
+            })
+            .collect()
+    };
+    // keep every even value alive outside of the map
+    let held: Vec<_> = held.into_iter().filter(|v| **v % 2 == 0).collect();
+
+    // readers keep cloning and dropping the Arcs of the odd entries while we evict
+    let readers: Vec<_> = (0..2)
+        .map(|_| {
+            let map = Arc::clone(&map);
+            std::thread::spawn(move || {
+                let guard = map.guard();
+                for _ in 0..16 {
+                    for i in (1..1024).step_by(2) {
+                        if let Some(v) = map.get(&i, &guard) {
+                            let v = Arc::clone(v);
+                            assert_eq!(*v, i);
+                        }
+                    }
+                }
+            })
+        })
+        .collect();
+
+    let guard = map.guard();
+    for _ in 0..16 {
+        map.retain(|_, v| Arc::strong_count(v) > 1, &guard);
+        // no live entry is ever evicted
+        for v in &held {
+            assert!(map.contains_key(&**v, &guard));
+        }
+    }
+    for r in readers {
+        r.join().unwrap();
+    }
+
+    // once the readers are gone, only the held entries survive
+    map.retain(|_, v| Arc::strong_count(v) > 1, &guard);
+    assert_eq!(map.len(), held.len());
+}
+
+#[test]
+fn update_all() {
     let map = HashMap::<usize, usize>::new();
+    let guard = map.guard();
+    map.update_all(|_, _| unreachable!(), &guard);
 
-    map.insert(42, 0, &map.guard());
+    for i in 0..1024 {
+        map.insert(i, i, &guard);
+    }
+    let mut visited = 0;
+    map.update_all(
+        |k, v| {
+            visited += 1;
+            assert_eq!(k, v);
+            // drop every third entry, including some bin heads
+            if k % 3 == 0 {
+                None
+            } else {
+                Some(v * 2)
+            }
+        },
+        &guard,
+    );
+    assert_eq!(visited, 1024);
+    assert_eq!(map.len(), 682);
+    for i in 0..1024 {
+        let expected = if i % 3 == 0 { None } else { Some(i * 2) };
+        assert_eq!(map.get(&i, &guard).copied(), expected);
+    }
+
+    map.update_all(|_, _| None, &guard);
+    assert!(map.is_empty());
+    assert_eq!(map.iter(&guard).count(), 0);
+}
+
+#[test]
+#[should_panic(expected = "cannot access the map for the same bin from within a compute closure")]
+fn update_all_reentrant() {
+    let map = HashMap::<usize, usize>::new();
+    let guard = map.guard();
+    map.insert(42, 0, &guard);
+    map.update_all(
+        |k, v| {
+            map.remove(k, &guard);
+            Some(*v)
+        },
+        &guard,
+    );
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn concurrent_update_all() {
+    let map = Arc::new(HashMap::<usize, usize>::new());
     {
         let guard = map.guard();
-        let e = map.get_key_value(&42, &guard).unwrap();
-        assert_eq!(e, (&42, &0));
+        for i in 0..4096 {
+            map.insert(i, 0, &guard);
+        }
+    }
+
+    // keep moving the entries between tables while they are being updated
+    let done = Arc::new(std::sync::atomic::AtomicBool::new(false));
+    let resizer = {
+        let map = Arc::clone(&map);
+        let done = Arc::clone(&done);
+        std::thread::spawn(move || {
+            let guard = map.guard();
+            while !done.load(std::sync::atomic::Ordering::SeqCst) {
+                map.reserve(1 << 15, &guard);
+                map.shrink_to_fit(&guard);
+            }
+        })
+    };
+
+    let guard = map.guard();
+    for _ in 0..8 {
+        map.update_all(|_, v| Some(v + 1), &guard);
+    }
+    done.store(true, std::sync::atomic::Ordering::SeqCst);
+    resizer.join().unwrap();
+
+    // every entry was updated exactly once per call
+    assert_eq!(map.len(), 4096);
+    for i in 0..4096 {
+        assert_eq!(map.get(&i, &guard), Some(&8));
     }
 }
 
-mod hasher;
-use hasher::ZeroHashBuilder;
+#[test]
+fn take_if() {
+    let map = HashMap::<usize, String>::new();
+    let guard = map.guard();
+    assert_eq!(map.take_if(&42, |_, _| unreachable!(), &guard), None);
+
+    map.insert(42, String::from("a"), &guard);
+    assert_eq!(map.take_if(&42, |_, v| v == "b", &guard), None);
+    assert_eq!(map.len(), 1);
+    assert_eq!(
+        map.take_if(&42, |k, v| *k == 42 && v == "a", &guard),
+        Some(String::from("a"))
+    );
+    assert!(map.is_empty());
+}
 
 #[test]
-fn one_bucket() {
-    let map = HashMap::<&'static str, usize, _>::with_hasher(ZeroHashBuilder);
+#[cfg_attr(miri, ignore)]
+fn concurrent_take_if() {
+    let map = Arc::new(HashMap::<usize, usize>::new());
+    {
+        let guard = map.guard();
+        for i in 0..1024 {
+            map.insert(i, i, &guard);
+        }
+    }
+
+    // workers race to claim the even entries
+    let threads: Vec<_> = (0..4)
+        .map(|_| {
+            let map = Arc::clone(&map);
+            std::thread::spawn(move || {
+                let guard = map.guard();
+                (0..1024)
+                    .filter_map(|i| map.take_if(&i, |_, v| v % 2 == 0, &guard))
+                    .collect::<Vec<_>>()
+            })
+        })
+        .collect();
+    let mut claimed: Vec<_> = threads
+        .into_iter()
+        .flat_map(|t| t.join().unwrap())
+        .collect();
+
+    // every even entry was claimed exactly once, and no odd one was
+    claimed.sort_unstable();
+    assert_eq!(claimed, (0..1024).step_by(2).collect::<Vec<_>>());
+    assert_eq!(map.len(), 512);
+}
+
+#[test]
+fn any_entry() {
+    let map = HashMap::<usize, usize>::new();
     let guard = map.guard();
+    assert_eq!(map.any_entry(&guard), None);
 
-    // we want to check that all operations work regardless on whether
-    // we are operating on the head of a bucket, the tail of the bucket,
-    // or somewhere in the middle.
-    let v = map.insert("head", 0, &guard);
-    assert_eq!(v, None);
-    let v = map.insert("middle", 10, &guard);
-    assert_eq!(v, None);
-    let v = map.insert("tail", 100, &guard);
-    assert_eq!(v, None);
-    let e = map.get("head", &guard).unwrap();
-    assert_eq!(e, &0);
-    let e = map.get("middle", &guard).unwrap();
-    assert_eq!(e, &10);
-    let e = map.get("tail", &guard).unwrap();
-    assert_eq!(e, &100);
+    for i in 0..64 {
+        map.insert(i, i * 2, &guard);
+    }
+    let (&k, &v) = map.any_entry(&guard).unwrap();
+    assert_eq!(v, k * 2);
+    // the same entry is found again while the map is unchanged
+    assert_eq!(map.any_entry(&guard), Some((&k, &v)));
 
-    // check that replacing the keys returns the correct old value
-    let v = map.insert("head", 1, &guard);
-    assert_eq!(v, Some(&0));
-    let v = map.insert("middle", 11, &guard);
-    assert_eq!(v, Some(&10));
-    let v = map.insert("tail", 101, &guard);
-    assert_eq!(v, Some(&100));
-    // and updated the right value
-    let e = map.get("head", &guard).unwrap();
-    assert_eq!(e, &1);
-    let e = map.get("middle", &guard).unwrap();
-    assert_eq!(e, &11);
-    let e = map.get("tail", &guard).unwrap();
-    assert_eq!(e, &101);
-    // and that remove produces the right value
-    // note that we must remove them in a particular order
-    // so that we test all three node positions
-    let v = map.remove("middle", &guard);
-    assert_eq!(v, Some(&11));
-    let v = map.remove("tail", &guard);
-    assert_eq!(v, Some(&101));
-    let v = map.remove("head", &guard);
-    assert_eq!(v, Some(&1));
+    for i in 0..64 {
+        map.remove(&i, &guard);
+    }
+    assert_eq!(map.any_entry(&guard), None);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn concurrent_any_entry() {
+    let map = Arc::new(HashMap::<usize, usize>::new());
+    map.pin().insert(0, 0);
+
+    // t1 keeps resizing the table by inserting and removing more entries
+    let map1 = Arc::clone(&map);
+    let t1 = std::thread::spawn(move || {
+        let guard = map1.guard();
+        for i in 1..4096 {
+            map1.insert(i, i, &guard);
+        }
+        for i in 1..4096 {
+            map1.remove(&i, &guard);
+        }
+    });
+
+    // entry 0 is never removed, so the map is never seen as empty
+    for _ in 0..4096 {
+        let guard = map.guard();
+        let (&k, &v) = map.any_entry(&guard).unwrap();
+        assert_eq!(k, v);
+    }
+    t1.join().unwrap();
+}
+
+#[test]
+#[cfg(feature = "rand")]
+fn sample() {
+    use rand::SeedableRng;
+
+    let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+    let map = HashMap::<usize, usize>::new();
+    let guard = map.guard();
+    assert!(map.sample(3, &mut rng, &guard).is_empty());
+
+    for i in 0..100 {
+        map.insert(i, i * 2, &guard);
+    }
+    assert!(map.sample(0, &mut rng, &guard).is_empty());
+
+    let sample = map.sample(10, &mut rng, &guard);
+    assert_eq!(sample.len(), 10);
+    let mut keys: Vec<_> = sample
+        .iter()
+        .map(|&(&k, &v)| {
+            assert_eq!(v, k * 2);
+            k
+        })
+        .collect();
+    keys.sort_unstable();
+    keys.dedup();
+    assert_eq!(keys.len(), 10);
+
+    // asking for more entries than there are returns all of them
+    let mut all: Vec<_> = map
+        .sample(1000, &mut rng, &guard)
+        .into_iter()
+        .map(|(&k, _)| k)
+        .collect();
+    all.sort_unstable();
+    assert_eq!(all, (0..100).collect::<Vec<_>>());
+}
+
+#[test]
+#[cfg(feature = "rand")]
+#[cfg_attr(miri, ignore)]
+fn sample_is_uniform() {
+    use rand::SeedableRng;
+
+    let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+    let map: HashMap<usize, ()> = (0..10).map(|i| (i, ())).collect();
+    let guard = map.guard();
+
+    let mut hits = [0; 10];
+    for _ in 0..10_000 {
+        for (&k, _) in map.sample(2, &mut rng, &guard) {
+            hits[k] += 1;
+        }
+    }
+    // each key is expected to be picked 2000 times
+    for &h in &hits {
+        assert!((1700..2300).contains(&h), "{:?}", hits);
+    }
+}
+
+#[test]
+#[cfg(feature = "versioned")]
+fn iter_since() {
+    let map = HashMap::<usize, usize>::new();
+    let guard = map.guard();
+    assert_eq!(map.current_version(), 0);
+    assert_eq!(map.iter_since(0, &guard).count(), 0);
+
+    for i in 0..100 {
+        map.insert(i, i, &guard);
+    }
+    assert_eq!(map.current_version(), 100);
+    assert_eq!(map.iter_since(0, &guard).count(), 100);
+
+    let seen = map.current_version();
+    assert_eq!(map.iter_since(seen, &guard).count(), 0);
+
+    // every kind of insert or update bumps the version
+    map.insert(1, 10, &guard);
+    map.compute_if_present(&2, |_, v| Some(v * 10), &guard);
+    map.compute(3, |_, v| v.map(|v| v * 10), &guard);
+    map.get_or_insert_with(100, || 1000, &guard);
+    map.try_insert(101, 1010, &guard).unwrap();
+    map.remove(&6, &guard);
+    let mut changed: Vec<_> = map.iter_since(seen, &guard).collect();
+    changed.sort();
+    assert_eq!(
+        changed,
+        [
+            (&1, &10),
+            (&2, &20),
+            (&3, &30),
+            (&100, &1000),
+            (&101, &1010)
+        ]
+    );
+
+    // removals and lookups do not bump the version
+    let seen = map.current_version();
+    map.remove(&7, &guard);
+    map.get(&8, &guard);
+    assert_eq!(map.current_version(), seen);
+
+    // a resize does not change the version of any entry
+    map.reserve(10_000, &guard);
+    assert_eq!(map.iter_since(seen, &guard).count(), 0);
+    map.shrink_to_fit(&guard);
+    assert_eq!(map.iter_since(seen, &guard).count(), 0);
+    assert_eq!(map.iter_since(0, &guard).count(), map.len());
+
+    map.update_all(|_, &v| Some(v + 1), &guard);
+    assert_eq!(map.iter_since(seen, &guard).count(), map.len());
+}
+
+#[test]
+#[cfg(feature = "versioned")]
+#[cfg_attr(miri, ignore)]
+fn concurrent_iter_since() {
+    use std::collections::HashMap as StdMap;
+
+    let map = Arc::new(HashMap::<usize, usize>::new());
+    let map1 = map.clone();
+    let writer = std::thread::spawn(move || {
+        let guard = map1.guard();
+        for round in 1..=20 {
+            for i in 0..512 {
+                map1.insert(i, round, &guard);
+            }
+        }
+    });
+
+    // a consumer that keeps its own copy in sync with the map sees every change at least once
+    let mut replica = StdMap::new();
+    let mut seen = 0;
+    loop {
+        let done = Arc::strong_count(&map) == 1;
+        let guard = map.guard();
+        let next = map.current_version();
+        for (&k, &v) in map.iter_since(seen, &guard) {
+            replica.insert(k, v);
+        }
+        seen = next;
+        if done {
+            break;
+        }
+    }
+    writer.join().unwrap();
+
+    let guard = map.guard();
+    assert_eq!(replica.len(), map.len());
+    for (k, v) in map.iter(&guard) {
+        assert_eq!(replica.get(k), Some(v));
+    }
+}
+
+#[test]
+fn remove_many() {
+    let map = HashMap::<String, usize>::new();
+    let guard = map.guard();
+    for i in 0..100 {
+        map.insert(i.to_string(), i, &guard);
+    }
+
+    let keys: Vec<String> = (0..50).map(|i| i.to_string()).collect();
+    assert_eq!(map.remove_many(keys.iter().map(String::as_str), &guard), 50);
+    assert_eq!(map.len(), 50);
+    assert!(keys.iter().all(|k| !map.contains_key(k.as_str(), &guard)));
+
+    // missing and repeated keys are not counted
+    assert_eq!(map.remove_many(["0", "50", "50", "99"], &guard), 2);
+    assert_eq!(map.len(), 48);
+    assert_eq!(map.remove_many(std::iter::empty::<&str>(), &guard), 0);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn concurrent_remove_many() {
+    let map = Arc::new(HashMap::<usize, usize>::new());
+    {
+        let guard = map.guard();
+        for i in 0..1024 {
+            map.insert(i, i, &guard);
+        }
+    }
+
+    let keys: Arc<Vec<usize>> = Arc::new((0..1024).collect());
+    let threads: Vec<_> = (0..4)
+        .map(|_| {
+            let map = Arc::clone(&map);
+            let keys = Arc::clone(&keys);
+            std::thread::spawn(move || map.remove_many(keys.iter(), &map.guard()))
+        })
+        .collect();
+    let removed: usize = threads.into_iter().map(|t| t.join().unwrap()).sum();
+
+    // every key was removed by exactly one thread
+    assert_eq!(removed, 1024);
+    assert!(map.is_empty());
+}
+
+#[test]
+fn remove_owned() {
+    let map = HashMap::<usize, Vec<usize>>::new();
+    let guard = map.guard();
+    map.insert(42, vec![1, 2, 3], &guard);
+    let borrowed = map.get(&42, &guard).unwrap();
+
+    let owned = map.remove_owned(&42, &guard).unwrap();
+    assert_eq!(owned, [1, 2, 3]);
+    assert!(!map.contains_key(&42, &guard));
+    // readers that got to the value before it was removed can still use it
+    assert_eq!(borrowed, &owned);
+    drop(guard);
+
+    // the owned value outlives the guard
+    assert_eq!(owned.len(), 3);
+    assert_eq!(map.remove_owned(&42, &map.guard()), None);
+}
+
+#[test]
+fn insert_owned() {
+    let map = HashMap::<usize, Vec<usize>>::new();
+    let guard = map.guard();
+    assert_eq!(map.insert_owned(42, vec![1, 2, 3], &guard), None);
+    let borrowed = map.get(&42, &guard).unwrap();
+
+    let owned = map.insert_owned(42, vec![4], &guard).unwrap();
+    assert_eq!(owned, [1, 2, 3]);
+    assert_eq!(map.get(&42, &guard), Some(&vec![4]));
+    // readers that got to the old value before it was replaced can still use it
+    assert_eq!(borrowed, &owned);
+    drop(guard);
+
+    // the owned value outlives the guard
+    assert_eq!(owned.len(), 3);
+}
+
+#[test]
+fn remove_if() {
+    let map = HashMap::<usize, usize>::new();
+
+    {
+        let guard = map.guard();
+        assert_eq!(map.remove_if(&42, |_, _| unreachable!(), &guard), None);
+        map.insert(42, 0, &guard);
+        assert_eq!(map.remove_if(&42, |_, v| *v == 1, &guard), None);
+        assert_eq!(map.get(&42, &guard), Some(&0));
+        assert_eq!(
+            map.remove_if(&42, |k, v| *k == 42 && *v == 0, &guard),
+            Some(&0)
+        );
+        assert!(map.get(&42, &guard).is_none());
+    }
+}
+
+#[test]
+fn insert_and_get() {
+    let map = HashMap::<usize, usize>::new();
+
+    map.insert(42, 0, &map.guard());
+    {
+        let guard = map.guard();
+        let e = map.get(&42, &guard).unwrap();
+        assert_eq!(e, &0);
+    }
+}
+
+#[test]
+fn contains_value() {
+    let map = HashMap::<usize, usize>::new();
+    let guard = map.guard();
+    assert!(!map.contains_value(&0, &guard));
+
+    map.insert(42, 0, &guard);
+    assert!(map.contains_value(&0, &guard));
+    assert!(!map.contains_value(&42, &guard));
+
+    map.remove(&42, &guard);
+    assert!(!map.contains_value(&0, &guard));
+}
+
+#[test]
+fn for_each() {
+    let map = HashMap::<usize, usize>::new();
+    let guard = map.guard();
+    map.for_each(|_, _| unreachable!(), &guard);
+
+    for i in 0..64 {
+        map.insert(i, i * 2, &guard);
+    }
+    let mut seen = std::collections::HashMap::new();
+    map.for_each(
+        |k, v| {
+            assert!(seen.insert(*k, *v).is_none());
+        },
+        &guard,
+    );
+    assert_eq!(seen.len(), 64);
+    for (k, v) in seen {
+        assert_eq!(v, k * 2);
+    }
+}
+
+#[test]
+fn fold() {
+    let map = HashMap::<usize, usize>::new();
+    let guard = map.guard();
+    assert_eq!(map.fold(7, |_, _, _| unreachable!(), &guard), 7);
+
+    for i in 0..64 {
+        map.insert(i, i * 2, &guard);
+    }
+    assert_eq!(map.fold(0, |sum, _, v| sum + v, &guard), 63 * 64);
+    let mut keys = map.fold(
+        Vec::new(),
+        |mut keys, k, _| {
+            keys.push(*k);
+            keys
+        },
+        &guard,
+    );
+    keys.sort_unstable();
+    assert_eq!(keys, (0..64).collect::<Vec<_>>());
+}
+
+#[test]
+fn collect_map() {
+    let map = HashMap::<usize, usize>::new();
+    let guard = map.guard();
+    assert!(map
+        .collect_map::<_, ()>(|_, _| unreachable!(), &guard)
+        .is_empty());
+
+    for i in 0..64 {
+        map.insert(i, i * 2, &guard);
+    }
+    let mut big = map.collect_map(|k, v| if *v >= 100 { Some(*k) } else { None }, &guard);
+    big.sort_unstable();
+    assert_eq!(big, (50..64).collect::<Vec<_>>());
+    assert_eq!(map.collect_map(|_, v| Some(*v), &guard).len(), 64);
+}
+
+#[test]
+fn count_if() {
+    let map = HashMap::<usize, usize>::new();
+    let guard = map.guard();
+    assert_eq!(map.count_if(|_, _| unreachable!(), &guard), 0);
+
+    for i in 0..64 {
+        map.insert(i, i * 2, &guard);
+    }
+    assert_eq!(map.count_if(|_, v| *v >= 100, &guard), 14);
+    assert_eq!(map.count_if(|_, _| true, &guard), 64);
+    assert_eq!(map.pin().count_if(|k, _| k % 2 == 0), 32);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn concurrent_count_if() {
+    let map = Arc::new(HashMap::<usize, usize>::new());
+    {
+        let guard = map.guard();
+        for i in 0..1024 {
+            map.insert(i, i, &guard);
+        }
+    }
+
+    let map1 = map.clone();
+    // keep resizing the table, so that the counts run into forwarding nodes
+    let t = std::thread::spawn(move || {
+        let guard = map1.guard();
+        for _ in 0..8 {
+            map1.reserve(1 << 14, &guard);
+            map1.shrink_to_fit(&guard);
+        }
+    });
+    for _ in 0..32 {
+        let guard = map.guard();
+        assert_eq!(map.count_if(|k, _| k % 2 == 0, &guard), 512);
+    }
+    t.join().unwrap();
+}
+
+#[test]
+fn insert_and_get_key_value() {
+    let map = HashMap::<usize, usize>::new();
+
+    map.insert(42, 0, &map.guard());
+    {
+        let guard = map.guard();
+        let e = map.get_key_value(&42, &guard).unwrap();
+        assert_eq!(e, (&42, &0));
+    }
+}
+
+#[test]
+fn get_key() {
+    let map = HashMap::<String, usize>::new();
+    let guard = map.guard();
+    assert_eq!(map.get_key("a", &guard), None);
+
+    map.insert(String::from("a"), 0, &guard);
+    let stored = map.get_key("a", &guard).unwrap();
+    assert_eq!(stored, "a");
+    // the stored key is returned, not the probe
+    assert!(std::ptr::eq(stored, map.keys(&guard).next().unwrap()));
+
+    map.remove("a", &guard);
+    assert_eq!(map.get_key("a", &guard), None);
+}
+
+#[test]
+fn memory_usage() {
+    let map = HashMap::<usize, usize>::new();
+    let guard = map.guard();
+    assert_eq!(map.memory_usage(&guard), 0);
+
+    // an empty table still costs its bins
+    map.reserve(100, &guard);
+    let empty = map.memory_usage(&guard);
+    assert!(empty >= map.capacity(&guard) * std::mem::size_of::<usize>());
+
+    // and every entry adds the same amount, as long as the table does not change
+    map.insert(0, 0, &guard);
+    let per_entry = map.memory_usage(&guard) - empty;
+    assert!(per_entry > 2 * std::mem::size_of::<usize>());
+    for i in 1..10 {
+        map.insert(i, i, &guard);
+    }
+    assert_eq!(map.memory_usage(&guard), empty + 10 * per_entry);
+
+    // replacing a value does not change the estimate
+    map.insert(0, 1, &guard);
+    assert_eq!(map.memory_usage(&guard), empty + 10 * per_entry);
+
+    map.remove(&0, &guard);
+    assert_eq!(map.memory_usage(&guard), empty + 9 * per_entry);
+}
+
+#[test]
+fn get_many() {
+    let map = HashMap::<usize, usize>::new();
+
+    {
+        let guard = map.guard();
+        assert_eq!(map.get_many([&1, &2], &guard), [None, None]);
+        map.insert(1, 10, &guard);
+        map.insert(2, 20, &guard);
+        assert_eq!(
+            map.get_many([&2, &3, &1, &2], &guard),
+            [Some(&20), None, Some(&10), Some(&20)]
+        );
+        assert_eq!(map.get_many::<usize, 0>([], &guard), []);
+    }
+}
+
+mod hasher;
+use hasher::ZeroHashBuilder;
+
+#[test]
+fn one_bucket() {
+    let map = HashMap::<&'static str, usize, _>::with_hasher(ZeroHashBuilder);
+    let guard = map.guard();
+
+    // we want to check that all operations work regardless on whether
+    // we are operating on the head of a bucket, the tail of the bucket,
+    // or somewhere in the middle.
+    let v = map.insert("head", 0, &guard);
+    assert_eq!(v, None);
+    let v = map.insert("middle", 10, &guard);
+    assert_eq!(v, None);
+    let v = map.insert("tail", 100, &guard);
+    assert_eq!(v, None);
+    let e = map.get("head", &guard).unwrap();
+    assert_eq!(e, &0);
+    let e = map.get("middle", &guard).unwrap();
+    assert_eq!(e, &10);
+    let e = map.get("tail", &guard).unwrap();
+    assert_eq!(e, &100);
+
+    // check that replacing the keys returns the correct old value
+    let v = map.insert("head", 1, &guard);
+    assert_eq!(v, Some(&0));
+    let v = map.insert("middle", 11, &guard);
+    assert_eq!(v, Some(&10));
+    let v = map.insert("tail", 101, &guard);
+    assert_eq!(v, Some(&100));
+    // and updated the right value
+    let e = map.get("head", &guard).unwrap();
+    assert_eq!(e, &1);
+    let e = map.get("middle", &guard).unwrap();
+    assert_eq!(e, &11);
+    let e = map.get("tail", &guard).unwrap();
+    assert_eq!(e, &101);
+    // and that remove produces the right value
+    // note that we must remove them in a particular order
+    // so that we test all three node positions
+    let v = map.remove("middle", &guard);
+    assert_eq!(v, Some(&11));
+    let v = map.remove("tail", &guard);
+    assert_eq!(v, Some(&101));
+    let v = map.remove("head", &guard);
+    assert_eq!(v, Some(&1));
+}
+
+#[test]
+fn update() {
+    let map = HashMap::<usize, usize>::new();
+
+    let guard = map.guard();
+    map.insert(42, 0, &guard);
+    let old = map.insert(42, 1, &guard);
+    assert_eq!(old, Some(&0));
+    {
+        let guard = map.guard();
+        let e = map.get(&42, &guard).unwrap();
+        assert_eq!(e, &1);
+    }
+}
+
+#[test]
+fn try_insert_returns_value() {
+    let map = HashMap::<usize, Vec<usize>>::new();
+    let guard = map.guard();
+    map.insert(42, vec![0], &guard);
+
+    let value = vec![1, 2, 3];
+    let ptr = value.as_ptr();
+    let err = map.try_insert(42, value, &guard).unwrap_err();
+    assert_eq!(err.current, &vec![0]);
+    // the rejected value is moved back out, not copied
+    assert_eq!(err.not_inserted.as_ptr(), ptr);
+    assert_eq!(map.get(&42, &guard), Some(&vec![0]));
+}
+
+#[test]
+fn get_or_insert() {
+    let map = HashMap::<usize, String>::new();
+    let guard = map.guard();
+
+    assert_eq!(map.get_or_insert(42, String::from("a"), &guard), "a");
+    assert_eq!(map.get_or_insert(42, String::from("b"), &guard), "a");
+    assert_eq!(map.len(), 1);
+}
+
+#[test]
+fn get_or_insert_with() {
+    let map = HashMap::<usize, String>::new();
+    let guard = map.guard();
+
+    assert_eq!(
+        map.get_or_insert_with(42, || String::from("a"), &guard),
+        "a"
+    );
+    assert_eq!(
+        map.get_or_insert_with(42, || panic!("key is present"), &guard),
+        "a"
+    );
+    assert_eq!(map.len(), 1);
+}
+
+#[test]
+fn get_or_insert_with_key() {
+    let map = HashMap::<usize, String>::new();
+    let guard = map.guard();
+
+    assert_eq!(
+        map.get_or_insert_with_key(42, |k| k.to_string(), &guard),
+        "42"
+    );
+    assert_eq!(
+        map.get_or_insert_with_key(42, |_| panic!("key is present"), &guard),
+        "42"
+    );
+    assert_eq!(map.len(), 1);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn concurrent_get_or_insert() {
+    let map = Arc::new(HashMap::<usize, usize>::new());
+    let calls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+
+    let handles: Vec<_> = (0..4)
+        .map(|t| {
+            let map = map.clone();
+            let calls = calls.clone();
+            std::thread::spawn(move || {
+                let guard = map.guard();
+                for i in 0..256 {
+                    let v = map.get_or_insert_with(
+                        i,
+                        || {
+                            calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                            t
+                        },
+                        &guard,
+                    );
+                    // everyone must see the same value as the thread that inserted it
+                    assert_eq!(map.get_or_insert(i, usize::MAX, &guard), v);
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 256);
+    assert_eq!(map.len(), 256);
+}
+
+#[test]
+fn get_or_try_insert_with() {
+    let map = HashMap::<usize, String>::new();
+    let guard = map.guard();
+
+    // a failed construction leaves the map unchanged
+    assert_eq!(
+        map.get_or_try_insert_with(42, || Err("no such file"), &guard),
+        Err("no such file")
+    );
+    assert_eq!(map.len(), 0);
+    assert_eq!(map.get(&42, &guard), None);
+
+    assert_eq!(
+        map.get_or_try_insert_with(42, || Ok::<_, ()>(String::from("42")), &guard),
+        Ok(&String::from("42"))
+    );
+    assert_eq!(
+        map.get_or_try_insert_with(
+            42,
+            || -> Result<String, ()> { panic!("key is present") },
+            &guard
+        ),
+        Ok(&String::from("42"))
+    );
+    assert_eq!(map.len(), 1);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn concurrent_get_or_try_insert_with() {
+    let map = Arc::new(HashMap::<usize, usize>::new());
+    let calls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+
+    let handles: Vec<_> = (0..4)
+        .map(|t| {
+            let map = map.clone();
+            let calls = calls.clone();
+            std::thread::spawn(move || {
+                let guard = map.guard();
+                for i in 0..256 {
+                    let result = map.get_or_try_insert_with(
+                        i,
+                        || {
+                            calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                            // odd threads fail to construct their value
+                            if t % 2 == 0 {
+                                Ok(t)
+                            } else {
+                                Err(t)
+                            }
+                        },
+                        &guard,
+                    );
+                    if let Ok(&v) = result {
+                        // everyone must see the same value as the thread that inserted it
+                        assert_eq!(map.get(&i, &guard), Some(&v));
+                        assert_eq!(v % 2, 0);
+                    }
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    // every key was inserted by one of the threads that succeed, and once a key was present,
+    // no thread called its constructor anymore
+    assert_eq!(map.len(), 256);
+    assert!(calls.load(std::sync::atomic::Ordering::SeqCst) <= 256 * 3);
+}
+
+#[test]
+fn bulk_insert() {
+    let map = HashMap::<usize, usize>::new();
+    let guard = map.guard();
+    map.insert(0, 42, &guard);
+
+    map.bulk_insert((0..1024).map(|i| (i, i)), &guard);
+    assert_eq!(map.len(), 1024);
+    assert!(map.capacity(&guard) >= 1024);
+    for i in 0..1024 {
+        assert_eq!(map.get(&i, &guard), Some(&i));
+    }
+
+    // an iterator whose size hint is too small still ends up with a properly sized table, which
+    // it reaches in a single resize
+    let resizes = map.stats().resize_count;
+    map.bulk_insert((1024..4096).filter(|_| true).map(|i| (i, i)), &guard);
+    assert_eq!(map.len(), 4096);
+    assert!(map.capacity(&guard) >= 4096);
+    assert_eq!(map.stats().resize_count, resizes + 1);
+    for i in 0..4096 {
+        assert_eq!(map.get(&i, &guard), Some(&i));
+    }
+}
+
+#[test]
+fn bulk_insert_without_size_hint() {
+    // with no size hint, every bin of the first, small table grows long during the load, which
+    // must not make the inserts grow the table step by step
+    let map = HashMap::<usize, usize>::new();
+    let guard = map.guard();
+    map.bulk_insert((0..4096).filter(|_| true).map(|i| (i, i)), &guard);
+    assert_eq!(map.len(), 4096);
+    assert!(map.capacity(&guard) >= 4096);
+    assert_eq!(map.stats().resize_count, 1);
+    for i in 0..4096 {
+        assert_eq!(map.get(&i, &guard), Some(&i));
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn concurrent_bulk_insert() {
+    let map = Arc::new(HashMap::<usize, usize>::new());
+
+    let handles: Vec<_> = (0..4)
+        .map(|t| {
+            let map = map.clone();
+            std::thread::spawn(move || {
+                let guard = map.guard();
+                map.bulk_insert((t * 1024..(t + 1) * 1024).map(|i| (i, i)), &guard);
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    let guard = map.guard();
+    assert_eq!(map.len(), 4096);
+    for i in 0..4096 {
+        assert_eq!(map.get(&i, &guard), Some(&i));
+    }
+}
+
+#[test]
+#[should_panic(expected = "cannot access the map for the same bin from within a compute closure")]
+fn compute_if_present_reentrant() {
+    let map = HashMap::<usize, usize>::new();
+    let guard = map.guard();
+    map.insert(42, 0, &guard);
+    // inserting the key that is being computed would deadlock on the bin lock
+    map.compute_if_present(
+        &42,
+        |_, v| {
+            map.insert(42, v + 1, &guard);
+            Some(*v)
+        },
+        &guard,
+    );
+}
+
+#[test]
+#[should_panic(expected = "cannot access the map for the same bin from within a compute closure")]
+fn compute_reentrant() {
+    let map = HashMap::<usize, usize>::new();
+    let guard = map.guard();
+    map.insert(42, 0, &guard);
+    map.compute(
+        42,
+        |k, v| {
+            map.remove(k, &guard);
+            v.copied()
+        },
+        &guard,
+    );
+}
+
+#[test]
+fn compute_if_present() {
+    let map = HashMap::<usize, usize>::new();
+
+    let guard = map.guard();
+    map.insert(42, 0, &guard);
+    let new = map.compute_if_present(&42, |_, v| Some(v + 1), &guard);
+    assert_eq!(new, Some(&1));
+    {
+        let guard = map.guard();
+        let e = map.get(&42, &guard).unwrap();
+        assert_eq!(e, &1);
+    }
+}
+
+#[test]
+fn with_value_mut() {
+    let map = HashMap::<usize, Vec<usize>>::new();
+    let guard = map.guard();
+    assert_eq!(
+        map.with_value_mut(&42, |_| unreachable!(), &guard),
+        None::<()>
+    );
+
+    map.insert(42, vec![0], &guard);
+    let old = map.get(&42, &guard).unwrap();
+    assert_eq!(map.with_value_mut(&42, |v| v.push(1), &guard), Some(()));
+    // references to the old value stay valid and unchanged
+    assert_eq!(old, &[0]);
+    assert_eq!(map.get(&42, &guard), Some(&vec![0, 1]));
+    assert_eq!(map.len(), 1);
+}
+
+#[test]
+#[should_panic(expected = "cannot access the map for the same bin from within a compute closure")]
+fn with_value_mut_reentrant() {
+    let map = HashMap::<usize, usize>::new();
+    let guard = map.guard();
+    map.insert(42, 0, &guard);
+    map.with_value_mut(&42, |v| map.insert(42, *v + 1, &guard).is_some(), &guard);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn concurrent_with_value_mut() {
+    let map = Arc::new(HashMap::<usize, Vec<usize>>::new());
+    map.insert(0, Vec::new(), &map.guard());
+
+    let threads: Vec<_> = (0..4)
+        .map(|t| {
+            let map = map.clone();
+            std::thread::spawn(move || {
+                let guard = map.guard();
+                for i in 0..256 {
+                    map.with_value_mut(&0, |v| v.push(t * 256 + i), &guard);
+                }
+            })
+        })
+        .collect();
+    for t in threads {
+        t.join().unwrap();
+    }
+
+    // no update was lost
+    let guard = map.guard();
+    let mut pushed = map.get(&0, &guard).unwrap().clone();
+    pushed.sort_unstable();
+    assert_eq!(pushed, (0..1024).collect::<Vec<_>>());
+}
+
+#[test]
+fn swap() {
+    let map = HashMap::<usize, usize>::new();
+    let guard = map.guard();
+    assert!(!map.swap(&1, &2, &guard));
+
+    for i in 0..64 {
+        map.insert(i, i * 10, &guard);
+    }
+    assert!(map.swap(&1, &2, &guard));
+    assert_eq!(map.get(&1, &guard), Some(&20));
+    assert_eq!(map.get(&2, &guard), Some(&10));
+
+    // swapping a key with itself is allowed, and does nothing
+    assert!(map.swap(&3, &3, &guard));
+    assert_eq!(map.get(&3, &guard), Some(&30));
+
+    // nothing changes if either key is missing
+    assert!(!map.swap(&4, &100, &guard));
+    assert!(!map.swap(&100, &4, &guard));
+    assert!(!map.swap(&100, &100, &guard));
+    assert_eq!(map.get(&4, &guard), Some(&40));
+    assert_eq!(map.len(), 64);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn concurrent_swap() {
+    use rand::{Rng, SeedableRng};
+
+    let map = Arc::new(HashMap::<usize, usize>::new());
+    {
+        let guard = map.guard();
+        for i in 0..64 {
+            map.insert(i, i, &guard);
+        }
+    }
+
+    let mut threads: Vec<_> = (0..4)
+        .map(|t| {
+            let map = map.clone();
+            std::thread::spawn(move || {
+                let mut rng = rand::rngs::StdRng::seed_from_u64(t);
+                let guard = map.guard();
+                for _ in 0..1024 {
+                    let a = rng.gen_range(0..64);
+                    let b = rng.gen_range(0..64);
+                    assert!(map.swap(&a, &b, &guard));
+                }
+            })
+        })
+        .collect();
+    // keep growing and shrinking the table underneath the swaps
+    let map1 = map.clone();
+    threads.push(std::thread::spawn(move || {
+        let guard = map1.guard();
+        for _ in 0..4 {
+            for i in 64..2048 {
+                map1.insert(i, i, &guard);
+            }
+            for i in 64..2048 {
+                map1.remove(&i, &guard);
+            }
+            map1.shrink_to_fit(&guard);
+        }
+    }));
+    for t in threads {
+        t.join().unwrap();
+    }
+
+    // the values were only ever moved around, never lost or duplicated
+    let guard = map.guard();
+    let mut values: Vec<_> = (0..64).map(|i| *map.get(&i, &guard).unwrap()).collect();
+    values.sort_unstable();
+    assert_eq!(values, (0..64).collect::<Vec<_>>());
+}
+
+fn hash_of<K, V, S, Q>(map: &HashMap<K, V, S>, key: &Q) -> u64
+where
+    S: std::hash::BuildHasher,
+    Q: ?Sized + std::hash::Hash,
+{
+    use std::hash::Hasher;
+    let mut hasher = map.hasher().build_hasher();
+    key.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[test]
+fn raw_entry() {
+    let map = HashMap::<String, usize>::new();
+    let guard = map.guard();
+    assert_eq!(
+        map.raw_entry(hash_of(&map, "a"), &guard).from_key("a"),
+        None
+    );
+
+    map.insert(String::from("a"), 1, &guard);
+    map.insert(String::from("b"), 2, &guard);
+    let hash = hash_of(&map, "a");
+    assert_eq!(
+        map.raw_entry(hash, &guard).from_key("a"),
+        Some((&String::from("a"), &1))
+    );
+    // the closure decides which key matches, so it may compare against any representation
+    assert_eq!(
+        map.raw_entry(hash, &guard)
+            .from_match(|k| k.as_bytes() == b"a")
+            .map(|(_, v)| v),
+        Some(&1)
+    );
+    // only keys with the given hash are considered
+    assert_eq!(map.raw_entry(hash, &guard).from_key("b"), None);
+
+    // the hashes yielded by iter_hashed can be used directly
+    for (hash, key, value) in map.iter_hashed(&guard) {
+        assert_eq!(
+            map.raw_entry(hash, &guard).from_key(key),
+            Some((key, value))
+        );
+    }
+}
+
+#[test]
+fn raw_entry_mut() {
+    let map = HashMap::<String, usize>::new();
+    let guard = map.guard();
+    let hash = hash_of(&map, "a");
+
+    match map.raw_entry_mut(hash, &guard).from_key("a") {
+        RawEntryMut::Occupied(_) => panic!("map is empty"),
+        RawEntryMut::Vacant(entry) => assert_eq!(entry.insert(String::from("a"), 1), &1),
+    }
+    match map.raw_entry_mut(hash, &guard).from_key("a") {
+        RawEntryMut::Occupied(entry) => {
+            assert_eq!(entry.key(), "a");
+            assert_eq!(entry.get(), &1);
+        }
+        RawEntryMut::Vacant(_) => panic!("key was inserted"),
+    }
+
+    let value = map
+        .raw_entry_mut(hash, &guard)
+        .from_key("a")
+        .or_insert_with(|| unreachable!());
+    assert_eq!(value, &1);
+
+    let hash = hash_of(&map, "b");
+    let value = map
+        .raw_entry_mut(hash, &guard)
+        .from_key("b")
+        .or_insert_with(|| (String::from("b"), 2));
+    assert_eq!(value, &2);
+    assert_eq!(map.get("b", &guard), Some(&2));
+    assert_eq!(map.len(), 2);
+
+    // a vacant entry does not overwrite a key that was inserted after the lookup
+    let hash = hash_of(&map, "c");
+    let entry = map.raw_entry_mut(hash, &guard).from_key("c");
+    map.insert(String::from("c"), 3, &guard);
+    assert_eq!(entry.or_insert(String::from("c"), 4), &3);
+    assert_eq!(map.get("c", &guard), Some(&3));
+    assert_eq!(map.len(), 3);
+}
+
+#[test]
+fn raw_entry_ref() {
+    let map = HashMap::<usize, usize>::new();
+    let mref = map.pin();
+    let hash = hash_of(&map, &1);
+    assert_eq!(mref.raw_entry_mut(hash).from_key(&1).or_insert(1, 10), &10);
+    assert_eq!(mref.raw_entry(hash).from_key(&1), Some((&1, &10)));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn concurrent_raw_entry_mut() {
+    let map = Arc::new(HashMap::<usize, usize>::new());
+    let handles: Vec<_> = (0..4)
+        .map(|t| {
+            let map = map.clone();
+            std::thread::spawn(move || {
+                let guard = map.guard();
+                for i in 0..512 {
+                    let hash = hash_of(&map, &i);
+                    map.raw_entry_mut(hash, &guard).from_key(&i).or_insert(i, t);
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    let guard = map.guard();
+    assert_eq!(map.len(), 512);
+    for i in 0..512 {
+        assert!(*map.get(&i, &guard).unwrap() < 4);
+    }
+}
+
+#[test]
+fn compute_if_present_empty() {
+    let map = HashMap::<usize, usize>::new();
+
+    let guard = map.guard();
+    let new = map.compute_if_present(&42, |_, v| Some(v + 1), &guard);
+    assert!(new.is_none());
+    {
+        let guard = map.guard();
+        assert!(map.get(&42, &guard).is_none());
+    }
+}
+
+#[test]
+fn compute_if_present_remove() {
+    let map = HashMap::<usize, usize>::new();
+
+    let guard = map.guard();
+    map.insert(42, 0, &guard);
+    let new = map.compute_if_present(&42, |_, _| None, &guard);
+    assert!(new.is_none());
+    {
+        let guard = map.guard();
+        assert!(map.get(&42, &guard).is_none());
+    }
+}
+
+#[test]
+fn update_value() {
+    let map = HashMap::<usize, Vec<usize>>::new();
+
+    let guard = map.guard();
+    assert_eq!(map.update(&42, |_| unreachable!(), &guard), None);
+    assert!(map.is_empty());
+
+    map.insert(42, vec![0], &guard);
+    let new = map.update(&42, |v| v.iter().map(|x| x + 1).collect(), &guard);
+    assert_eq!(new, Some(&vec![1]));
+    assert_eq!(map.get(&42, &guard), Some(&vec![1]));
+    assert_eq!(map.len(), 1);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn concurrent_update_value() {
+    let map = Arc::new(HashMap::<usize, usize>::new());
+    {
+        let guard = map.guard();
+        for i in 0..64 {
+            map.insert(i, 0, &guard);
+        }
+    }
+
+    let threads: Vec<_> = (0..4)
+        .map(|_| {
+            let map = Arc::clone(&map);
+            std::thread::spawn(move || {
+                let guard = map.guard();
+                for _ in 0..100 {
+                    for i in 0..64 {
+                        assert!(map.update(&i, |v| v + 1, &guard).is_some());
+                    }
+                }
+            })
+        })
+        .collect();
+    for t in threads {
+        t.join().unwrap();
+    }
+
+    // no increments were lost
+    let guard = map.guard();
+    for i in 0..64 {
+        assert_eq!(map.get(&i, &guard), Some(&400));
+    }
+}
+
+#[test]
+fn compute_if_absent() {
+    let map = HashMap::<usize, usize>::new();
+
+    let guard = map.guard();
+    assert_eq!(map.compute_if_absent(42, |k| k + 1, &guard), &43);
+    {
+        let guard = map.guard();
+        let e = map.get(&42, &guard).unwrap();
+        assert_eq!(e, &43);
+    }
+}
+
+#[test]
+fn compute_if_absent_present() {
+    let map = HashMap::<usize, usize>::new();
+
+    let guard = map.guard();
+    map.insert(42, 0, &guard);
+    let v = map.compute_if_absent(42, |_| panic!("key is present"), &guard);
+    assert_eq!(v, &0);
+    assert_eq!(map.len(), 1);
+}
+
+#[test]
+fn compute_if_absent_many() {
+    let map = HashMap::<usize, usize>::new();
+
+    let guard = map.guard();
+    // enough keys to share bins and to force the table to resize
+    for i in 0..256 {
+        assert_eq!(map.compute_if_absent(i, |k| k * 2, &guard), &(i * 2));
+    }
+    for i in 0..256 {
+        assert_eq!(map.compute_if_absent(i, |_| 0, &guard), &(i * 2));
+    }
+    assert_eq!(map.len(), 256);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn compute_if_absent_panic() {
+    let map = HashMap::<usize, usize>::new();
+
+    let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        map.compute_if_absent(42, |_| panic!("oops"), &map.guard());
+    }));
+    assert!(res.is_err());
+
+    // the bin is released again, and nothing was inserted
+    let guard = map.guard();
+    assert!(map.get(&42, &guard).is_none());
+    assert_eq!(map.compute_if_absent(42, |_| 1, &guard), &1);
+    assert_eq!(map.len(), 1);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn concurrent_compute_if_absent() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let map = Arc::new(HashMap::<usize, usize>::new());
+    let calls = Arc::new(AtomicUsize::new(0));
+
+    let mut handles = Vec::new();
+    for t in 0..4 {
+        let map = map.clone();
+        let calls = calls.clone();
+        handles.push(std::thread::spawn(move || {
+            let guard = map.guard();
+            (0..64)
+                .map(|i| {
+                    *map.compute_if_absent(
+                        i,
+                        |_| {
+                            calls.fetch_add(1, Ordering::SeqCst);
+                            t
+                        },
+                        &guard,
+                    )
+                })
+                .collect::<Vec<_>>()
+        }));
+    }
+    let values: Vec<Vec<usize>> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+
+    // every key was computed exactly once, and all threads saw the computed value
+    assert_eq!(calls.load(Ordering::SeqCst), 64);
+    assert!(values.windows(2).all(|w| w[0] == w[1]));
+    assert_eq!(map.len(), 64);
+}
+
+#[test]
+fn compute() {
+    let map = HashMap::<usize, usize>::new();
+
+    let guard = map.guard();
+    // absent -> inserted
+    let new = map.compute(42, |_, v| Some(v.map_or(0, |v| v + 1)), &guard);
+    assert_eq!(new, Some(&0));
+    // present -> updated
+    let new = map.compute(42, |_, v| Some(v.map_or(0, |v| v + 1)), &guard);
+    assert_eq!(new, Some(&1));
+    {
+        let guard = map.guard();
+        let e = map.get(&42, &guard).unwrap();
+        assert_eq!(e, &1);
+    }
+}
+
+#[test]
+fn compute_remove() {
+    let map = HashMap::<usize, usize>::new();
+
+    let guard = map.guard();
+    // absent -> stays absent
+    assert!(map.compute(42, |_, _| None, &guard).is_none());
+    assert!(map.is_empty());
+
+    // present -> removed
+    map.insert(42, 0, &guard);
+    let new = map.compute(
+        42,
+        |_, v| {
+            assert_eq!(v, Some(&0));
+            None
+        },
+        &guard,
+    );
+    assert!(new.is_none());
+    {
+        let guard = map.guard();
+        assert!(map.get(&42, &guard).is_none());
+    }
+    assert!(map.is_empty());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn concurrent_compute() {
+    let map = Arc::new(HashMap::<usize, usize>::new());
+
+    let mut handles = Vec::new();
+    for _ in 0..4 {
+        let map = map.clone();
+        handles.push(std::thread::spawn(move || {
+            let guard = map.guard();
+            for _ in 0..100 {
+                for i in 0..16 {
+                    map.compute(i, |_, v| Some(v.map_or(1, |v| v + 1)), &guard);
+                }
+            }
+        }));
+    }
+    for h in handles {
+        h.join().unwrap();
+    }
+
+    // no increment was lost
+    let guard = map.guard();
+    for i in 0..16 {
+        assert_eq!(map.get(&i, &guard), Some(&400));
+    }
+}
+
+#[test]
+fn merge() {
+    let map = HashMap::<usize, usize>::new();
+
+    let guard = map.guard();
+    assert_eq!(
+        map.merge(42, 1, |_, _| panic!("key is absent"), &guard),
+        Some(&1)
+    );
+    assert_eq!(
+        map.merge(42, 2, |old, new| Some(old + new), &guard),
+        Some(&3)
+    );
+    {
+        let guard = map.guard();
+        assert_eq!(map.get(&42, &guard), Some(&3));
+    }
+    assert_eq!(map.merge(42, 0, |_, _| None, &guard), None);
+    assert!(map.get(&42, &guard).is_none());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn concurrent_merge() {
+    let map = Arc::new(HashMap::<usize, usize>::new());
+
+    let mut handles = Vec::new();
+    for _ in 0..4 {
+        let map = map.clone();
+        handles.push(std::thread::spawn(move || {
+            let guard = map.guard();
+            for _ in 0..100 {
+                for i in 0..16 {
+                    map.merge(i, 1, |old, new| Some(old + new), &guard);
+                }
+            }
+        }));
+    }
+    for h in handles {
+        h.join().unwrap();
+    }
+
+    let guard = map.guard();
+    for i in 0..16 {
+        assert_eq!(map.get(&i, &guard), Some(&400));
+    }
+}
+
+#[test]
+fn entry_or_insert() {
+    let map = HashMap::<usize, usize>::new();
+
+    let guard = map.guard();
+    assert_eq!(map.entry(42, &guard).or_insert(0), &0);
+    assert_eq!(map.entry(42, &guard).or_insert(1), &0);
+    assert_eq!(map.len(), 1);
+}
+
+#[test]
+fn entry_or_insert_with() {
+    let map = HashMap::<usize, Vec<usize>>::new();
+
+    let guard = map.guard();
+    assert_eq!(map.entry(42, &guard).or_insert_with(|| vec![1]), &vec![1]);
+    let v = map
+        .entry(42, &guard)
+        .or_insert_with(|| panic!("key is present"));
+    assert_eq!(v, &vec![1]);
+    assert_eq!(map.entry(43, &guard).or_default(), &Vec::new());
+}
+
+#[test]
+fn entry_and_modify() {
+    let map = HashMap::<usize, usize>::new();
+
+    let guard = map.guard();
+    let v = map.entry(42, &guard).and_modify(|_, v| v + 1).or_insert(0);
+    assert_eq!(v, &0);
+    let v = map.entry(42, &guard).and_modify(|_, v| v + 1).or_insert(0);
+    assert_eq!(v, &1);
+    assert_eq!(map.get(&42, &guard), Some(&1));
+}
+
+#[test]
+fn entry_variants() {
+    let map = HashMap::<usize, usize>::new();
+
+    let guard = map.guard();
+    match map.entry(42, &guard) {
+        Entry::Vacant(e) => assert_eq!(e.insert(1), &1),
+        Entry::Occupied(_) => panic!("entry should be vacant"),
+    }
+    match map.entry(42, &guard) {
+        Entry::Occupied(e) => {
+            assert_eq!(e.key(), &42);
+            assert_eq!(e.get(), &1);
+        }
+        Entry::Vacant(_) => panic!("entry should be occupied"),
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn concurrent_entry() {
+    let map = Arc::new(HashMap::<usize, usize>::new());
+
+    let mut handles = Vec::new();
+    for t in 0..4 {
+        let map = map.clone();
+        handles.push(std::thread::spawn(move || {
+            let guard = map.guard();
+            for i in 0..64 {
+                map.entry(i, &guard).or_insert(t);
+            }
+        }));
+    }
+    let values: Vec<Vec<usize>> = handles
+        .into_iter()
+        .map(|h| {
+            h.join().unwrap();
+            let guard = map.guard();
+            (0..64).map(|i| *map.get(&i, &guard).unwrap()).collect()
+        })
+        .collect();
+
+    // once inserted, the value of a key never changes
+    assert!(values.windows(2).all(|w| w[0] == w[1]));
+    assert_eq!(map.len(), 64);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn concurrent_insert() {
+    let map = Arc::new(HashMap::<usize, usize>::new());
+
+    let map1 = map.clone();
+    let t1 = std::thread::spawn(move || {
+        for i in 0..64 {
+            map1.insert(i, 0, &map1.guard());
+        }
+    });
+    let map2 = map.clone();
+    let t2 = std::thread::spawn(move || {
+        for i in 0..64 {
+            map2.insert(i, 1, &map2.guard());
+        }
+    });
+
+    t1.join().unwrap();
+    t2.join().unwrap();
+
+    let guard = map.guard();
+    for i in 0..64 {
+        let v = map.get(&i, &guard).unwrap();
+        assert!(v == &0 || v == &1);
+
+        let kv = map.get_key_value(&i, &guard).unwrap();
+        assert!(kv == (&i, &0) || kv == (&i, &1));
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn concurrent_replace_and_remove() {
+    let map = Arc::new(HashMap::<usize, usize>::new());
+
+    {
+        let guard = map.guard();
+        for i in 0..64 {
+            map.insert(i, i, &guard);
+        }
+    }
+
+    let map1 = map.clone();
+    let t1 = std::thread::spawn(move || {
+        let guard = map1.guard();
+        for i in 0..64 {
+            map1.remove(&i, &guard);
+        }
+    });
+    let map2 = map.clone();
+    let t2 = std::thread::spawn(move || {
+        let guard = map2.guard();
+        for i in 0..64 {
+            if let Some(v) = map2.replace(&i, i + 1, &guard) {
+                assert_eq!(v, &i);
+            }
+        }
+    });
+
+    t1.join().unwrap();
+    t2.join().unwrap();
+
+    // replace must never resurrect a removed key
+    assert!(map.is_empty());
 }
 
 #[test]
-fn update() {
-    let map = HashMap::<usize, usize>::new();
-
-    let guard = map.guard();
-    map.insert(42, 0, &guard);
-    let old = map.insert(42, 1, &guard);
-    assert_eq!(old, Some(&0));
-    {
-        let guard = map.guard();
-        let e = map.get(&42, &guard).unwrap();
-        assert_eq!(e, &1);
+#[cfg_attr(miri, ignore)]
+fn concurrent_replace_if_eq() {
+    let map = Arc::new(HashMap::<usize, usize>::new());
+    map.pin().insert(0, 0);
+
+    // every thread increments the counter with a CAS loop, so no increment may be lost
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let map = map.clone();
+            std::thread::spawn(move || {
+                let guard = map.guard();
+                for _ in 0..256 {
+                    let mut current = *map.get(&0, &guard).unwrap();
+                    while map
+                        .replace_if_eq(&0, &current, current + 1, &guard)
+                        .is_err()
+                    {
+                        current = *map.get(&0, &guard).unwrap();
+                    }
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
     }
+
+    assert_eq!(map.pin().get(&0), Some(&1024));
 }
 
 #[test]
-fn compute_if_present() {
-    let map = HashMap::<usize, usize>::new();
-
-    let guard = map.guard();
-    map.insert(42, 0, &guard);
-    let new = map.compute_if_present(&42, |_, v| Some(v + 1), &guard);
-    assert_eq!(new, Some(&1));
+#[cfg_attr(miri, ignore)]
+fn concurrent_drain() {
+    let map = Arc::new(HashMap::<usize, usize>::new());
     {
         let guard = map.guard();
-        let e = map.get(&42, &guard).unwrap();
-        assert_eq!(e, &1);
+        for i in 0..1024 {
+            map.insert(i, i, &guard);
+        }
     }
-}
 
-#[test]
-fn compute_if_present_empty() {
-    let map = HashMap::<usize, usize>::new();
+    // two threads draining at once must never yield the same entry twice
+    let drainers: Vec<_> = (0..2)
+        .map(|_| {
+            let map = map.clone();
+            std::thread::spawn(move || {
+                let guard = map.guard();
+                map.drain(&guard).map(|(&k, _)| k).collect::<Vec<_>>()
+            })
+        })
+        .collect();
+    let mut drained: Vec<_> = drainers
+        .into_iter()
+        .flat_map(|t| t.join().unwrap())
+        .collect();
 
-    let guard = map.guard();
-    let new = map.compute_if_present(&42, |_, v| Some(v + 1), &guard);
-    assert!(new.is_none());
-    {
-        let guard = map.guard();
-        assert!(map.get(&42, &guard).is_none());
-    }
+    drained.sort_unstable();
+    assert_eq!(drained, (0..1024).collect::<Vec<_>>());
+    assert!(map.is_empty());
 }
 
 #[test]
-fn compute_if_present_remove() {
-    let map = HashMap::<usize, usize>::new();
+#[cfg_attr(miri, ignore)]
+fn concurrent_remove_if() {
+    let map = Arc::new(HashMap::<usize, usize>::new());
 
-    let guard = map.guard();
-    map.insert(42, 0, &guard);
-    let new = map.compute_if_present(&42, |_, _| None, &guard);
-    assert!(new.is_none());
     {
         let guard = map.guard();
-        assert!(map.get(&42, &guard).is_none());
+        for i in 0..64 {
+            map.insert(i, 0, &guard);
+        }
     }
-}
-
-#[test]
-#[cfg_attr(miri, ignore)]
-fn concurrent_insert() {
-    let map = Arc::new(HashMap::<usize, usize>::new());
 
+    // t1 keeps bumping the values, t2 only removes entries it saw at exactly 1
     let map1 = map.clone();
     let t1 = std::thread::spawn(move || {
+        let guard = map1.guard();
         for i in 0..64 {
-            map1.insert(i, 0, &map1.guard());
+            for _ in 0..3 {
+                map1.compute_if_present(&i, |_, v| Some(v + 1), &guard);
+            }
         }
     });
     let map2 = map.clone();
     let t2 = std::thread::spawn(move || {
+        let guard = map2.guard();
+        let mut removed = 0;
         for i in 0..64 {
-            map2.insert(i, 1, &map2.guard());
+            if let Some(v) = map2.remove_if(&i, |_, v| *v == 1, &guard) {
+                assert_eq!(v, &1);
+                removed += 1;
+            }
         }
+        removed
     });
 
     t1.join().unwrap();
-    t2.join().unwrap();
+    let removed = t2.join().unwrap();
 
     let guard = map.guard();
+    assert_eq!(map.len(), 64 - removed);
     for i in 0..64 {
-        let v = map.get(&i, &guard).unwrap();
-        assert!(v == &0 || v == &1);
-
-        let kv = map.get_key_value(&i, &guard).unwrap();
-        assert!(kv == (&i, &0) || kv == (&i, &1));
+        if let Some(v) = map.get(&i, &guard) {
+            assert_eq!(v, &3);
+        }
     }
 }
 
@@ -362,6 +2235,156 @@ fn concurrent_resize_and_get() {
     }
 }
 
+#[test]
+#[cfg_attr(miri, ignore)]
+fn concurrent_resize_and_contains_value() {
+    let map = Arc::new(HashMap::<usize, usize>::new());
+    {
+        let guard = map.guard();
+        for i in 0..64 {
+            map.insert(i, i, &guard);
+        }
+    }
+
+    let map1 = map.clone();
+    // t1 triggers a bunch of resizes
+    let t1 = std::thread::spawn(move || {
+        let guard = map1.guard();
+        for i in 64..(1 << 14) {
+            map1.insert(i, i, &guard);
+        }
+    });
+    let map2 = map.clone();
+    // t2 keeps looking for existing values, which must be found through any Moved bins
+    let t2 = std::thread::spawn(move || {
+        let guard = map2.guard();
+        for _ in 0..32 {
+            for i in 0..64 {
+                assert!(map2.contains_value(&i, &guard));
+            }
+        }
+    });
+
+    t1.join().unwrap();
+    t2.join().unwrap();
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn concurrent_resize_eager() {
+    let map = Arc::new(HashMap::<usize, usize>::new());
+    {
+        let guard = map.guard();
+        for i in 0..1024 {
+            map.insert(i, i, &guard);
+        }
+    }
+
+    let map1 = map.clone();
+    // t1 keeps moving the whole map between a small and a large table
+    let t1 = std::thread::spawn(move || {
+        let guard = map1.guard();
+        for _ in 0..8 {
+            map1.resize_eager(1 << 14, &guard);
+            map1.resize_eager(0, &guard);
+        }
+    });
+    let map2 = map.clone();
+    // t2 keeps inserting new keys, which may also grow the table
+    let t2 = std::thread::spawn(move || {
+        let guard = map2.guard();
+        for i in 1024..4096 {
+            map2.insert(i, i, &guard);
+        }
+    });
+    let map3 = map.clone();
+    // t3 retrieves existing keys while their bins are moved
+    let t3 = std::thread::spawn(move || {
+        let guard = map3.guard();
+        for _ in 0..32 {
+            for i in 0..1024 {
+                assert_eq!(map3.get(&i, &guard), Some(&i));
+            }
+        }
+    });
+    t1.join().unwrap();
+    t2.join().unwrap();
+    t3.join().unwrap();
+
+    let guard = map.guard();
+    assert_eq!(map.len(), 4096);
+    for i in 0..4096 {
+        assert_eq!(map.get(&i, &guard), Some(&i));
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn concurrent_shrink_and_get() {
+    let map = Arc::new(HashMap::<usize, usize>::new());
+    {
+        let guard = map.guard();
+        for i in 0..1024 {
+            map.insert(i, i, &guard);
+        }
+    }
+
+    let map1 = map.clone();
+    // t1 keeps growing and shrinking the table
+    let t1 = std::thread::spawn(move || {
+        let guard = map1.guard();
+        for _ in 0..8 {
+            map1.reserve(1 << 14, &guard);
+            map1.shrink_to_fit(&guard);
+        }
+    });
+    let map2 = map.clone();
+    // t2 keeps inserting new keys while the table is resized
+    let t2 = std::thread::spawn(move || {
+        let guard = map2.guard();
+        for i in 1024..4096 {
+            map2.insert(i, i, &guard);
+        }
+    });
+    let map3 = map.clone();
+    // t3 is retrieving existing keys a lot, attempting to encounter a BinEntry::Moved
+    let t3 = std::thread::spawn(move || {
+        let guard = map3.guard();
+        for _ in 0..32 {
+            for i in 0..1024 {
+                let v = map3.get(&i, &guard).unwrap();
+                assert_eq!(v, &i);
+            }
+        }
+    });
+    let map4 = map.clone();
+    // t4 iterates over the map, and must see each of the original keys exactly once
+    let t4 = std::thread::spawn(move || {
+        for _ in 0..32 {
+            let guard = map4.guard();
+            let mut seen = std::collections::HashSet::new();
+            for (&k, &v) in map4.iter(&guard) {
+                assert_eq!(k, v);
+                assert!(seen.insert(k), "key {} was yielded twice", k);
+            }
+            assert!((0..1024).all(|i| seen.contains(&i)));
+        }
+    });
+
+    t1.join().unwrap();
+    t2.join().unwrap();
+    t3.join().unwrap();
+    t4.join().unwrap();
+
+    // make sure all the entries still exist after all the resizes
+    let guard = map.guard();
+    assert_eq!(map.len(), 4096);
+    for i in 0..4096 {
+        let v = map.get(&i, &guard).unwrap();
+        assert_eq!(v, &i);
+    }
+}
+
 #[test]
 fn current_kv_dropped() {
     let dropped1 = Arc::new(0);
@@ -381,57 +2404,246 @@ fn current_kv_dropped() {
 }
 
 #[test]
-fn empty_maps_equal() {
-    let map1 = HashMap::<usize, usize>::new();
-    let map2 = HashMap::<usize, usize>::new();
-    assert_eq!(map1, map2);
-    assert_eq!(map2, map1);
+fn empty_maps_equal() {
+    let map1 = HashMap::<usize, usize>::new();
+    let map2 = HashMap::<usize, usize>::new();
+    assert_eq!(map1, map2);
+    assert_eq!(map2, map1);
+}
+
+#[test]
+fn different_size_maps_not_equal() {
+    let map1 = HashMap::<usize, usize>::new();
+    let map2 = HashMap::<usize, usize>::new();
+    {
+        let guard1 = map1.guard();
+        let guard2 = map2.guard();
+
+        map1.insert(1, 0, &guard1);
+        map1.insert(2, 0, &guard1);
+        map1.insert(3, 0, &guard1);
+
+        map2.insert(1, 0, &guard2);
+        map2.insert(2, 0, &guard2);
+    }
+
+    assert_ne!(map1, map2);
+    assert_ne!(map2, map1);
+}
+
+#[test]
+fn same_values_equal() {
+    let map1 = HashMap::<usize, usize>::new();
+    let map2 = HashMap::<usize, usize>::new();
+    {
+        map1.pin().insert(1, 0);
+        map2.pin().insert(1, 0);
+    }
+
+    assert_eq!(map1, map2);
+    assert_eq!(map2, map1);
+}
+
+#[test]
+fn different_values_not_equal() {
+    let map1 = HashMap::<usize, usize>::new();
+    let map2 = HashMap::<usize, usize>::new();
+    {
+        map1.pin().insert(1, 0);
+        map2.pin().insert(1, 1);
+    }
+
+    assert_ne!(map1, map2);
+    assert_ne!(map2, map1);
+}
+
+#[test]
+fn insert_cooperative() {
+    let map = HashMap::<usize, usize>::new();
+    let guard = map.guard();
+
+    let mut resizes = 0;
+    for i in 0..1024 {
+        let (old, helped) = map.insert_cooperative(i, i, &guard);
+        assert_eq!(old, None);
+        if helped {
+            resizes += 1;
+        }
+    }
+    assert_eq!(map.len(), 1024);
+    // every resize happened on this thread, during one of the inserts
+    assert_eq!(resizes, map.stats().resize_count);
+
+    // replacing existing keys never grows the map
+    for i in 0..1024 {
+        assert_eq!(map.insert_cooperative(i, i + 1, &guard), (Some(&i), false));
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn concurrent_insert_cooperative() {
+    let map = Arc::new(HashMap::<usize, usize>::new());
+    let threads: Vec<_> = (0..4)
+        .map(|t| {
+            let map = Arc::clone(&map);
+            std::thread::spawn(move || {
+                let guard = map.guard();
+                for i in 0..1024 {
+                    let key = t * 1024 + i;
+                    if map.insert_cooperative(key, key, &guard).1 {
+                        std::thread::yield_now();
+                    }
+                }
+            })
+        })
+        .collect();
+    for t in threads {
+        t.join().unwrap();
+    }
+
+    let guard = map.guard();
+    assert_eq!(map.len(), 4096);
+    for key in 0..4096 {
+        assert_eq!(map.get(&key, &guard), Some(&key));
+    }
+}
+
+#[test]
+fn std_eq() {
+    use std::collections::hash_map::RandomState;
+
+    let map = HashMap::<usize, usize>::new();
+    let mut std_map = std::collections::HashMap::with_hasher(RandomState::new());
+    assert_eq!(map, std_map);
+    assert_eq!(std_map, map);
+
+    for i in 0..100 {
+        map.pin().insert(i, i * 2);
+        std_map.insert(i, i * 2);
+    }
+    // the maps use different hashers, so only their contents are compared
+    assert_eq!(map, std_map);
+    assert_eq!(std_map, map);
+    assert_eq!(map.pin(), std_map);
+    assert_eq!(std_map, map.pin());
+
+    std_map.insert(0, 1);
+    assert_ne!(map, std_map);
+    assert_ne!(std_map, map.pin());
+    std_map.insert(0, 0);
+    std_map.insert(100, 200);
+    assert_ne!(map, std_map);
+    assert_ne!(map.pin(), std_map);
+}
+
+#[test]
+fn shared_collector_outlives_map() {
+    let collector = Arc::new(Collector::new());
+    let a = HashMap::<usize, Arc<()>>::new().with_shared_collector(Arc::clone(&collector));
+    let b = HashMap::<usize, usize>::new().with_shared_collector(Arc::clone(&collector));
+
+    let value = Arc::new(());
+    {
+        let guard = collector.enter();
+        a.insert(1, Arc::clone(&value), &guard);
+        a.remove(&1, &guard);
+        b.insert(1, 1, &guard);
+    }
+    // the removed value may still be waiting in the shared collector
+    drop(a);
+    assert_eq!(b.get(&1, &collector.enter()), Some(&1));
+
+    // it is freed at the latest when the last map using the collector goes away
+    drop(b);
+    drop(collector);
+    assert_eq!(Arc::strong_count(&value), 1);
+}
+
+#[test]
+#[should_panic]
+fn shared_collector_wrong_guard() {
+    let collector = Arc::new(Collector::new());
+    let map = HashMap::<usize, usize>::new().with_shared_collector(collector);
+    let other = HashMap::<usize, usize>::new();
+    map.insert(1, 1, &other.guard());
 }
 
 #[test]
-fn different_size_maps_not_equal() {
-    let map1 = HashMap::<usize, usize>::new();
-    let map2 = HashMap::<usize, usize>::new();
-    {
-        let guard1 = map1.guard();
-        let guard2 = map2.guard();
+#[cfg_attr(miri, ignore)]
+fn drop_runs_deferred_destructors() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static DROPPED: AtomicUsize = AtomicUsize::new(0);
+    struct Flush;
+    impl Drop for Flush {
+        fn drop(&mut self) {
+            DROPPED.fetch_add(1, Ordering::SeqCst);
+        }
+    }
 
-        map1.insert(1, 0, &guard1);
-        map1.insert(2, 0, &guard1);
-        map1.insert(3, 0, &guard1);
+    let map = Arc::new(HashMap::<usize, Flush>::new());
+    {
+        let guard = map.guard();
+        for i in 0..64 {
+            map.insert(i, Flush, &guard);
+        }
+    }
 
-        map2.insert(1, 0, &guard2);
-        map2.insert(2, 0, &guard2);
+    // remove and replace values from several threads, so that garbage is left behind in the
+    // batches of threads that have since exited
+    let threads: Vec<_> = (0..4)
+        .map(|t| {
+            let map = map.clone();
+            std::thread::spawn(move || {
+                let guard = map.guard();
+                for i in (t..64).step_by(4) {
+                    if i % 2 == 0 {
+                        map.remove(&i, &guard);
+                    } else {
+                        map.insert(i, Flush, &guard);
+                    }
+                }
+            })
+        })
+        .collect();
+    for t in threads {
+        t.join().unwrap();
     }
 
-    assert_ne!(map1, map2);
-    assert_ne!(map2, map1);
+    // 32 values were removed and 32 replaced, leaving 64 values garbage and 32 in the map
+    let map = Arc::try_unwrap(map).ok().unwrap();
+    assert_eq!(map.len(), 32);
+    drop(map);
+    assert_eq!(DROPPED.load(Ordering::SeqCst), 96);
 }
 
 #[test]
-fn same_values_equal() {
+fn guarded_eq() {
     let map1 = HashMap::<usize, usize>::new();
     let map2 = HashMap::<usize, usize>::new();
-    {
-        map1.pin().insert(1, 0);
-        map2.pin().insert(1, 0);
-    }
-
-    assert_eq!(map1, map2);
-    assert_eq!(map2, map1);
+    let guard1 = map1.guard();
+    let guard2 = map2.guard();
+    assert!(map1.guarded_eq(&map2, &guard1, &guard2));
+
+    map1.insert(1, 0, &guard1);
+    assert!(!map1.guarded_eq(&map2, &guard1, &guard2));
+    map2.insert(1, 1, &guard2);
+    assert!(!map1.guarded_eq(&map2, &guard1, &guard2));
+    map2.insert(1, 0, &guard2);
+    assert!(map1.guarded_eq(&map2, &guard1, &guard2));
+    assert!(map2.guarded_eq(&map1, &guard2, &guard1));
 }
 
 #[test]
-fn different_values_not_equal() {
+#[should_panic]
+fn guarded_eq_wrong_guard() {
     let map1 = HashMap::<usize, usize>::new();
     let map2 = HashMap::<usize, usize>::new();
-    {
-        map1.pin().insert(1, 0);
-        map2.pin().insert(1, 1);
-    }
-
-    assert_ne!(map1, map2);
-    assert_ne!(map2, map1);
+    let guard1 = map1.guard();
+    let guard2 = map2.guard();
+    // the guards are swapped
+    map1.guarded_eq(&map2, &guard2, &guard1);
 }
 
 #[test]
@@ -482,6 +2694,17 @@ fn clone_map_filled() {
     assert_ne!(&map, &cloned_map);
 }
 
+#[test]
+fn clone_reserves_len() {
+    let map: HashMap<usize, usize> = (0..1000).map(|i| (i, i)).collect();
+    let cloned_map = map.clone();
+    let stats = cloned_map.stats();
+    assert_eq!(stats.len, 1000);
+    assert!(stats.capacity >= 1000);
+    // copying the entries did not have to grow the table
+    assert_eq!(stats.resize_count, 0);
+}
+
 #[test]
 fn default() {
     let map: HashMap<usize, usize> = Default::default();
@@ -514,7 +2737,7 @@ fn extend() {
     let mut entries: Vec<(usize, usize)> = vec![(42, 0), (16, 6), (38, 42)];
     entries.sort_unstable();
 
-    (&map).extend(entries.clone().into_iter());
+    (&map).extend(entries.clone());
 
     let mut collected: Vec<(usize, usize)> = map
         .iter(&guard)
@@ -532,7 +2755,7 @@ fn extend_ref() {
     let mut entries: Vec<(&usize, &usize)> = vec![(&42, &0), (&16, &6), (&38, &42)];
     entries.sort();
 
-    (&map).extend(entries.clone().into_iter());
+    (&map).extend(entries.clone());
 
     let guard = map.guard();
     let mut collected: Vec<(&usize, &usize)> = map.iter(&guard).collect();
@@ -548,13 +2771,472 @@ fn from_iter_ref() {
     let mut entries: Vec<(&usize, &usize)> = vec![(&42, &0), (&16, &6), (&38, &42)];
     entries.sort();
 
-    let map: HashMap<usize, usize> = HashMap::from_iter(entries.clone().into_iter());
+    let map: HashMap<usize, usize> = HashMap::from_iter(entries.clone());
 
     let guard = map.guard();
     let mut collected: Vec<(&usize, &usize)> = map.iter(&guard).collect();
     collected.sort();
 
-    assert_eq!(entries, entries)
+    assert_eq!(entries, collected)
+}
+
+#[test]
+fn from_iter_duplicates() {
+    let map: HashMap<usize, usize> = vec![(42, 0), (16, 6), (42, 1)].into_iter().collect();
+
+    // the last value for a key wins, like with repeated calls to `insert`
+    let guard = map.guard();
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.get(&42, &guard), Some(&1));
+    assert_eq!(map.get(&16, &guard), Some(&6));
+}
+
+#[test]
+fn from_array() {
+    let map = HashMap::<usize, usize>::from([(42, 0), (16, 6), (42, 1)]);
+
+    let guard = map.guard();
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.get(&42, &guard), Some(&1));
+    assert_eq!(map.get(&16, &guard), Some(&6));
+    assert!(HashMap::<usize, usize>::from([]).is_empty());
+}
+
+#[test]
+fn into_iter() {
+    let map: HashMap<usize, String> = (0..100).map(|i| (i, i.to_string())).collect();
+
+    let mut entries: Vec<(usize, String)> = map.into_iter().collect();
+    entries.sort_unstable();
+    assert_eq!(
+        entries,
+        (0..100).map(|i| (i, i.to_string())).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn into_iter_empty() {
+    let map = HashMap::<usize, usize>::new();
+    assert_eq!(map.into_iter().count(), 0);
+}
+
+#[test]
+fn into_std() {
+    let map = HashMap::<usize, String>::new();
+    {
+        let guard = map.guard();
+        for i in 0..100 {
+            map.insert(i, i.to_string(), &guard);
+        }
+    }
+
+    let map = map.into_std();
+    assert_eq!(map.len(), 100);
+    for i in 0..100 {
+        assert_eq!(map[&i], i.to_string());
+    }
+}
+
+#[test]
+fn from_std() {
+    use std::hash::BuildHasherDefault;
+
+    // the values are moved into the map, so they are not cloned
+    let value = Arc::new(());
+    let mut std_map = std::collections::HashMap::with_hasher(BuildHasherDefault::<
+        std::collections::hash_map::DefaultHasher,
+    >::default());
+    for i in 0..100 {
+        std_map.insert(i, Arc::clone(&value));
+    }
+
+    let map = HashMap::from(std_map);
+    assert_eq!(map.len(), 100);
+    assert!(map.capacity(&map.guard()) >= 100);
+    assert_eq!(Arc::strong_count(&value), 101);
+    let guard = map.guard();
+    for i in 0..100 {
+        assert!(Arc::ptr_eq(map.get(&i, &guard).unwrap(), &value));
+    }
+
+    // and converting back yields the same map
+    drop(guard);
+    let std_map = map.into_std();
+    assert_eq!(std_map.len(), 100);
+    assert_eq!(Arc::strong_count(&value), 101);
+}
+
+#[test]
+fn to_std() {
+    let map = HashMap::<usize, String>::new();
+    let guard = map.guard();
+    for i in 0..100 {
+        map.insert(i, i.to_string(), &guard);
+    }
+
+    let copy = map.to_std(&guard);
+    assert_eq!(copy.len(), 100);
+    for i in 0..100 {
+        assert_eq!(copy[&i], i.to_string());
+        assert_eq!(map.get(&i, &guard), Some(&i.to_string()));
+    }
+}
+
+#[test]
+fn freeze() {
+    let map = HashMap::<usize, String>::new();
+    let guard = map.guard();
+    for i in 0..100 {
+        map.insert(i, i.to_string(), &guard);
+    }
+
+    let frozen = map.freeze(&guard);
+    // later changes to the source are not visible through the frozen copy
+    map.insert(0, String::from("changed"), &guard);
+    map.insert(100, String::from("new"), &guard);
+    drop(guard);
+
+    assert_eq!(frozen.len(), 100);
+    assert_eq!(frozen.iter().len(), 100);
+    for i in 0..100 {
+        assert_eq!(frozen.get(&i), Some(&i.to_string()));
+        assert_eq!(frozen.get_key_value(&i), Some((&i, &i.to_string())));
+    }
+    assert!(!frozen.contains_key(&100));
+    let mut keys: Vec<_> = frozen.iter().map(|(&k, _)| k).collect();
+    keys.sort_unstable();
+    assert_eq!(keys, (0..100).collect::<Vec<_>>());
+}
+
+#[test]
+fn freeze_empty() {
+    let map = HashMap::<usize, usize>::new();
+    let frozen = map.pin().freeze();
+    assert!(frozen.is_empty());
+    assert_eq!(frozen.get(&0), None);
+    assert_eq!(frozen.iter().count(), 0);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn freeze_shared_across_threads() {
+    let map = HashMap::<usize, usize>::new();
+    map.pin().bulk_insert((0..1024).map(|i| (i, i * 2)));
+    let frozen = Arc::new(map.pin().freeze());
+    drop(map);
+
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let frozen = frozen.clone();
+            std::thread::spawn(move || {
+                for i in 0..1024 {
+                    assert_eq!(frozen[&i], i * 2);
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+}
+
+#[test]
+fn preallocate() {
+    let map = HashMap::<usize, usize>::new();
+    let guard = map.guard();
+
+    map.preallocate(1000, &guard);
+    let capacity = map.capacity(&guard);
+    assert!(capacity >= 1000);
+    let resizes = map.stats().resize_count;
+
+    // preallocating less than the table already has does nothing
+    map.preallocate(10, &guard);
+    assert_eq!(map.capacity(&guard), capacity);
+
+    for i in 0..1000 {
+        map.insert(i, i, &guard);
+    }
+    assert_eq!(map.capacity(&guard), capacity);
+    assert_eq!(map.stats().resize_count, resizes);
+
+    // growing an existing table keeps its entries
+    map.preallocate(4 * capacity, &guard);
+    assert!(map.capacity(&guard) >= 4 * capacity);
+    assert_eq!(map.len(), 1000);
+    assert!((0..1000).all(|i| map.get(&i, &guard) == Some(&i)));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn concurrent_preallocate() {
+    let map = Arc::new(HashMap::<usize, usize>::new());
+    let barrier = Arc::new(std::sync::Barrier::new(4));
+    let threads: Vec<_> = (0..4)
+        .map(|t| {
+            let map = Arc::clone(&map);
+            let barrier = Arc::clone(&barrier);
+            std::thread::spawn(move || {
+                let guard = map.guard();
+                map.preallocate(4096, &guard);
+                assert!(map.capacity(&guard) >= 4096);
+                let resizes = map.stats().resize_count;
+                // wait until every thread is done preallocating
+                barrier.wait();
+                for i in 0..1024 {
+                    map.insert(t * 1024 + i, i, &guard);
+                }
+                resizes
+            })
+        })
+        .collect();
+    let resizes: Vec<_> = threads.into_iter().map(|t| t.join().unwrap()).collect();
+
+    assert_eq!(map.len(), 4096);
+    // the inserts never outgrew the preallocated table
+    let after = map.stats().resize_count;
+    assert!(resizes.iter().all(|&r| r == after));
+}
+
+#[test]
+fn is_resizing() {
+    let map = HashMap::<usize, usize>::new();
+    let guard = map.guard();
+    assert!(!map.is_resizing(&guard));
+
+    // resizes run to completion on the calling thread, so without other threads, one is never
+    // observed as ongoing
+    for i in 0..1024 {
+        map.insert(i, i, &guard);
+        assert!(!map.is_resizing(&guard));
+    }
+    map.pin().shrink_to_fit();
+    assert!(!map.pin().is_resizing());
+}
+
+#[test]
+fn no_resize_oscillation() {
+    let map = HashMap::<usize, usize>::new();
+    let guard = map.guard();
+
+    // hover right around the resize threshold of the table
+    for i in 0..20 {
+        map.insert(i, i, &guard);
+    }
+    assert_eq!(map.capacity(&guard), 32);
+    let before = map.stats().resize_count;
+    for _ in 0..100 {
+        // crossing the threshold grows the table to 64 bins
+        for i in 20..24 {
+            map.insert(i, i, &guard);
+        }
+        map.shrink_to_fit(&guard);
+        // 20 entries would fit in 32 bins again, but that is too full to shrink to
+        for i in 20..24 {
+            map.remove(&i, &guard);
+        }
+        map.shrink_to_fit(&guard);
+    }
+    // the table grew once, and was never shrunk back
+    assert_eq!(map.stats().resize_count, before + 1);
+}
+
+#[test]
+fn len_and_capacity() {
+    let map = HashMap::<usize, usize>::new();
+    let guard = map.guard();
+    assert_eq!(map.len_and_capacity(&guard), (0, 0));
+    assert_eq!(map.pin().len_and_capacity(), (0, 0));
+
+    for i in 0..1024 {
+        map.insert(i, i, &guard);
+        assert_eq!(map.len_and_capacity(&guard), (i + 1, map.capacity(&guard)));
+    }
+}
+
+#[test]
+fn stats() {
+    let map = HashMap::<usize, usize>::new();
+    let stats = map.stats();
+    assert_eq!(stats.len, 0);
+    assert_eq!(stats.capacity, 0);
+    assert_eq!(stats.resize_count, 0);
+
+    let guard = map.guard();
+    map.insert(0, 0, &guard);
+    // allocating the first table is not a resize
+    assert_eq!(map.stats().resize_count, 0);
+
+    for i in 1..1024 {
+        map.insert(i, i, &guard);
+    }
+    let grown = map.stats();
+    assert_eq!(grown.len, 1024);
+    assert_eq!(grown.capacity, map.capacity(&guard));
+    assert!(grown.resize_count > 0);
+
+    map.clear(&guard);
+    map.shrink_to_fit(&guard);
+    let shrunk = map.stats();
+    assert_eq!(shrunk.len, 0);
+    assert!(shrunk.capacity < grown.capacity);
+    assert_eq!(shrunk.resize_count, grown.resize_count + 1);
+}
+
+#[test]
+fn key_projection() {
+    use std::borrow::Borrow;
+    use std::cmp::Ordering;
+    use std::hash::{Hash, Hasher};
+
+    // a key that hashes and compares by an embedded id only
+    #[derive(Debug, Clone)]
+    struct Record {
+        id: u64,
+        payload: Vec<u8>,
+    }
+
+    impl Hash for Record {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            self.id.hash(state);
+        }
+    }
+    impl PartialEq for Record {
+        fn eq(&self, other: &Self) -> bool {
+            self.id == other.id
+        }
+    }
+    impl Eq for Record {}
+    impl PartialOrd for Record {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+    impl Ord for Record {
+        fn cmp(&self, other: &Self) -> Ordering {
+            self.id.cmp(&other.id)
+        }
+    }
+    impl Borrow<u64> for Record {
+        fn borrow(&self) -> &u64 {
+            &self.id
+        }
+    }
+
+    // every record lands in the same bin, which is turned into a tree bin that is searched by
+    // comparing ids
+    let map = HashMap::<Record, usize, _>::with_hasher(ZeroHashBuilder);
+    let guard = map.guard();
+    for id in 0..100 {
+        let record = Record {
+            id,
+            payload: vec![0; 64],
+        };
+        map.insert(record, id as usize, &guard);
+    }
+
+    let (record, value) = map.get_key_value(&42u64, &guard).unwrap();
+    assert_eq!(record.id, 42);
+    assert_eq!(value, &42);
+    assert_eq!(map.get(&1000u64, &guard), None);
+
+    // a record with the same id replaces the value, but the stored record is kept
+    let record = Record {
+        id: 7,
+        payload: vec![1; 8],
+    };
+    assert_eq!(map.insert(record, 700, &guard), Some(&7));
+    let (record, value) = map.get_key_value(&7u64, &guard).unwrap();
+    assert_eq!(record.payload, vec![0; 64]);
+    assert_eq!(value, &700);
+
+    assert_eq!(map.remove(&42u64, &guard), Some(&42));
+    assert!(!map.contains_key(&42u64, &guard));
+    assert_eq!(map.len(), 99);
+}
+
+#[test]
+fn on_resize() {
+    use std::sync::Mutex;
+
+    let map = HashMap::<usize, usize>::new();
+    let resizes = Arc::new(Mutex::new(Vec::new()));
+    let log = resizes.clone();
+    map.on_resize(move |old, new| log.lock().unwrap().push((old, new)));
+
+    let guard = map.guard();
+    map.insert(0, 0, &guard);
+    // allocating the first table is not a resize
+    assert!(resizes.lock().unwrap().is_empty());
+
+    for i in 1..1024 {
+        map.insert(i, i, &guard);
+    }
+    let grown = resizes.lock().unwrap().clone();
+    assert_eq!(grown.len(), map.stats().resize_count);
+    for window in grown.windows(2) {
+        assert_eq!(window[0].1, window[1].0);
+    }
+    for &(old, new) in &grown {
+        assert_eq!(new, old * 2);
+    }
+    assert_eq!(grown.last().unwrap().1, map.capacity(&guard));
+
+    map.clear(&guard);
+    map.shrink_to_fit(&guard);
+    let (old, new) = *resizes.lock().unwrap().last().unwrap();
+    assert_eq!(old, grown.last().unwrap().1);
+    assert_eq!(new, map.capacity(&guard));
+}
+
+#[test]
+fn on_resize_reentrant() {
+    let map = Arc::new(HashMap::<usize, usize>::new());
+    let inner = Arc::downgrade(&map);
+    // the callback runs outside of any bin lock, so it can modify the map
+    map.on_resize(move |_, new| {
+        if let Some(map) = inner.upgrade() {
+            map.pin().insert(usize::MAX - new, new);
+        }
+    });
+
+    let guard = map.guard();
+    for i in 0..1024 {
+        map.insert(i, i, &guard);
+    }
+    assert!(map.len() > 1024);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn concurrent_on_resize() {
+    let map = Arc::new(HashMap::<usize, usize>::new());
+    let resizes = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let count = resizes.clone();
+    map.on_resize(move |_, _| {
+        count.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    });
+
+    let handles: Vec<_> = (0..4)
+        .map(|t| {
+            let map = map.clone();
+            std::thread::spawn(move || {
+                let guard = map.guard();
+                for i in t * 4096..(t + 1) * 4096 {
+                    map.insert(i, i, &guard);
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    // every completed resize is reported exactly once
+    assert_eq!(
+        resizes.load(std::sync::atomic::Ordering::SeqCst),
+        map.stats().resize_count
+    );
 }
 
 #[test]
@@ -562,11 +3244,38 @@ fn from_iter_empty() {
     use std::iter::FromIterator;
 
     let entries: Vec<(usize, usize)> = Vec::new();
-    let map: HashMap<usize, usize> = HashMap::from_iter(entries.into_iter());
+    let map: HashMap<usize, usize> = HashMap::from_iter(entries);
 
     assert_eq!(map.len(), 0)
 }
 
+#[test]
+fn hash() {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    fn hash_of<T: Hash>(value: &T) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    let a: HashMap<u32, u32> = (0..64).map(|x| (x, x * 2)).collect();
+    let b: HashMap<u32, u32> = (0..64).rev().map(|x| (x, x * 2)).collect();
+    assert_eq!(a, b);
+    assert_eq!(hash_of(&a), hash_of(&b));
+
+    b.pin().insert(0, 1);
+    assert_ne!(hash_of(&a), hash_of(&b));
+
+    // maps can be used as keys of other maps, as long as they are not modified while they are
+    #[allow(clippy::mutable_key_type)]
+    let mut outer = std::collections::HashMap::new();
+    outer.insert(a, "a");
+    let c: HashMap<u32, u32> = (0..64).map(|x| (x, x * 2)).collect();
+    assert_eq!(outer.get(&c), Some(&"a"));
+}
+
 #[test]
 fn retain_empty() {
     let map = HashMap::<&'static str, u32>::new();
@@ -602,6 +3311,63 @@ fn retain_some() {
     assert_eq!(map, expected_map);
 }
 
+#[test]
+fn retain_bounded() {
+    let map: HashMap<u32, u32> = (0..10).map(|x| (x, x)).collect();
+    let guard = map.guard();
+    let mut calls = 0;
+    map.retain_bounded(
+        3,
+        |_, _| {
+            calls += 1;
+            false
+        },
+        &guard,
+    );
+    assert_eq!(calls, 3);
+    assert_eq!(map.len(), 7);
+
+    // a limit that is never reached behaves like retain
+    map.retain_bounded(100, |_, v| *v % 2 == 0, &guard);
+    assert!(map.iter(&guard).all(|(_, v)| *v % 2 == 0));
+
+    map.retain_bounded(0, |_, _| unreachable!(), &guard);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn concurrent_resize_and_retain_bounded() {
+    let map = Arc::new(HashMap::<usize, usize>::new());
+    {
+        let guard = map.guard();
+        for i in 0..1024 {
+            map.insert(i, i, &guard);
+        }
+    }
+
+    let map1 = map.clone();
+    let t1 = std::thread::spawn(move || {
+        let guard = map1.guard();
+        for i in 1024..4096 {
+            map1.insert(i, i, &guard);
+        }
+    });
+    let map2 = map.clone();
+    let t2 = std::thread::spawn(move || {
+        let guard = map2.guard();
+        map2.retain_bounded(512, |k, _| *k >= 1024, &guard);
+    });
+
+    t1.join().unwrap();
+    t2.join().unwrap();
+
+    let guard = map.guard();
+    assert_eq!(map.len(), 4096 - 512);
+    for i in 1024..4096 {
+        assert_eq!(map.get(&i, &guard), Some(&i));
+    }
+}
+
 #[test]
 fn retain_force_empty() {
     let map = HashMap::<&'static str, u32>::new();
@@ -71,6 +71,149 @@ fn insert_and_remove() {
     assert!(map.get(&42).is_none());
 }
 
+#[test]
+fn remove_many() {
+    let map = HashMap::<usize, usize>::new();
+    let map = map.pin();
+    map.bulk_insert((0..10).map(|i| (i, i)));
+    assert_eq!(map.remove_many(&[0, 1, 1, 10]), 2);
+    assert_eq!(map.len(), 8);
+}
+
+#[test]
+fn update_all() {
+    let map = HashMap::<usize, usize>::new();
+    let map = map.pin();
+    map.bulk_insert((0..10).map(|i| (i, i)));
+    map.update_all(|&k, &v| if k < 5 { None } else { Some(v * 10) });
+    assert_eq!(map.len(), 5);
+    assert_eq!(map.get(&9), Some(&90));
+}
+
+#[test]
+fn take_if() {
+    let map = HashMap::<usize, String>::new();
+    let map = map.pin();
+    map.insert(42, String::from("a"));
+    assert_eq!(map.take_if(&42, |_, v| v.is_empty()), None);
+    assert_eq!(
+        map.take_if(&42, |_, v| !v.is_empty()),
+        Some(String::from("a"))
+    );
+    assert!(map.is_empty());
+}
+
+#[test]
+fn insert_owned() {
+    let map = HashMap::<usize, String>::new();
+    let map = map.pin();
+    assert_eq!(map.insert_owned(42, String::from("a")), None);
+    assert_eq!(
+        map.insert_owned(42, String::from("b")),
+        Some(String::from("a"))
+    );
+    assert_eq!(map.get(&42), Some(&String::from("b")));
+}
+
+#[test]
+fn remove_owned() {
+    let map = HashMap::<usize, String>::new();
+    let map = map.pin();
+    map.insert(42, String::from("a"));
+    assert_eq!(map.remove_owned(&42), Some(String::from("a")));
+    assert_eq!(map.remove_owned(&42), None);
+    assert!(map.is_empty());
+}
+
+#[test]
+fn debug_iter_sorted() {
+    let map = HashMap::<usize, usize>::new();
+    let map = map.pin();
+    assert!(map.debug_iter_sorted().is_empty());
+    for i in (0..64).rev() {
+        map.insert(i, i + 1);
+    }
+    let sorted = map.debug_iter_sorted();
+    assert_eq!(sorted.len(), 64);
+    for (i, (k, v)) in sorted.into_iter().enumerate() {
+        assert_eq!((*k, *v), (i, i + 1));
+    }
+}
+
+#[test]
+fn update_value() {
+    let map = HashMap::<usize, usize>::new();
+    let map = map.pin();
+    assert_eq!(map.update(&42, |v| v + 1), None);
+    map.insert(42, 0);
+    assert_eq!(map.update(&42, |v| v + 1), Some(&1));
+}
+
+#[test]
+#[should_panic(expected = "no entry found for key")]
+fn index_missing() {
+    let map = HashMap::<usize, usize>::new();
+    let map = map.pin();
+    map.insert(42, 0);
+    let _ = map[&43];
+}
+
+#[test]
+fn guard() {
+    let map = HashMap::<usize, usize>::new();
+
+    // a pinned reference hands out the guard it created
+    let mref = map.pin();
+    mref.insert(42, 0);
+    assert_eq!(map.get(&42, mref.guard()), Some(&0));
+
+    // a reference made with `with_guard` hands out the guard it was given
+    let guard = map.guard();
+    let mref = map.with_guard(&guard);
+    map.insert(43, 1, mref.guard());
+    assert_eq!(mref.get(&43), Some(&1));
+}
+
+#[test]
+fn shared_collector() {
+    let collector = Arc::new(Collector::new());
+    let a = HashMap::<usize, usize>::new().with_shared_collector(Arc::clone(&collector));
+    let b = HashMap::<usize, &str>::new().with_shared_collector(Arc::clone(&collector));
+
+    let guard = collector.enter();
+    let aref = a.with_guard(&guard);
+    let bref = b.with_guard(&guard);
+    aref.insert(1, 10);
+    bref.insert(10, "ten");
+    assert_eq!(bref.get(aref.get(&1).unwrap()), Some(&"ten"));
+
+    // each map still hands out guards that the other map accepts
+    let other = b.guard();
+    assert_eq!(a.get(&1, &other), Some(&10));
+}
+
+#[test]
+fn any_entry() {
+    let map = HashMap::<usize, usize>::new();
+    let map = map.pin();
+    assert_eq!(map.any_entry(), None);
+    map.insert(42, 0);
+    assert_eq!(map.any_entry(), Some((&42, &0)));
+}
+
+#[test]
+fn pinned_map_alias() {
+    fn total(map: &PinnedMap<'_, usize, usize>) -> usize {
+        map.values().sum()
+    }
+
+    let map = HashMap::<usize, usize>::new();
+    let pinned: PinnedMap<'_, usize, usize> = map.pin();
+    pinned.insert(1, 10);
+    pinned.insert(2, 20);
+    assert_eq!(total(&pinned), 30);
+}
+
 #[test]
 fn insert_and_get() {
     let map = HashMap::<usize, usize>::new();
@@ -94,6 +237,14 @@ fn insert_and_get_key_value() {
     }
 }
 
+#[test]
+fn get_many() {
+    let map = HashMap::<usize, usize>::new();
+    let map = map.pin();
+    map.insert(1, 10);
+    assert_eq!(map.get_many([&1, &2]), [Some(&10), None]);
+}
+
 mod hasher;
 use hasher::ZeroHashBuilder;
 
@@ -200,6 +351,78 @@ fn compute_if_present_remove() {
     }
 }
 
+#[test]
+fn compute_if_absent() {
+    let map = HashMap::<usize, usize>::new();
+
+    let map1 = map.pin();
+    assert_eq!(map1.compute_if_absent(42, |k| k + 1), &43);
+    assert_eq!(
+        map1.compute_if_absent(42, |_| panic!("key is present")),
+        &43
+    );
+    {
+        let map2 = map.pin();
+        let e = map2.get(&42).unwrap();
+        assert_eq!(e, &43);
+    }
+}
+
+#[test]
+fn compute() {
+    let map = HashMap::<usize, usize>::new();
+
+    let map1 = map.pin();
+    assert_eq!(
+        map1.compute(42, |_, v| Some(v.map_or(0, |v| v + 1))),
+        Some(&0)
+    );
+    assert_eq!(
+        map1.compute(42, |_, v| Some(v.map_or(0, |v| v + 1))),
+        Some(&1)
+    );
+    assert_eq!(map1.compute(42, |_, _| None), None);
+    {
+        let map2 = map.pin();
+        assert!(map2.get(&42).is_none());
+    }
+}
+
+#[test]
+fn merge() {
+    let map = HashMap::<usize, usize>::new();
+
+    let map1 = map.pin();
+    assert_eq!(map1.merge(42, 1, |old, new| Some(old + new)), Some(&1));
+    assert_eq!(map1.merge(42, 2, |old, new| Some(old + new)), Some(&3));
+    {
+        let map2 = map.pin();
+        assert_eq!(map2.get(&42), Some(&3));
+    }
+}
+
+#[test]
+fn entry_or_insert_with() {
+    let map = HashMap::<usize, Vec<usize>>::new();
+
+    let map1 = map.pin();
+    assert_eq!(map1.entry(42).or_insert_with(|| vec![1]), &vec![1]);
+    {
+        let map2 = map.pin();
+        assert_eq!(map2.get(&42), Some(&vec![1]));
+    }
+}
+
+#[test]
+fn entry_and_modify() {
+    let map = HashMap::<usize, usize>::new();
+
+    let map1 = map.pin();
+    map1.insert(42, 0);
+    let v = map1.entry(42).and_modify(|_, v| v + 1).or_insert(0);
+    assert_eq!(v, &1);
+}
+
 #[test]
 fn empty_maps_equal() {
     let map1 = HashMap::<usize, usize>::new();
@@ -334,6 +557,39 @@ fn debug() {
     assert!(formatted == "{42: 0, 16: 8}" || formatted == "{16: 8, 42: 0}");
 }
 
+#[test]
+fn extend() {
+    let map: HashMap<usize, usize> = HashMap::new();
+    let map1 = map.pin();
+
+    let mut entries: Vec<(usize, usize)> = vec![(42, 0), (16, 6), (38, 42)];
+    entries.sort_unstable();
+
+    (&map1).extend(entries.clone());
+
+    let mut collected: Vec<(usize, usize)> =
+        map1.iter().map(|(key, value)| (*key, *value)).collect();
+    collected.sort_unstable();
+
+    assert_eq!(entries, collected);
+}
+
+#[test]
+fn extend_ref() {
+    let map: HashMap<usize, usize> = HashMap::new();
+    let map1 = map.pin();
+
+    let mut entries: Vec<(&usize, &usize)> = vec![(&42, &0), (&16, &6), (&38, &42)];
+    entries.sort();
+
+    (&map1).extend(entries.clone());
+
+    let mut collected: Vec<(&usize, &usize)> = map1.iter().collect();
+    collected.sort();
+
+    assert_eq!(entries, collected);
+}
+
 #[test]
 fn from_iter_ref() {
     use std::iter::FromIterator;
@@ -341,12 +597,12 @@ fn from_iter_ref() {
     let mut entries: Vec<(&usize, &usize)> = vec![(&42, &0), (&16, &6), (&38, &42)];
     entries.sort();
 
-    let map: HashMap<usize, usize> = HashMap::from_iter(entries.clone().into_iter());
+    let map: HashMap<usize, usize> = HashMap::from_iter(entries.clone());
     let map = map.pin();
     let mut collected: Vec<(&usize, &usize)> = map.iter().collect();
     collected.sort();
 
-    assert_eq!(entries, entries)
+    assert_eq!(entries, collected)
 }
 
 #[test]
@@ -354,7 +610,7 @@ fn from_iter_empty() {
     use std::iter::FromIterator;
 
     let entries: Vec<(usize, usize)> = Vec::new();
-    let map: HashMap<usize, usize> = HashMap::from_iter(entries.into_iter());
+    let map: HashMap<usize, usize> = HashMap::from_iter(entries);
     let map = map.pin();
     assert_eq!(map.len(), 0)
 }
@@ -369,7 +625,7 @@ fn retain_empty() {
 
 #[test]
 fn retain_all_false() {
-    let map: HashMap<u32, u32> = (0..10 as u32).map(|x| (x, x)).collect();
+    let map: HashMap<u32, u32> = (0..10_u32).map(|x| (x, x)).collect();
     let map = map.pin();
     map.retain(|_, _| false);
     assert_eq!(map.len(), 0);
@@ -413,6 +669,102 @@ fn retain_force_some() {
     assert_eq!(map, expected_map);
 }
 
+#[test]
+fn try_insert_alloc() {
+    let map = HashMap::<usize, usize>::new();
+    let map = map.pin();
+    assert_eq!(map.try_insert_alloc(42, 0), Ok(None));
+    assert_eq!(map.try_insert_alloc(42, 1), Ok(Some(&0)));
+
+    // inserts that grow the table succeed as long as memory is available
+    let capacity = map.capacity();
+    for i in 0..1024 {
+        assert!(map.try_insert_alloc(i, i).is_ok());
+    }
+    assert!(map.capacity() > capacity);
+    assert_eq!(map.len(), 1024);
+}
+
+#[test]
+fn try_reserve() {
+    let map = HashMap::<usize, usize>::new();
+    let map = map.pin();
+    assert_eq!(map.try_reserve(100), Ok(()));
+    assert!(map.capacity() >= 100);
+    assert_eq!(
+        map.try_reserve(usize::MAX),
+        Err(flurry::TryReserveError::CapacityOverflow)
+    );
+}
+
+#[test]
+fn pin_arc() {
+    struct Holder {
+        map: flurry::OwnedHashMapRef<usize, String>,
+    }
+
+    let map = std::sync::Arc::new(HashMap::<usize, String>::new());
+    let holder = Holder { map: map.pin_arc() };
+    holder.map.map_ref().insert(1, String::from("a"));
+    let a = holder.map.map_ref().get(&1).unwrap().clone();
+    assert_eq!(a, "a");
+    assert_eq!(holder.map.map().get(&1, holder.map.guard()), Some(&a));
+
+    // the handle keeps the map alive on its own
+    drop(map);
+    assert_eq!(holder.map.map_ref().len(), 1);
+    drop(holder);
+}
+
+#[test]
+fn get_or_insert() {
+    let map = HashMap::<usize, usize>::new();
+    let map = map.pin();
+    assert_eq!(map.get_or_insert(1, 42), &42);
+    assert_eq!(map.get_or_insert(1, 0), &42);
+    assert_eq!(map.get_or_insert_with(2, || 84), &84);
+    assert_eq!(map.get_or_insert_with(2, || unreachable!()), &84);
+    assert_eq!(map.get_or_insert_with_key(3, |k| k * 2), &6);
+    assert_eq!(map.get_or_insert_with_key(3, |_| unreachable!()), &6);
+}
+
+#[test]
+fn bulk_insert() {
+    let map = HashMap::<usize, usize>::new();
+    let map = map.pin();
+    map.bulk_insert((0..100).map(|i| (i, i + 1)));
+    assert_eq!(map.len(), 100);
+    assert_eq!(map.get(&99), Some(&100));
+}
+
+#[test]
+fn stats() {
+    let map = HashMap::<usize, usize>::new();
+    let map = map.pin();
+    map.insert(1, 1);
+    let stats = map.stats();
+    assert_eq!(stats.len, 1);
+    assert_eq!(stats.capacity, map.capacity());
+}
+
+#[test]
+fn shrink_to_fit() {
+    let map = HashMap::<usize, usize>::with_capacity(1024);
+    let map = map.pin();
+    for i in 0..10 {
+        map.insert(i, i);
+    }
+
+    assert!(map.capacity() >= 1024);
+    map.shrink_to_fit();
+    assert!(map.capacity() < 1024);
+    map.shrink_to(100);
+    assert_eq!(map.len(), 10);
+    for i in 0..10 {
+        assert_eq!(map.get(&i), Some(&i));
+    }
+}
+
 #[test]
 #[cfg_attr(miri, ignore)]
 fn concurrent_insert() {
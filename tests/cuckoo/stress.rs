@@ -50,8 +50,8 @@ impl Environment {
             vals1: Mutex::new(vec![0usize; NUM_KEYS]),
             vals2: Mutex::new(vec![0usize; NUM_KEYS]),
             ind_dist: Uniform::from(0..NUM_KEYS - 1),
-            val_dist1: Uniform::from(Value::min_value()..Value::max_value()),
-            val_dist2: Uniform::from(Value::min_value()..Value::max_value()),
+            val_dist1: Uniform::from(Value::MIN..Value::MAX),
+            val_dist2: Uniform::from(Value::MIN..Value::MAX),
             in_table: Mutex::new(vec![false; NUM_KEYS]),
             in_use: Mutex::new(in_use),
             finished: AtomicBool::new(false),
@@ -151,13 +151,13 @@ fn stress_find_thread(env: Arc<Environment>) {
             let val2 = (*env.vals2.lock())[idx];
 
             let value = env.table1.get(&key, &guard1);
-            if value.is_some() {
-                assert_eq!(&val1, value.unwrap());
+            if let Some(value) = value {
+                assert_eq!(&val1, value);
                 assert!((*in_table)[idx]);
             }
             let value = env.table2.get(&key, &guard2);
-            if value.is_some() {
-                assert_eq!(&val2, value.unwrap());
+            if let Some(value) = value {
+                assert_eq!(&val2, value);
                 assert!((*in_table)[idx]);
             }
             (*in_use)[idx].swap(false, Ordering::SeqCst);
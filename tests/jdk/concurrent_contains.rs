@@ -17,9 +17,9 @@ fn test_concurrent_contains_key() {
     let mut content = [0; NUM_ENTRIES];
     {
         let guard = map.guard();
-        for k in 0..NUM_ENTRIES {
+        for (k, c) in content.iter_mut().enumerate() {
             map.insert(k, k, &guard);
-            content[k] = k;
+            *c = k;
         }
     }
     test(content, Arc::new(map));
@@ -35,7 +35,6 @@ fn test_once(content: [usize; NUM_ENTRIES], map: Arc<HashMap<usize, usize>>) {
     let mut threads = Vec::new();
     for _ in 0..num_cpus::get().min(8) {
         let map = map.clone();
-        let content = content;
         let handle = thread::spawn(move || {
             let guard = map.guard();
             let map = map.clone();
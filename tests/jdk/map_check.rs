@@ -53,8 +53,8 @@ where
 {
     let mut sum = 0;
     let guard = map.guard();
-    for i in 0..keys.len() {
-        if map.insert(keys[i], 0, &guard).is_none() {
+    for &key in keys {
+        if map.insert(key, 0, &guard).is_none() {
             sum += 1;
         }
     }
@@ -67,8 +67,8 @@ where
 {
     let mut sum = 0;
     let guard = map.guard();
-    for i in 0..keys.len() {
-        if map.contains_key(&keys[i], &guard) {
+    for key in keys {
+        if map.contains_key(key, &guard) {
             sum += 1;
         }
     }
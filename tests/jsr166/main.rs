@@ -1,7 +1,7 @@
 use flurry::*;
 use std::iter::FromIterator;
 
-const ITER: [(usize, &'static str); 5] = [(1, "A"), (2, "B"), (3, "C"), (4, "D"), (5, "E")];
+const ITER: [(usize, &str); 5] = [(1, "A"), (2, "B"), (3, "C"), (4, "D"), (5, "E")];
 
 #[test]
 fn test_from_iter() {
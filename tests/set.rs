@@ -6,6 +6,20 @@ fn new() {
     let _set = HashSet::<usize>::new();
 }
 
+#[test]
+fn pinned_set_alias() {
+    fn has_all(set: &flurry::PinnedSet<'_, usize>, values: &[usize]) -> bool {
+        values.iter().all(|v| set.contains(v))
+    }
+
+    let set = HashSet::<usize>::new();
+    let pinned = set.pin();
+    pinned.insert(1);
+    pinned.insert(2);
+    assert!(has_all(&pinned, &[1, 2]));
+    assert!(!has_all(&pinned, &[1, 3]));
+}
+
 #[test]
 fn insert() {
     let set = HashSet::new();
@@ -213,6 +227,18 @@ fn different_values_not_equal() {
     assert_ne!(set2, set1);
 }
 
+#[test]
+fn guarded_eq() {
+    let set1 = HashSet::<usize>::new();
+    let set2 = HashSet::<usize>::new();
+    let guard1 = set1.guard();
+    let guard2 = set2.guard();
+    set1.insert(1, &guard1);
+    assert!(!set1.guarded_eq(&set2, &guard1, &guard2));
+    set2.insert(1, &guard2);
+    assert!(set1.guarded_eq(&set2, &guard1, &guard2));
+}
+
 #[test]
 fn clone_set_empty() {
     let set = HashSet::<&'static str>::new();
@@ -222,6 +248,24 @@ fn clone_set_empty() {
     assert_eq!(cloned_set.len(), 0);
 }
 
+#[test]
+fn clone_set_ref() {
+    let set = HashSet::<usize>::new();
+    let pinned = set.pin();
+    pinned.insert(1);
+
+    // the clone refers to the same set, so it sees later inserts
+    let cloned = pinned.clone();
+    pinned.insert(2);
+    assert!(cloned.contains(&2));
+    assert_eq!(cloned.len(), 2);
+
+    // and it keeps working once the original reference is gone
+    drop(pinned);
+    cloned.insert(3);
+    assert_eq!(set.len(), 3);
+}
+
 #[test]
 // Test that same values exists in both maps (original and cloned)
 fn clone_set_filled() {
@@ -269,7 +313,7 @@ fn extend() {
     let mut entries = vec![42, 16, 38];
     entries.sort_unstable();
 
-    (&set).extend(entries.clone().into_iter());
+    (&set).extend(entries.clone());
 
     let mut collected: Vec<_> = set.iter(&guard).copied().collect();
     collected.sort_unstable();
@@ -284,7 +328,7 @@ fn extend_ref() {
     let mut entries = vec![&42, &16, &38];
     entries.sort();
 
-    (&set).extend(entries.clone().into_iter());
+    (&set).extend(entries.clone());
 
     let guard = set.guard();
     let mut collected: Vec<_> = set.iter(&guard).collect();
@@ -293,6 +337,19 @@ fn extend_ref() {
     assert_eq!(entries, collected);
 }
 
+#[test]
+fn extend_pinned() {
+    let set: HashSet<usize> = HashSet::new();
+    let pinned = set.pin();
+
+    (&pinned).extend(vec![42, 16]);
+    (&pinned).extend(vec![&16, &38]);
+
+    let mut collected: Vec<_> = pinned.iter().copied().collect();
+    collected.sort_unstable();
+    assert_eq!(collected, [16, 38, 42]);
+}
+
 #[test]
 fn from_iter_ref() {
     use std::iter::FromIterator;
@@ -300,13 +357,13 @@ fn from_iter_ref() {
     let mut entries: Vec<_> = vec![&42, &16, &38];
     entries.sort();
 
-    let set: HashSet<usize> = HashSet::from_iter(entries.clone().into_iter());
+    let set: HashSet<usize> = HashSet::from_iter(entries.clone());
 
     let guard = set.guard();
     let mut collected: Vec<_> = set.iter(&guard).collect();
     collected.sort();
 
-    assert_eq!(entries, entries)
+    assert_eq!(entries, collected)
 }
 
 #[test]
@@ -317,3 +374,334 @@ fn from_iter_empty() {
 
     assert_eq!(set.len(), 0)
 }
+
+#[test]
+fn set_operations() {
+    let a: HashSet<usize> = (0..10).collect();
+    let b: HashSet<usize> = (5..20).collect();
+    let (a_guard, b_guard) = (a.guard(), b.guard());
+
+    let sorted = |iter: &mut dyn Iterator<Item = &usize>| {
+        let mut values: Vec<usize> = iter.copied().collect();
+        values.sort_unstable();
+        values
+    };
+
+    assert_eq!(
+        sorted(&mut a.difference(&b, &a_guard, &b_guard)),
+        (0..5).collect::<Vec<_>>()
+    );
+    assert_eq!(
+        sorted(&mut b.difference(&a, &b_guard, &a_guard)),
+        (10..20).collect::<Vec<_>>()
+    );
+    assert_eq!(
+        sorted(&mut a.symmetric_difference(&b, &a_guard, &b_guard)),
+        (0..5).chain(10..20).collect::<Vec<_>>()
+    );
+    assert_eq!(
+        sorted(&mut a.intersection(&b, &a_guard, &b_guard)),
+        (5..10).collect::<Vec<_>>()
+    );
+    assert_eq!(
+        sorted(&mut b.intersection(&a, &b_guard, &a_guard)),
+        (5..10).collect::<Vec<_>>()
+    );
+    assert_eq!(
+        sorted(&mut a.union(&b, &a_guard, &b_guard)),
+        (0..20).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn set_operations_ref() {
+    let a: HashSet<usize> = (0..3).collect();
+    let b: HashSet<usize> = HashSet::new();
+    let (a, b) = (a.pin(), b.pin());
+
+    assert_eq!(a.difference(&b).count(), 3);
+    assert_eq!(a.symmetric_difference(&b).count(), 3);
+    assert_eq!(a.intersection(&b).count(), 0);
+    assert_eq!(b.union(&a).count(), 3);
+}
+
+#[test]
+fn set_predicates() {
+    let a: HashSet<usize> = (0..10).collect();
+    let b: HashSet<usize> = (5..20).collect();
+    let c: HashSet<usize> = (5..8).collect();
+    let (a_guard, b_guard, c_guard) = (a.guard(), b.guard(), c.guard());
+
+    assert!(!a.is_disjoint(&b, &a_guard, &b_guard));
+    assert!(!b.is_disjoint(&a, &b_guard, &a_guard));
+    assert!(c.is_subset(&a, &c_guard, &a_guard));
+    assert!(c.is_subset(&b, &c_guard, &b_guard));
+    assert!(!a.is_subset(&b, &a_guard, &b_guard));
+    assert!(a.is_superset(&c, &a_guard, &c_guard));
+    assert!(!c.is_superset(&a, &c_guard, &a_guard));
+
+    a.remove(&5, &a_guard);
+    a.remove(&6, &a_guard);
+    a.remove(&7, &a_guard);
+    assert!(a.is_disjoint(&c, &a_guard, &c_guard));
+    assert!(c.is_disjoint(&a, &c_guard, &a_guard));
+    assert!(!c.is_subset(&a, &c_guard, &a_guard));
+}
+
+#[derive(Debug, Clone)]
+struct Tagged {
+    id: usize,
+    tag: usize,
+}
+
+impl PartialEq for Tagged {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for Tagged {}
+
+impl PartialOrd for Tagged {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Tagged {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.id.cmp(&other.id)
+    }
+}
+
+impl std::hash::Hash for Tagged {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+#[test]
+fn replace() {
+    let set = HashSet::new();
+    let guard = set.guard();
+
+    assert_eq!(set.replace(Tagged { id: 1, tag: 0 }, &guard), None);
+    let old = set.replace(Tagged { id: 1, tag: 1 }, &guard).unwrap();
+    assert_eq!(old.tag, 0);
+    assert_eq!(set.len(), 1);
+
+    let current = set.get(&Tagged { id: 1, tag: 42 }, &guard).unwrap();
+    assert_eq!(current.tag, 1);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn concurrent_replace() {
+    const NUM_KEYS: usize = 64;
+    let set = Arc::new(HashSet::new());
+    for id in 0..NUM_KEYS {
+        set.pin().insert(Tagged { id, tag: 0 });
+    }
+
+    let writers: Vec<_> = (1..=4)
+        .map(|tag| {
+            let set = set.clone();
+            std::thread::spawn(move || {
+                let guard = set.guard();
+                for id in 0..NUM_KEYS {
+                    assert!(set.replace(Tagged { id, tag }, &guard).is_some());
+                }
+            })
+        })
+        .collect();
+    let reader = {
+        let set = set.clone();
+        std::thread::spawn(move || {
+            for _ in 0..16 {
+                let guard = set.guard();
+                for id in 0..NUM_KEYS {
+                    // the element must never go missing while it is being replaced
+                    assert!(set.contains(&Tagged { id, tag: 0 }, &guard));
+                }
+            }
+        })
+    };
+
+    for writer in writers {
+        writer.join().unwrap();
+    }
+    reader.join().unwrap();
+    assert_eq!(set.len(), NUM_KEYS);
+}
+
+#[test]
+fn get_and_take_borrowed() {
+    let set = HashSet::<String>::new();
+    let guard = set.guard();
+    set.insert(String::from("interned"), &guard);
+
+    // the lookup borrows the stored value rather than the probe
+    let stored = set.get("interned", &guard).unwrap();
+    assert_eq!(stored, "interned");
+    assert!(std::ptr::eq(stored, set.get("interned", &guard).unwrap()));
+    assert_eq!(set.get("missing", &guard), None);
+
+    let taken = set.take("interned", &guard).unwrap();
+    assert!(std::ptr::eq(stored, taken));
+    assert!(!set.contains("interned", &guard));
+    assert_eq!(set.take("interned", &guard), None);
+}
+
+#[test]
+fn with_capacity_and_hasher() {
+    use std::collections::hash_map::RandomState;
+
+    let set = HashSet::<usize, _>::with_capacity_and_hasher(1000, RandomState::new());
+    let guard = set.guard();
+    for i in 0..1000 {
+        assert!(set.insert(i, &guard));
+    }
+    assert_eq!(set.len(), 1000);
+    assert!(set.contains(&42, &guard));
+}
+
+#[test]
+fn hasher() {
+    use std::collections::hash_map::RandomState;
+    use std::hash::{BuildHasher, Hash, Hasher};
+
+    let s = RandomState::new();
+    let set = HashSet::<usize, _>::with_hasher(s.clone());
+
+    let hash = |s: &RandomState| {
+        let mut hasher = s.build_hasher();
+        42usize.hash(&mut hasher);
+        hasher.finish()
+    };
+    assert_eq!(hash(set.hasher()), hash(&s));
+    assert_eq!(hash(set.pin().hasher()), hash(&s));
+}
+
+#[test]
+fn builder() {
+    use std::collections::hash_map::RandomState;
+
+    let set: HashSet<usize, _> = HashSet::builder()
+        .capacity(1000)
+        .load_factor(0.9)
+        .hasher(RandomState::new())
+        .build();
+    let guard = set.guard();
+    for i in 0..1000 {
+        assert!(set.insert(i, &guard));
+    }
+    assert_eq!(set.len(), 1000);
+    assert!(set.contains(&42, &guard));
+}
+
+#[test]
+fn try_reserve() {
+    let set = HashSet::<usize>::new();
+    let set = set.pin();
+    assert_eq!(set.try_reserve(100), Ok(()));
+    assert_eq!(
+        set.try_reserve(usize::MAX),
+        Err(flurry::TryReserveError::CapacityOverflow)
+    );
+}
+
+#[test]
+fn retain() {
+    let set: HashSet<usize> = (0..100).collect();
+    let guard = set.guard();
+
+    set.retain(|&e| e % 3 == 0, &guard);
+    assert_eq!(set.len(), 34);
+    assert!((0..100).all(|e| set.contains(&e, &guard) == (e % 3 == 0)));
+
+    set.pin().retain(|_| false);
+    assert!(set.is_empty());
+}
+
+#[test]
+fn retain_force() {
+    let set: HashSet<usize> = (0..100).collect();
+    let guard = set.guard();
+
+    let mut visited = 0;
+    set.retain_force(
+        |&e| {
+            visited += 1;
+            // re-inserting the element would make `retain` keep it
+            set.insert(e, &guard);
+            e % 3 == 0
+        },
+        &guard,
+    );
+    assert_eq!(visited, 100);
+    assert_eq!(set.len(), 34);
+    assert!((0..100).all(|e| set.contains(&e, &guard) == (e % 3 == 0)));
+
+    set.pin().retain_force(|_| false);
+    assert!(set.is_empty());
+}
+
+#[test]
+fn default_generic() {
+    use std::hash::BuildHasher;
+
+    fn empty<T, S: BuildHasher + Default>() -> HashSet<T, S> {
+        HashSet::default()
+    }
+
+    let set: HashSet<usize> = empty();
+    assert!(set.pin().is_empty());
+    let set: HashSet<usize, std::collections::hash_map::RandomState> = empty();
+    assert!(set.pin().insert(1));
+    assert!(!set.pin().insert(1));
+}
+
+#[test]
+fn into_iter() {
+    let set = HashSet::new();
+    for i in 0..100 {
+        set.pin().insert(vec![i]);
+    }
+    // the values are moved out, not cloned
+    let buffers: Vec<*const usize> = set.pin().iter().map(|v| v.as_ptr()).collect();
+
+    let mut values: Vec<Vec<usize>> = set.into_iter().collect();
+    assert!(values.iter().all(|v| buffers.contains(&v.as_ptr())));
+    values.sort();
+    assert_eq!(values, (0..100).map(|i| vec![i]).collect::<Vec<_>>());
+
+    let empty = HashSet::<usize>::new();
+    assert_eq!(empty.into_iter().count(), 0);
+}
+
+#[test]
+fn drain() {
+    let set = HashSet::new();
+    let guard = set.guard();
+    for i in 0..100 {
+        set.insert(i, &guard);
+    }
+
+    let mut drained: Vec<usize> = set.drain(&guard).copied().collect();
+    drained.sort_unstable();
+    assert_eq!(drained, (0..100).collect::<Vec<_>>());
+    assert!(set.is_empty());
+
+    set.insert(1, &guard);
+    assert_eq!(set.pin().drain().collect::<Vec<_>>(), [&1]);
+    assert!(set.is_empty());
+}
+
+#[test]
+fn count_if() {
+    let set: HashSet<usize> = (0..100).collect();
+    let guard = set.guard();
+    assert_eq!(set.count_if(|e| e % 10 == 0, &guard), 10);
+    assert_eq!(set.count_if(|_| false, &guard), 0);
+    assert_eq!(set.pin().count_if(|&e| e < 50), 50);
+}